@@ -36,3 +36,346 @@ impl UnexpectedEOFError {
         }
     }
 }
+
+/// Represents an error raised by a running TI-BASIC program.
+///
+/// These mirror the `ERR:` screens shown by the calculator, so the [`fmt::Display`]
+/// implementation produces the same text that the calculator would show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TIError {
+    /// A token appeared somewhere it isn't allowed, such as `Then` without an `If`.
+    Syntax,
+    /// A value of the wrong type was used, such as adding a string to a number.
+    DataType,
+    /// A function was given the wrong number of arguments.
+    Argument,
+    /// A value is outside of the domain of a function.
+    Domain,
+    /// A division by zero was attempted.
+    DivideBy0,
+    /// A result is too large to be represented.
+    Overflow,
+    /// An index or dimension is outside of the size of a list or matrix, such as `l1(0)`.
+    InvalidDim,
+    /// Two lists or matrices don't have matching dimensions, such as when adding them.
+    DimMismatch,
+    /// A variable was used before being defined.
+    Undefined,
+    /// `Xmin` isn't less than `Xmax`, or `Ymin` isn't less than `Ymax`, when drawing.
+    WindowRange,
+    /// A `Goto` targeted a label that doesn't exist.
+    Label(String),
+    /// The program was interrupted.
+    Break,
+    /// The token is valid, but the interpreter doesn't support it yet.
+    Unsupported(String),
+}
+
+impl fmt::Display for TIError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TIError::Syntax => write!(f, "ERR:SYNTAX"),
+            TIError::DataType => write!(f, "ERR:DATA TYPE"),
+            TIError::Argument => write!(f, "ERR:ARGUMENT"),
+            TIError::Domain => write!(f, "ERR:DOMAIN"),
+            TIError::DivideBy0 => write!(f, "ERR:DIVIDE BY 0"),
+            TIError::Overflow => write!(f, "ERR:OVERFLOW"),
+            TIError::InvalidDim => write!(f, "ERR:INVALID DIM"),
+            TIError::DimMismatch => write!(f, "ERR:DIM MISMATCH"),
+            TIError::Undefined => write!(f, "ERR:UNDEFINED"),
+            TIError::WindowRange => write!(f, "ERR:WINDOW RANGE"),
+            TIError::Label(name) => write!(f, "ERR:LABEL ({})", name),
+            TIError::Break => write!(f, "ERR:BREAK"),
+            TIError::Unsupported(token) => write!(f, "Unsupported token: {}", token),
+        }
+    }
+}
+
+impl Error for TIError {}
+
+/// Represents an error found while parsing TI-BASIC bytecode into statements and expressions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The byte offset in the data section where the error was found
+    pub offset: usize,
+    /// A description of what went wrong
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Parse error at byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+impl ParseError {
+    /// Creates a new instance of `ParseError`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tio2::errors::ParseError;
+    ///
+    /// let error = ParseError::new(4, "Expected `,`");
+    /// assert_eq!(error.to_string(), "Parse error at byte 4: Expected `,`");
+    /// ```
+    pub fn new(offset: usize, message: impl ToString) -> Self {
+        Self {
+            offset,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Represents a problem found while validating the contents of an 8XP file.
+///
+/// See `8xp_file_structure.md` for a description of each of the fields that are checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileError {
+    /// The file is too short to contain a header and footer.
+    TooShort {
+        /// The length of the file
+        length: usize,
+    },
+    /// The file doesn't start with `**TI83F*\x1a\n`.
+    BadSignature {
+        /// The first bytes of the file
+        found: Vec<u8>,
+    },
+    /// A size field in the header doesn't match the length of the data section.
+    LengthMismatch {
+        /// The name of the field
+        field: &'static str,
+        /// The offset of the field in the header
+        offset: usize,
+        /// The value that the field should have, based on the length of the data section
+        expected: u16,
+        /// The value stored in the field
+        actual: u16,
+    },
+    /// A size is too large to be stored in its two byte size field.
+    TooLarge {
+        /// The name of the field
+        field: &'static str,
+        /// The offset of the field in the header
+        offset: usize,
+        /// The size that the field should describe
+        size: usize,
+    },
+    /// The checksum in the footer doesn't match the contents of the file.
+    ChecksumMismatch {
+        /// The checksum calculated from the header and data section
+        expected: u16,
+        /// The checksum stored in the footer
+        actual: u16,
+    },
+    /// The type byte of a variable entry isn't a known variable type.
+    UnknownType(u8),
+    /// A variable entry can't be read, because its header is invalid or it runs past the end of
+    /// the file.
+    InvalidEntry {
+        /// The offset of the start of the entry in the file
+        offset: usize,
+        /// A description of what is wrong with the entry
+        message: String,
+    },
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FileError::TooShort { length } => write!(
+                f,
+                "File is {} bytes long, which is too short to contain a header and footer",
+                length
+            ),
+            FileError::BadSignature { found } => {
+                write!(f, "Invalid file signature: {:02x?}", found)
+            }
+            FileError::LengthMismatch {
+                field,
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} at byte {} is {}, but the data section implies {}",
+                field, offset, actual, expected
+            ),
+            FileError::TooLarge {
+                field,
+                offset,
+                size,
+            } => write!(
+                f,
+                "{} at byte {} can't describe {} bytes, since it can be at most {}",
+                field,
+                offset,
+                size,
+                u16::MAX
+            ),
+            FileError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "Checksum mismatch: expected 0x{:04X}, found 0x{:04X}",
+                expected, actual
+            ),
+            FileError::UnknownType(byte) => write!(f, "Unknown file type byte 0x{:02X}", byte),
+            FileError::InvalidEntry { offset, message } => {
+                write!(f, "Invalid variable entry at byte {}: {}", offset, message)
+            }
+        }
+    }
+}
+
+impl Error for FileError {}
+
+impl FileError {
+    /// Checks if the error makes the file unreadable, even when reading leniently.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::errors::FileError;
+    ///
+    /// assert!(FileError::TooShort { length: 3 }.is_fatal());
+    /// assert!(!FileError::UnknownType(0x42).is_fatal());
+    /// ```
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            FileError::TooShort { .. }
+                | FileError::BadSignature { .. }
+                | FileError::InvalidEntry { .. }
+        )
+    }
+}
+
+/// Represents text in a TI-BASIC source file that couldn't be converted into tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileError {
+    /// The line that the text is on, starting at 1
+    pub line: usize,
+    /// The column of the first character of the text, in characters and starting at 1
+    pub column: usize,
+    /// The text that couldn't be converted into tokens
+    pub text: String,
+    /// The full source line containing the text, used when rendering the error
+    pub source_line: String,
+    /// The closest known tokens to the text, best match first
+    pub suggestions: Vec<String>,
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Unknown token {:?} at line {}, column {}",
+            self.text, self.line, self.column
+        )?;
+
+        if !self.suggestions.is_empty() {
+            let suggestions: Vec<String> = self
+                .suggestions
+                .iter()
+                .map(|s| format!("{:?}", s))
+                .collect();
+            write!(f, ". Did you mean {}?", suggestions.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for CompileError {}
+
+impl CompileError {
+    /// Renders the error along with the source line, with carets under the offending text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tio2::errors::CompileError;
+    ///
+    /// let error = CompileError {
+    ///     line: 2,
+    ///     column: 6,
+    ///     text: "✓".to_string(),
+    ///     source_line: "Disp ✓A".to_string(),
+    ///     suggestions: vec![],
+    /// };
+    ///
+    /// assert_eq!(
+    ///     error.render(),
+    ///     "error: Unknown token \"✓\" at line 2, column 6\n 2 | Disp ✓A\n   |      ^"
+    /// );
+    /// ```
+    pub fn render(&self) -> String {
+        let line_number = self.line.to_string();
+        let padding = " ".repeat(line_number.len());
+
+        format!(
+            "error: {}\n {} | {}\n {} | {}{}",
+            self,
+            line_number,
+            self.source_line,
+            padding,
+            " ".repeat(self.column.saturating_sub(1)),
+            "^".repeat(self.text.chars().count().max(1))
+        )
+    }
+}
+
+/// Represents every [`CompileError`] found in a TI-BASIC source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileErrors(pub Vec<CompileError>);
+
+impl fmt::Display for CompileErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let errors: Vec<String> = self.0.iter().map(CompileError::to_string).collect();
+        write!(f, "{}", errors.join("\n"))
+    }
+}
+
+impl Error for CompileErrors {}
+
+/// Represents a problem found while reading a token sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetError {
+    /// The line of the sheet where the problem was found, starting at 1
+    pub line: usize,
+    /// A description of what went wrong
+    pub message: String,
+}
+
+impl fmt::Display for SheetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid token sheet at line {}: {}",
+            self.line, self.message
+        )
+    }
+}
+
+impl Error for SheetError {}
+
+impl SheetError {
+    /// Creates a new instance of `SheetError`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tio2::errors::SheetError;
+    ///
+    /// let error = SheetError::new(3, "Expected `>`");
+    /// assert_eq!(error.to_string(), "Invalid token sheet at line 3: Expected `>`");
+    /// ```
+    pub fn new(line: usize, message: impl ToString) -> Self {
+        Self {
+            line,
+            message: message.to_string(),
+        }
+    }
+}
@@ -0,0 +1,815 @@
+//! The `float` module provides [`TIFloat`], a decimal floating-point number that rounds the same
+//! way as the real numbers on a TI-84 Plus calculator.
+//!
+//! The calculator stores real numbers as 9 bytes: a sign/type byte, an exponent byte, and 14
+//! binary-coded decimal digits. While calculating, the calculator keeps a few extra guard digits,
+//! which is why `1/3*3` is exactly `1` even though `1/3` can't be stored exactly.
+
+use std::{cmp::Ordering, fmt, hash::Hash, str::FromStr};
+
+use crate::errors::TIError;
+
+/// The number of significant digits kept while calculating, including the guard digits.
+const DIGITS: u32 = 18;
+
+/// The number of significant digits that are stored in a variable.
+pub const STORED_DIGITS: u32 = 14;
+
+/// The number of significant digits shown on the screen.
+pub const DISPLAY_DIGITS: u32 = 10;
+
+/// The largest exponent that a [`TIFloat`] can have.
+pub const MAX_EXPONENT: i32 = 99;
+
+/// The smallest exponent that a [`TIFloat`] can have. Anything smaller becomes `0`.
+pub const MIN_EXPONENT: i32 = -99;
+
+/// `10^(DIGITS - 1)`, the smallest possible normalized mantissa
+const MANTISSA_MIN: u64 = 10u64.pow(DIGITS - 1);
+
+/// A real number with the same precision and rounding behavior as the calculator.
+///
+/// Equality and ordering compare numbers after rounding them to [`STORED_DIGITS`] digits, which
+/// matches the calculator's relational operators.
+///
+/// # Example
+///
+/// ```
+/// use tio2::float::TIFloat;
+///
+/// let third = TIFloat::from(1).try_div(TIFloat::from(3)).unwrap();
+/// assert_eq!(third.try_mul(TIFloat::from(3)).unwrap(), TIFloat::from(1));
+/// assert_eq!(third.to_string(), ".3333333333");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TIFloat {
+    /// Whether the number is negative
+    negative: bool,
+    /// The power of 10 of the first digit of the mantissa
+    exponent: i32,
+    /// The digits of the number, normalized so that there are always [`DIGITS`] digits
+    /// unless the number is 0
+    mantissa: u64,
+}
+
+impl TIFloat {
+    /// The number 0
+    pub const ZERO: TIFloat = TIFloat {
+        negative: false,
+        exponent: 0,
+        mantissa: 0,
+    };
+
+    /// The number 1
+    pub const ONE: TIFloat = TIFloat {
+        negative: false,
+        exponent: 0,
+        mantissa: MANTISSA_MIN,
+    };
+
+    /// Creates a number equal to `digits * 10^exponent`, rounding it to the calculator's
+    /// precision.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Overflow`] if the magnitude of the number is `1E100` or larger.
+    /// Numbers that are too small to be represented become `0`.
+    fn from_parts(negative: bool, digits: u128, exponent: i32) -> Result<TIFloat, TIError> {
+        Self::round_parts(negative, digits, exponent, DIGITS)
+    }
+
+    /// Like [`TIFloat::from_parts`], but rounds to `significant` digits instead of [`DIGITS`].
+    fn round_parts(
+        negative: bool,
+        mut digits: u128,
+        mut exponent: i32,
+        significant: u32,
+    ) -> Result<TIFloat, TIError> {
+        if digits == 0 {
+            return Ok(TIFloat::ZERO);
+        }
+
+        let count = digits.ilog10() + 1;
+        if count > significant {
+            let drop = 10u128.pow(count - significant);
+            let remainder = digits % drop;
+            digits /= drop;
+            exponent += (count - significant) as i32;
+
+            // round half away from zero
+            if remainder * 2 >= drop {
+                digits += 1;
+                if digits == 10u128.pow(significant) {
+                    digits /= 10;
+                    exponent += 1;
+                }
+            }
+        }
+
+        // pad the mantissa back out to the full number of digits
+        let count = digits.ilog10() + 1;
+        digits *= 10u128.pow(DIGITS - count);
+        exponent -= (DIGITS - count) as i32;
+
+        // exponent is currently the power of 10 of the last digit
+        let exponent = exponent + DIGITS as i32 - 1;
+        if exponent > MAX_EXPONENT {
+            return Err(TIError::Overflow);
+        }
+        if exponent < MIN_EXPONENT {
+            return Ok(TIFloat::ZERO);
+        }
+
+        Ok(TIFloat {
+            negative,
+            exponent,
+            mantissa: digits as u64,
+        })
+    }
+
+    /// The power of 10 of the last digit of the mantissa.
+    fn last_digit_exponent(&self) -> i32 {
+        self.exponent - DIGITS as i32 + 1
+    }
+
+    /// Returns whether this number is 0.
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// Returns whether this number is less than 0.
+    pub fn is_negative(&self) -> bool {
+        self.negative && !self.is_zero()
+    }
+
+    /// Returns whether this number has no fractional part.
+    pub fn is_integer(&self) -> bool {
+        self.ipart() == *self
+    }
+
+    /// Returns the power of 10 of the first significant digit. This is 0 for the number 0.
+    pub fn exponent(&self) -> i32 {
+        self.exponent
+    }
+
+    /// Returns the absolute value of this number.
+    pub fn abs(&self) -> TIFloat {
+        TIFloat {
+            negative: false,
+            ..*self
+        }
+    }
+
+    /// Rounds this number to the [`STORED_DIGITS`] digits that are kept when storing it into a
+    /// variable.
+    pub fn to_stored(&self) -> TIFloat {
+        self.round_significant(STORED_DIGITS)
+    }
+
+    /// Rounds this number to the given number of significant digits.
+    fn round_significant(&self, significant: u32) -> TIFloat {
+        // rounding can only ever make the number smaller or carry into an exponent that
+        // already fits, except for numbers like 9.99...E99
+        Self::round_parts(
+            self.negative,
+            self.mantissa as u128,
+            self.last_digit_exponent(),
+            significant,
+        )
+        .unwrap_or(*self)
+    }
+
+    /// Drops every digit after the given power of 10, optionally rounding instead of truncating.
+    fn truncate_at(&self, exponent: i32, round: bool) -> TIFloat {
+        if self.is_zero() {
+            return *self;
+        }
+
+        let keep = self.exponent - exponent + 1;
+        if keep >= DIGITS as i32 {
+            return *self;
+        }
+        if keep < 0 || (keep == 0 && !round) {
+            return TIFloat::ZERO;
+        }
+        if keep == 0 {
+            // only a rounding carry can be left
+            return if self.mantissa >= 5 * MANTISSA_MIN {
+                TIFloat {
+                    negative: self.negative,
+                    exponent,
+                    mantissa: MANTISSA_MIN,
+                }
+            } else {
+                TIFloat::ZERO
+            };
+        }
+
+        if round {
+            self.round_significant(keep as u32)
+        } else {
+            let drop = 10u64.pow(DIGITS - keep as u32);
+            TIFloat {
+                mantissa: self.mantissa / drop * drop,
+                ..*self
+            }
+        }
+    }
+
+    /// Returns the integer part of this number, rounding towards 0 (`iPart(`).
+    pub fn ipart(&self) -> TIFloat {
+        self.truncate_at(0, false)
+    }
+
+    /// Returns the fractional part of this number (`fPart(`).
+    pub fn fpart(&self) -> TIFloat {
+        // subtracting the integer part is always exact
+        self.try_sub(self.ipart()).unwrap_or(TIFloat::ZERO)
+    }
+
+    /// Returns the largest integer less than or equal to this number (`int(`).
+    pub fn floor(&self) -> TIFloat {
+        let truncated = self.ipart();
+        if self.is_negative() && truncated != *self {
+            truncated.try_sub(TIFloat::ONE).unwrap_or(truncated)
+        } else {
+            truncated
+        }
+    }
+
+    /// Rounds this number to the given number of decimal places, rounding halves away from 0
+    /// (`round(`).
+    pub fn round(&self, places: i32) -> TIFloat {
+        self.truncate_at(-places, true)
+    }
+
+    /// Adds two numbers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Overflow`] if the result is too large.
+    pub fn try_add(&self, other: TIFloat) -> Result<TIFloat, TIError> {
+        if self.is_zero() {
+            return Ok(other);
+        }
+        if other.is_zero() {
+            return Ok(*self);
+        }
+
+        // compare the raw digits, since comparison operators ignore the guard digits
+        let (big, small) = if (self.exponent, self.mantissa) >= (other.exponent, other.mantissa) {
+            (*self, other)
+        } else {
+            (other, *self)
+        };
+
+        // the smaller number is too small to change any of the digits
+        let difference = (big.exponent - small.exponent) as u32;
+        if difference > DIGITS + 1 {
+            return Ok(big);
+        }
+
+        let big_digits = big.mantissa as u128 * 10u128.pow(difference);
+        let small_digits = small.mantissa as u128;
+        let digits = if big.negative == small.negative {
+            big_digits + small_digits
+        } else {
+            big_digits - small_digits
+        };
+
+        Self::from_parts(big.negative, digits, small.last_digit_exponent())
+    }
+
+    /// Subtracts `other` from this number.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Overflow`] if the result is too large.
+    pub fn try_sub(&self, other: TIFloat) -> Result<TIFloat, TIError> {
+        self.try_add(-other)
+    }
+
+    /// Multiplies two numbers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Overflow`] if the result is too large.
+    pub fn try_mul(&self, other: TIFloat) -> Result<TIFloat, TIError> {
+        Self::from_parts(
+            self.negative != other.negative,
+            self.mantissa as u128 * other.mantissa as u128,
+            self.last_digit_exponent() + other.last_digit_exponent(),
+        )
+    }
+
+    /// Divides this number by `other`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::DivideBy0`] if `other` is 0, or [`TIError::Overflow`] if the result is
+    /// too large.
+    pub fn try_div(&self, other: TIFloat) -> Result<TIFloat, TIError> {
+        if other.is_zero() {
+            return Err(TIError::DivideBy0);
+        }
+
+        // scale up the dividend so that the quotient has more digits than we need, which makes
+        // rounding the truncated quotient correct
+        let scale = 20;
+        let dividend = self.mantissa as u128 * 10u128.pow(scale);
+        Self::from_parts(
+            self.negative != other.negative,
+            dividend / other.mantissa as u128,
+            self.last_digit_exponent() - other.last_digit_exponent() - scale as i32,
+        )
+    }
+
+    /// Raises this number to the power of `exponent` (`^`).
+    ///
+    /// Integer powers are calculated exactly by repeated multiplication.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Domain`] for a fractional power of a negative number,
+    /// [`TIError::DivideBy0`] for a negative power of 0, or [`TIError::Overflow`] if the result
+    /// is too large.
+    pub fn try_pow(&self, exponent: TIFloat) -> Result<TIFloat, TIError> {
+        if self.is_zero() {
+            return match exponent.cmp(&TIFloat::ZERO) {
+                Ordering::Less => Err(TIError::DivideBy0),
+                Ordering::Equal => Err(TIError::Domain),
+                Ordering::Greater => Ok(TIFloat::ZERO),
+            };
+        }
+
+        if let Some(power) = exponent.to_i64().filter(|p| p.unsigned_abs() <= 1 << 16) {
+            let mut result = TIFloat::ONE;
+            let mut base = *self;
+            let mut remaining = power.unsigned_abs();
+            while remaining > 0 {
+                if remaining & 1 == 1 {
+                    result = result.try_mul(base)?;
+                }
+                remaining >>= 1;
+                if remaining > 0 {
+                    base = base.try_mul(base)?;
+                }
+            }
+
+            return if power < 0 {
+                TIFloat::ONE.try_div(result)
+            } else {
+                Ok(result)
+            };
+        }
+
+        if self.is_negative() {
+            return Err(TIError::Domain);
+        }
+
+        TIFloat::from_f64(self.to_f64().powf(exponent.to_f64()))
+    }
+
+    /// Applies a function from [`f64`] to [`f64`] to this number. This is used for functions such
+    /// as `sin(` and `ln(` that don't need to be exact.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Domain`] if the function returns NaN, or [`TIError::Overflow`] if the
+    /// result is too large.
+    pub fn map_f64(&self, f: impl FnOnce(f64) -> f64) -> Result<TIFloat, TIError> {
+        TIFloat::from_f64(f(self.to_f64()))
+    }
+
+    /// Converts an [`f64`] into a [`TIFloat`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Domain`] for NaN and [`TIError::Overflow`] for infinite values or values
+    /// too large to be represented.
+    pub fn from_f64(value: f64) -> Result<TIFloat, TIError> {
+        if value.is_nan() {
+            return Err(TIError::Domain);
+        }
+        if value.is_infinite() {
+            return Err(TIError::Overflow);
+        }
+
+        // {:e} formats the shortest representation that converts back to the same f64
+        format!("{:e}", value).parse().map_err(|e| match e {
+            TIError::Overflow => TIError::Overflow,
+            _ => TIError::Domain,
+        })
+    }
+
+    /// Converts this number into the closest [`f64`].
+    pub fn to_f64(&self) -> f64 {
+        let value = format!("{}e{}", self.mantissa, self.last_digit_exponent())
+            .parse::<f64>()
+            .unwrap_or(0.0);
+        if self.negative {
+            -value
+        } else {
+            value
+        }
+    }
+
+    /// Converts this number into an [`i64`] if it is an integer that fits.
+    pub fn to_i64(&self) -> Option<i64> {
+        if !self.is_integer() || self.exponent >= 18 {
+            return None;
+        }
+        if self.is_zero() {
+            return Some(0);
+        }
+
+        let value = (self.mantissa / 10u64.pow(DIGITS - 1 - self.exponent as u32)) as i64;
+        Some(if self.negative { -value } else { value })
+    }
+
+    /// Reads a number from the 9-byte format used by real variables on the calculator.
+    ///
+    /// The first byte holds the sign in its highest bit, the second byte holds the exponent
+    /// offset by `0x80`, and the remaining 7 bytes hold 14 binary-coded decimal digits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the mantissa nibbles is not a decimal digit, or if the exponent
+    /// is out of range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::float::TIFloat;
+    ///
+    /// let bytes = [0x80, 0x82, 0x12, 0x50, 0x00, 0x00, 0x00, 0x00, 0x00];
+    /// let number = TIFloat::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(number.to_string(), "-125");
+    /// assert_eq!(number.to_bytes(), bytes);
+    /// ```
+    pub fn from_bytes(bytes: &[u8; 9]) -> Result<TIFloat, anyhow::Error> {
+        let mut digits: u128 = 0;
+        for &byte in &bytes[2..] {
+            for nibble in [byte >> 4, byte & 0xF] {
+                if nibble > 9 {
+                    return Err(anyhow::Error::msg(format!(
+                        "Invalid BCD digit in real number: {:x?}",
+                        bytes
+                    )));
+                }
+                digits = digits * 10 + nibble as u128;
+            }
+        }
+
+        let exponent = bytes[1] as i32 - 0x80;
+        if !(MIN_EXPONENT..=MAX_EXPONENT).contains(&exponent) {
+            return Err(anyhow::Error::msg(format!(
+                "Real number exponent out of range: {}",
+                exponent
+            )));
+        }
+
+        Ok(Self::from_parts(
+            bytes[0] & 0x80 != 0,
+            digits,
+            exponent - STORED_DIGITS as i32 + 1,
+        )?)
+    }
+
+    /// Converts this number into the 9-byte format used by real variables on the calculator,
+    /// rounding it to [`STORED_DIGITS`] digits.
+    pub fn to_bytes(&self) -> [u8; 9] {
+        let stored = self.to_stored();
+        let mut bytes = [0u8; 9];
+
+        if stored.is_zero() {
+            bytes[1] = 0x80;
+            return bytes;
+        }
+
+        bytes[0] = if stored.negative { 0x80 } else { 0x00 };
+        bytes[1] = (0x80 + stored.exponent) as u8;
+
+        let digits = stored.mantissa / 10u64.pow(DIGITS - STORED_DIGITS);
+        let digits = format!("{:014}", digits).into_bytes();
+        for (i, pair) in digits.chunks(2).enumerate() {
+            bytes[i + 2] = ((pair[0] - b'0') << 4) | (pair[1] - b'0');
+        }
+
+        bytes
+    }
+
+    /// The sign, exponent, and mantissa after rounding to [`STORED_DIGITS`] digits, which is what
+    /// comparisons are done on.
+    fn comparison_key(&self) -> (bool, i32, u64) {
+        let stored = self.to_stored();
+        if stored.is_zero() {
+            (false, 0, 0)
+        } else {
+            (stored.negative, stored.exponent, stored.mantissa)
+        }
+    }
+}
+
+impl PartialEq for TIFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.comparison_key() == other.comparison_key()
+    }
+}
+
+impl Eq for TIFloat {}
+
+impl Hash for TIFloat {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.comparison_key().hash(state);
+    }
+}
+
+impl PartialOrd for TIFloat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TIFloat {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a_negative, a_exponent, a_mantissa) = self.comparison_key();
+        let (b_negative, b_exponent, b_mantissa) = other.comparison_key();
+
+        let magnitude = |exponent: i32, mantissa: u64| {
+            if mantissa == 0 {
+                // 0 is smaller than any other magnitude
+                (i32::MIN, 0)
+            } else {
+                (exponent, mantissa)
+            }
+        };
+        let a = magnitude(a_exponent, a_mantissa);
+        let b = magnitude(b_exponent, b_mantissa);
+
+        match (a_negative, b_negative) {
+            (false, false) => a.cmp(&b),
+            (true, true) => b.cmp(&a),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl std::ops::Neg for TIFloat {
+    type Output = TIFloat;
+
+    fn neg(self) -> TIFloat {
+        if self.is_zero() {
+            return self;
+        }
+
+        TIFloat {
+            negative: !self.negative,
+            ..self
+        }
+    }
+}
+
+impl From<i64> for TIFloat {
+    fn from(value: i64) -> Self {
+        // an i64 has at most 19 digits, so this can't overflow
+        Self::from_parts(value < 0, value.unsigned_abs() as u128, 0).unwrap_or(TIFloat::ZERO)
+    }
+}
+
+impl From<i32> for TIFloat {
+    fn from(value: i32) -> Self {
+        TIFloat::from(value as i64)
+    }
+}
+
+impl From<bool> for TIFloat {
+    fn from(value: bool) -> Self {
+        if value {
+            TIFloat::ONE
+        } else {
+            TIFloat::ZERO
+        }
+    }
+}
+
+impl FromStr for TIFloat {
+    type Err = TIError;
+
+    /// Parses a number such as `12`, `-.5` or `1.2E-3`. Both `E` and `ᴇ` are accepted for the
+    /// exponent.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, s) = match s.strip_prefix(['-', '⁻']) {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+
+        let (mantissa, exponent) = match s.split_once(['e', 'E', 'ᴇ']) {
+            Some((m, e)) => {
+                let e = e.replace('⁻', "-");
+                // anything outside of this range is an overflow or 0 anyway
+                let e = e.parse::<i32>().map_err(|_| TIError::Syntax)?;
+                (m, e.clamp(-1000, 1000))
+            }
+            None => (s, 0),
+        };
+
+        let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if (whole.is_empty() && fraction.is_empty())
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(TIError::Syntax);
+        }
+
+        // only DIGITS + 1 digits are needed to round correctly, so drop the rest
+        let all_digits = format!("{}{}", whole, fraction);
+        let all_digits = all_digits.trim_start_matches('0');
+        let kept = all_digits.len().min(DIGITS as usize + 1);
+        let digits: u128 = all_digits[..kept].parse().unwrap_or(0);
+
+        let exponent =
+            exponent.saturating_sub(fraction.len() as i32) + (all_digits.len() - kept) as i32;
+        Self::from_parts(negative, digits, exponent)
+    }
+}
+
+impl fmt::Display for TIFloat {
+    /// Formats the number the way that the calculator displays it in `Normal` mode, with up to
+    /// [`DISPLAY_DIGITS`] significant digits. Large and small numbers use scientific notation.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rounded = self.round_significant(DISPLAY_DIGITS);
+        if rounded.is_zero() {
+            return write!(f, "0");
+        }
+
+        let sign = if rounded.negative { "-" } else { "" };
+        let digits = format!("{}", rounded.mantissa);
+        let digits = digits.trim_end_matches('0');
+        let exponent = rounded.exponent;
+
+        if !(-3..DISPLAY_DIGITS as i32).contains(&exponent) {
+            let (first, rest) = digits.split_at(1);
+            return if rest.is_empty() {
+                write!(f, "{}{}E{}", sign, first, exponent)
+            } else {
+                write!(f, "{}{}.{}E{}", sign, first, rest, exponent)
+            };
+        }
+
+        let body = if exponent < 0 {
+            format!(".{}{}", "0".repeat((-exponent - 1) as usize), digits)
+        } else {
+            let point = exponent as usize + 1;
+            if digits.len() <= point {
+                format!("{}{}", digits, "0".repeat(point - digits.len()))
+            } else {
+                format!("{}.{}", &digits[..point], &digits[point..])
+            }
+        };
+
+        write!(f, "{}{}", sign, body)
+    }
+}
+
+/// A complex number made of two [`TIFloat`]s, such as `2+3i`.
+///
+/// # Example
+///
+/// ```
+/// use tio2::float::{Complex, TIFloat};
+///
+/// let a = Complex::new(TIFloat::from(1), TIFloat::from(2));
+/// let b = Complex::new(TIFloat::from(3), TIFloat::from(-1));
+///
+/// assert_eq!(a.try_mul(b).unwrap().to_string(), "5+5i");
+/// assert_eq!(a.try_div(b).unwrap().to_string(), ".1+.7i");
+/// assert_eq!((-Complex::I).to_string(), "-i");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Complex {
+    /// The real part
+    pub re: TIFloat,
+    /// The imaginary part
+    pub im: TIFloat,
+}
+
+impl Complex {
+    /// The imaginary unit `i`
+    pub const I: Complex = Complex {
+        re: TIFloat::ZERO,
+        im: TIFloat::ONE,
+    };
+
+    /// Creates a complex number from its real and imaginary parts.
+    pub fn new(re: TIFloat, im: TIFloat) -> Self {
+        Self { re, im }
+    }
+
+    /// Returns whether the imaginary part is 0.
+    pub fn is_real(&self) -> bool {
+        self.im.is_zero()
+    }
+
+    /// Rounds both parts to the digits that are kept when storing into a variable.
+    pub fn to_stored(&self) -> Complex {
+        Complex::new(self.re.to_stored(), self.im.to_stored())
+    }
+
+    /// Adds two complex numbers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Overflow`] if either part is too large.
+    pub fn try_add(&self, other: Complex) -> Result<Complex, TIError> {
+        Ok(Complex::new(
+            self.re.try_add(other.re)?,
+            self.im.try_add(other.im)?,
+        ))
+    }
+
+    /// Subtracts a complex number from this one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Overflow`] if either part is too large.
+    pub fn try_sub(&self, other: Complex) -> Result<Complex, TIError> {
+        self.try_add(-other)
+    }
+
+    /// Multiplies two complex numbers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Overflow`] if either part is too large.
+    pub fn try_mul(&self, other: Complex) -> Result<Complex, TIError> {
+        let re = self
+            .re
+            .try_mul(other.re)?
+            .try_sub(self.im.try_mul(other.im)?)?;
+        let im = self
+            .re
+            .try_mul(other.im)?
+            .try_add(self.im.try_mul(other.re)?)?;
+        Ok(Complex::new(re, im))
+    }
+
+    /// Divides this complex number by another.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::DivideBy0`] if `other` is 0, or [`TIError::Overflow`] if either part
+    /// is too large.
+    pub fn try_div(&self, other: Complex) -> Result<Complex, TIError> {
+        let denominator = other
+            .re
+            .try_mul(other.re)?
+            .try_add(other.im.try_mul(other.im)?)?;
+        let conjugate = Complex::new(other.re, -other.im);
+        let numerator = self.try_mul(conjugate)?;
+
+        Ok(Complex::new(
+            numerator.re.try_div(denominator)?,
+            numerator.im.try_div(denominator)?,
+        ))
+    }
+}
+
+impl From<TIFloat> for Complex {
+    fn from(value: TIFloat) -> Self {
+        Complex::new(value, TIFloat::ZERO)
+    }
+}
+
+impl std::ops::Neg for Complex {
+    type Output = Complex;
+
+    fn neg(self) -> Complex {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+impl fmt::Display for Complex {
+    /// Formats the number the way that the calculator displays it in `a+bi` mode, leaving out
+    /// parts that are 0 and an imaginary part of 1.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let im = self.im.abs();
+        let im = if im == TIFloat::ONE {
+            String::new()
+        } else {
+            im.to_string()
+        };
+
+        match (self.re.is_zero(), self.im.is_zero(), self.im.is_negative()) {
+            (_, true, _) => write!(f, "{}", self.re),
+            (true, false, false) => write!(f, "{}i", im),
+            (true, false, true) => write!(f, "-{}i", im),
+            (false, false, false) => write!(f, "{}+{}i", self.re, im),
+            (false, false, true) => write!(f, "{}-{}i", self.re, im),
+        }
+    }
+}
@@ -0,0 +1,792 @@
+//! The `graph` module models the calculator's graph screen, which is where `Pxl-On(`, `Line(`,
+//! `Circle(`, `Text(` and the other drawing commands draw.
+//!
+//! The screen is a framebuffer of 96 by 64 pixels on monochrome models and 320 by 240 on color
+//! models. Drawing happens in the graph area, which is 95 by 63 pixels in the top left corner of
+//! a monochrome screen, and 265 by 165 pixels below the status bar of a color screen. Commands
+//! that take coordinates, such as `Pt-On(`, map them onto the graph area using the [`Window`].
+//!
+//! The screen can be saved as a PNG or PBM image with [`GraphScreen::save`], so that drawings can
+//! be compared against expected images without a terminal.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{errors::TIError, translation::models::Model};
+
+/// The color of pixels that haven't been drawn on.
+pub const BACKGROUND: u8 = 0;
+
+/// The color number of `BLUE`, which color models draw with by default.
+pub const BLUE: u8 = 10;
+
+/// The color number of `BLACK`, which monochrome models draw with.
+pub const BLACK: u8 = 12;
+
+/// The color number of `WHITE`.
+pub const WHITE: u8 = 20;
+
+/// The RGB values of the color numbers from `BLUE` (10) to `DARKGRAY` (24).
+const PALETTE: [[u8; 3]; 15] = [
+    [0x00, 0x00, 0xFF],
+    [0xFF, 0x00, 0x00],
+    [0x00, 0x00, 0x00],
+    [0xFF, 0x00, 0xFF],
+    [0x00, 0x9F, 0x00],
+    [0xFF, 0x8F, 0x20],
+    [0xB6, 0x20, 0x20],
+    [0x00, 0x00, 0x86],
+    [0x00, 0x93, 0xFF],
+    [0xFF, 0xFF, 0x00],
+    [0xFF, 0xFF, 0xFF],
+    [0xE7, 0xE3, 0xE7],
+    [0xBD, 0xBA, 0xBD],
+    [0x8E, 0x8A, 0x8E],
+    [0x52, 0x50, 0x52],
+];
+
+/// The size of a character drawn by `Text(`, including the space after it.
+const GLYPH_WIDTH: usize = 4;
+const GLYPH_HEIGHT: usize = 6;
+
+/// The range of the graph area, set with the window variables.
+///
+/// # Example
+///
+/// ```
+/// use tio2::interpreter::graph::Window;
+///
+/// let window = Window::default();
+///
+/// assert_eq!((window.xmin, window.xmax, window.ymin, window.ymax), (-10.0, 10.0, -10.0, 10.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Window {
+    /// `Xmin`, the x coordinate of the left edge
+    pub xmin: f64,
+    /// `Xmax`, the x coordinate of the right edge
+    pub xmax: f64,
+    /// `Ymin`, the y coordinate of the bottom edge
+    pub ymin: f64,
+    /// `Ymax`, the y coordinate of the top edge
+    pub ymax: f64,
+}
+
+impl Default for Window {
+    /// The standard window, from -10 to 10 in both directions.
+    fn default() -> Self {
+        Self {
+            xmin: -10.0,
+            xmax: 10.0,
+            ymin: -10.0,
+            ymax: 10.0,
+        }
+    }
+}
+
+/// How a drawing command changes the pixels it touches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pen {
+    /// Turn pixels on with a color number
+    On(u8),
+    /// Turn pixels off
+    Off,
+    /// Turn pixels that are off on with a color number, and pixels that are on off
+    Change(u8),
+}
+
+/// The calculator's graph screen.
+///
+/// # Example
+///
+/// ```
+/// use tio2::interpreter::graph::{GraphScreen, Pen, BLACK};
+///
+/// let mut graph = GraphScreen::default();
+/// graph.line(-10.0, 10.0, 10.0, -10.0, Pen::On(BLACK)).unwrap();
+///
+/// assert_eq!(graph.pixel(0, 0), Some(BLACK));
+/// assert_eq!(graph.pixel(31, 47), Some(BLACK));
+/// assert_eq!(graph.pixel(0, 94), Some(0));
+/// assert_eq!(graph.pixel(63, 0), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphScreen {
+    /// The width of the whole screen in pixels
+    width: usize,
+    /// The height of the whole screen in pixels
+    height: usize,
+    /// The column, row, width and height of the graph area
+    area: (usize, usize, usize, usize),
+    /// The color number of every pixel, row by row, where [`BACKGROUND`] is off
+    pixels: Vec<u8>,
+    /// Whether pixels can have colors other than [`BLACK`]
+    color: bool,
+    /// The range of the graph area
+    pub window: Window,
+}
+
+impl Default for GraphScreen {
+    /// Creates the 96 by 64 screen of the monochrome models.
+    fn default() -> Self {
+        Self::new(96, 64, (0, 0, 95, 63), false)
+    }
+}
+
+impl GraphScreen {
+    /// Creates a blank screen with the given size and graph area, with the standard window.
+    fn new(width: usize, height: usize, area: (usize, usize, usize, usize), color: bool) -> Self {
+        Self {
+            width,
+            height,
+            area,
+            pixels: vec![BACKGROUND; width * height],
+            color,
+            window: Window::default(),
+        }
+    }
+
+    /// Creates the 320 by 240 screen of the color models.
+    pub fn color() -> Self {
+        Self::new(320, 240, (27, 52, 265, 165), true)
+    }
+
+    /// Creates a blank screen the size of a model's, which is in color on the TI-84 Plus C Silver
+    /// Edition and TI-84 Plus CE.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::{interpreter::graph::GraphScreen, translation::models::Model};
+    ///
+    /// assert_eq!(GraphScreen::for_model(Model::TI84PlusCE).size(), (320, 240));
+    /// assert_eq!(GraphScreen::for_model(Model::TI84PlusCE).graph_size(), (265, 165));
+    /// assert_eq!(GraphScreen::for_model(Model::TI83Plus).graph_size(), (95, 63));
+    /// ```
+    pub fn for_model(model: Model) -> Self {
+        if model >= Model::TI84PlusCSE {
+            Self::color()
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Returns the width and height of the whole screen in pixels.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Returns the width and height of the graph area in pixels.
+    pub fn graph_size(&self) -> (usize, usize) {
+        (self.area.2, self.area.3)
+    }
+
+    /// Returns whether the screen has colors.
+    pub fn is_color(&self) -> bool {
+        self.color
+    }
+
+    /// Returns the color that drawing commands use when they aren't given one.
+    pub fn default_color(&self) -> u8 {
+        if self.color {
+            BLUE
+        } else {
+            BLACK
+        }
+    }
+
+    /// Turns every pixel off, like `ClrDraw`.
+    pub fn clear(&mut self) {
+        self.pixels.fill(BACKGROUND);
+    }
+
+    /// Returns the color of a pixel in the graph area, or [`None`] if it is outside of the area.
+    pub fn pixel(&self, row: usize, column: usize) -> Option<u8> {
+        self.index(row as i64, column as i64)
+            .map(|index| self.pixels[index])
+    }
+
+    /// Changes a pixel in the graph area, like `Pxl-On(`.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the pixel is outside of the graph area, in which case nothing changes.
+    pub fn set_pixel(&mut self, row: i64, column: i64, pen: Pen) -> bool {
+        let index = match self.index(row, column) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        let pixel = &mut self.pixels[index];
+        *pixel = match pen {
+            Pen::On(color) => color,
+            Pen::Off => BACKGROUND,
+            Pen::Change(_) if *pixel != BACKGROUND => BACKGROUND,
+            Pen::Change(color) => color,
+        };
+
+        true
+    }
+
+    /// Returns the x coordinate of a column of the graph area.
+    pub fn column_x(&self, column: usize) -> f64 {
+        let (dx, _) = self.pixel_size();
+        self.window.xmin + column as f64 * dx
+    }
+
+    /// Checks that `Xmin` is less than `Xmax` and `Ymin` is less than `Ymax`, which commands that
+    /// use the window need.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::WindowRange`] if the window is empty.
+    pub fn check_window(&self) -> Result<(), TIError> {
+        let window = &self.window;
+        if window.xmin < window.xmax && window.ymin < window.ymax {
+            Ok(())
+        } else {
+            Err(TIError::WindowRange)
+        }
+    }
+
+    /// Draws a point at window coordinates, like `Pt-On(`. Points outside of the window are
+    /// ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `mark` - 1 for a single pixel, 2 for a small box, or 3 for a small cross.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::WindowRange`] if the window is empty, or [`TIError::Domain`] if `mark`
+    /// isn't 1, 2 or 3.
+    pub fn point(&mut self, x: f64, y: f64, mark: u8, pen: Pen) -> Result<(), TIError> {
+        self.check_window()?;
+        let (column, row) = self.to_pixel(x, y);
+        let (column, row) = (column.round() as i64, row.round() as i64);
+
+        let offsets: &[(i64, i64)] = match mark {
+            1 => &[(0, 0)],
+            2 => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+            3 => &[(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)],
+            _ => return Err(TIError::Domain),
+        };
+        for (dr, dc) in offsets {
+            self.set_pixel(row + dr, column + dc, pen);
+        }
+
+        Ok(())
+    }
+
+    /// Draws a line between two points in window coordinates, like `Line(`. The parts outside of
+    /// the window are cut off.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::WindowRange`] if the window is empty.
+    pub fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, pen: Pen) -> Result<(), TIError> {
+        self.check_window()?;
+        let start = self.to_pixel(x1, y1);
+        let end = self.to_pixel(x2, y2);
+
+        if let Some((start, end)) = self.clip(start, end) {
+            self.pixel_line(start, end, pen);
+        }
+
+        Ok(())
+    }
+
+    /// Draws a line across the graph area at a y coordinate, like `Horizontal`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::WindowRange`] if the window is empty.
+    pub fn horizontal(&mut self, y: f64, pen: Pen) -> Result<(), TIError> {
+        let window = self.window;
+        self.line(window.xmin, y, window.xmax, y, pen)
+    }
+
+    /// Draws a line down the graph area at an x coordinate, like `Vertical`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::WindowRange`] if the window is empty.
+    pub fn vertical(&mut self, x: f64, pen: Pen) -> Result<(), TIError> {
+        let window = self.window;
+        self.line(x, window.ymin, x, window.ymax, pen)
+    }
+
+    /// Draws a circle in window coordinates, like `Circle(`. The radius is measured along the x
+    /// and y axes separately, so the circle is stretched if the window isn't square.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::WindowRange`] if the window is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::interpreter::graph::{GraphScreen, Pen, BLACK};
+    ///
+    /// let mut graph = GraphScreen::default();
+    /// graph.circle(0.0, 0.0, 5.0, Pen::On(BLACK)).unwrap();
+    ///
+    /// // the rightmost point is half way between the center and the right edge
+    /// assert_eq!(graph.pixel(31, 71), Some(BLACK));
+    /// assert_eq!(graph.pixel(31, 47), Some(0));
+    /// ```
+    pub fn circle(&mut self, x: f64, y: f64, radius: f64, pen: Pen) -> Result<(), TIError> {
+        self.check_window()?;
+        let (dx, dy) = self.pixel_size();
+        let (column, row) = self.to_pixel(x, y);
+        let (rx, ry) = ((radius / dx).abs(), (radius / dy).abs());
+
+        // enough steps that neighbouring points touch, without running forever for huge circles
+        let steps = ((rx.max(ry) * 8.0).ceil() as usize).clamp(8, 100_000);
+        let mut drawn = Vec::with_capacity(steps);
+        for step in 0..steps {
+            let angle = step as f64 / steps as f64 * std::f64::consts::TAU;
+            let point = (
+                (row - ry * angle.sin()).round() as i64,
+                (column + rx * angle.cos()).round() as i64,
+            );
+            // Pt-Change style pens would undo pixels that are visited twice
+            if !drawn.contains(&point) {
+                drawn.push(point);
+                self.set_pixel(point.0, point.1, pen);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fills the pixels of a column between two y coordinates, for `Shade(`.
+    pub fn shade_column(&mut self, column: usize, lower: f64, upper: f64, pen: Pen) {
+        let (_, bottom) = self.to_pixel(0.0, lower.min(upper));
+        let (_, top) = self.to_pixel(0.0, lower.max(upper));
+
+        let top = top.round().max(0.0) as i64;
+        let bottom = bottom.round().min(self.area.3 as f64) as i64;
+        for row in top..=bottom {
+            self.set_pixel(row, column as i64, pen);
+        }
+    }
+
+    /// Draws text with its top left corner at a pixel of the graph area, like `Text(`. Each
+    /// character is 3 by 5 pixels on a background 4 by 6 pixels, and anything past the edge of
+    /// the graph area is cut off. Letters are drawn in uppercase.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the starting pixel is outside of the graph area, in which case nothing is
+    /// drawn.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::interpreter::graph::{GraphScreen, Pen, BLACK};
+    ///
+    /// let mut graph = GraphScreen::default();
+    /// assert!(graph.text(0, 0, "HI", Pen::On(BLACK)));
+    ///
+    /// assert_eq!(
+    ///     &graph.snapshot()[..5 * 96],
+    ///     [
+    ///         "#.#.###",
+    ///         "#.#..#.",
+    ///         "###..#.",
+    ///         "#.#..#.",
+    ///         "#.#.###",
+    ///     ]
+    ///     .map(|row| format!("{:.<95}\n", row))
+    ///     .concat()
+    /// );
+    /// ```
+    pub fn text(&mut self, row: usize, column: usize, text: &str, pen: Pen) -> bool {
+        if self.pixel(row, column).is_none() {
+            return false;
+        }
+
+        for (index, c) in text.chars().enumerate() {
+            let left = (column + index * GLYPH_WIDTH) as i64;
+            let rows = glyph(c);
+            for r in 0..GLYPH_HEIGHT {
+                // the bottom row and the right column are the background between characters
+                let bits = rows.get(r).copied().unwrap_or(0);
+                for dc in 0..GLYPH_WIDTH {
+                    let on = dc < 3 && bits & (0b100 >> dc) != 0;
+                    let pen = if on { pen } else { Pen::Off };
+                    self.set_pixel(row as i64 + r as i64, left + dc as i64, pen);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns the graph area as text, with `#` for pixels that are on and `.` for pixels that
+    /// are off, and a newline after each row. This is meant for comparing against expected
+    /// output in tests.
+    pub fn snapshot(&self) -> String {
+        let (width, height) = self.graph_size();
+        let mut text = String::with_capacity((width + 1) * height);
+
+        for row in 0..height {
+            for column in 0..width {
+                let on = self.pixel(row, column).is_some_and(is_ink);
+                text.push(if on { '#' } else { '.' });
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
+    /// Encodes the whole screen as a binary PBM image, where pixels that are on are black.
+    pub fn to_pbm(&self) -> Vec<u8> {
+        let mut data = format!("P4\n{} {}\n", self.width, self.height).into_bytes();
+
+        for row in self.pixels.chunks(self.width) {
+            for bits in row.chunks(8) {
+                let byte = bits
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &pixel)| is_ink(pixel))
+                    .fold(0u8, |byte, (bit, _)| byte | (0x80 >> bit));
+                data.push(byte);
+            }
+        }
+
+        data
+    }
+
+    /// Encodes the whole screen as an RGB PNG image, with a white background.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::interpreter::graph::GraphScreen;
+    ///
+    /// let png = GraphScreen::default().to_png();
+    ///
+    /// assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    /// ```
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.pixels.chunks(self.width) {
+            // no filter
+            raw.push(0);
+            for &pixel in row {
+                raw.extend(rgb(pixel));
+            }
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, default compression, filtering and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut png, b"IEND", &[]);
+
+        png
+    }
+
+    /// Saves the whole screen as an image, which is a PNG or PBM image depending on the extension
+    /// of `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extension isn't `.png` or `.pbm`, or if the file can't be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), anyhow::Error> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+
+        let data = match extension.as_deref() {
+            Some("png") => self.to_png(),
+            Some("pbm") => self.to_pbm(),
+            _ => {
+                return Err(anyhow::Error::msg(format!(
+                    "Can't save the graph screen as {}, expected a .png or .pbm file",
+                    path.display()
+                )))
+            }
+        };
+
+        Ok(fs::write(path, data)?)
+    }
+
+    /// Draws the graph area in a terminal with two pixels in each character, replacing whatever
+    /// was on the terminal before.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn render(&self, out: &mut impl Write) -> io::Result<()> {
+        let (width, height) = self.graph_size();
+        let on = |row: usize, column: usize| self.pixel(row, column).is_some_and(is_ink);
+        let border = "─".repeat(width);
+
+        write!(out, "\x1b[2J\x1b[H┌{}┐\r\n", border)?;
+        for row in (0..height).step_by(2) {
+            let line: String = (0..width)
+                .map(|column| match (on(row, column), on(row + 1, column)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect();
+            write!(out, "│{}│\r\n", line)?;
+        }
+        write!(out, "└{}┘\r\n", border)?;
+
+        out.flush()
+    }
+
+    /// Returns the index of a pixel of the graph area in [`GraphScreen::pixels`].
+    fn index(&self, row: i64, column: i64) -> Option<usize> {
+        let (left, top, width, height) = self.area;
+        if !(0..height as i64).contains(&row) || !(0..width as i64).contains(&column) {
+            return None;
+        }
+
+        Some((top + row as usize) * self.width + left + column as usize)
+    }
+
+    /// Returns the width and height of a pixel in window units.
+    fn pixel_size(&self) -> (f64, f64) {
+        let window = &self.window;
+        (
+            (window.xmax - window.xmin) / (self.area.2 - 1) as f64,
+            (window.ymax - window.ymin) / (self.area.3 - 1) as f64,
+        )
+    }
+
+    /// Converts window coordinates into the column and row of the graph area, without rounding.
+    fn to_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        let (dx, dy) = self.pixel_size();
+        ((x - self.window.xmin) / dx, (self.window.ymax - y) / dy)
+    }
+
+    /// Cuts a line between two pixel positions down to the part just around the graph area.
+    fn clip(&self, start: (f64, f64), end: (f64, f64)) -> Option<((f64, f64), (f64, f64))> {
+        let (width, height) = (self.area.2 as f64, self.area.3 as f64);
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let (mut low, mut high) = (0.0f64, 1.0f64);
+
+        // Liang-Barsky, against a box one pixel bigger than the graph area
+        for (p, q) in [
+            (-dx, start.0 + 1.0),
+            (dx, width - start.0),
+            (-dy, start.1 + 1.0),
+            (dy, height - start.1),
+        ] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+                continue;
+            }
+
+            let t = q / p;
+            if p < 0.0 {
+                low = low.max(t);
+            } else {
+                high = high.min(t);
+            }
+            if low > high {
+                return None;
+            }
+        }
+
+        Some((
+            (start.0 + low * dx, start.1 + low * dy),
+            (start.0 + high * dx, start.1 + high * dy),
+        ))
+    }
+
+    /// Draws a line between two pixel positions with Bresenham's algorithm.
+    fn pixel_line(&mut self, start: (f64, f64), end: (f64, f64), pen: Pen) {
+        let (mut column, mut row) = (start.0.round() as i64, start.1.round() as i64);
+        let (end_column, end_row) = (end.0.round() as i64, end.1.round() as i64);
+
+        let dx = (end_column - column).abs();
+        let dy = -(end_row - row).abs();
+        let step_x = if column < end_column { 1 } else { -1 };
+        let step_y = if row < end_row { 1 } else { -1 };
+        let mut error = dx + dy;
+
+        loop {
+            self.set_pixel(row, column, pen);
+            if column == end_column && row == end_row {
+                break;
+            }
+
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                column += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                row += step_y;
+            }
+        }
+    }
+}
+
+/// Returns whether a pixel is drawn in a color that shows up on a white background.
+fn is_ink(pixel: u8) -> bool {
+    pixel != BACKGROUND && pixel != WHITE
+}
+
+/// Returns the RGB values of a pixel.
+fn rgb(pixel: u8) -> [u8; 3] {
+    match pixel {
+        BLUE..=24 => PALETTE[(pixel - BLUE) as usize],
+        BACKGROUND => [0xFF, 0xFF, 0xFF],
+        _ => [0x00, 0x00, 0x00],
+    }
+}
+
+/// Adds a chunk to a PNG image.
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+
+    png.extend(crc.to_be_bytes());
+}
+
+/// Wraps data in a zlib stream without compressing it, using stored deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const BLOCK: usize = 0xFFFF;
+
+    let mut stream = vec![0x78, 0x01];
+    let blocks = data.chunks(BLOCK).collect::<Vec<_>>();
+    if blocks.is_empty() {
+        stream.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
+    for (index, block) in blocks.iter().enumerate() {
+        let last = index + 1 == blocks.len();
+        let length = block.len() as u16;
+        stream.push(last as u8);
+        stream.extend(length.to_le_bytes());
+        stream.extend((!length).to_le_bytes());
+        stream.extend(*block);
+    }
+
+    // Adler-32 checksum of the uncompressed data
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    stream.extend(((b << 16) | a).to_be_bytes());
+
+    stream
+}
+
+/// Calculates the CRC-32 checksum used by PNG chunks.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+/// Returns the rows of a character in the 3 by 5 font used by `Text(`, with the leftmost pixel in
+/// the highest of the three bits. Characters without a glyph are drawn as a filled box.
+fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        '0' => [7, 5, 5, 5, 7],
+        '1' => [2, 6, 2, 2, 7],
+        '2' => [7, 1, 7, 4, 7],
+        '3' => [7, 1, 3, 1, 7],
+        '4' => [5, 5, 7, 1, 1],
+        '5' => [7, 4, 7, 1, 7],
+        '6' => [7, 4, 7, 5, 7],
+        '7' => [7, 1, 2, 2, 2],
+        '8' => [7, 5, 7, 5, 7],
+        '9' => [7, 5, 7, 1, 7],
+        'A' => [2, 5, 7, 5, 5],
+        'B' => [6, 5, 6, 5, 6],
+        'C' => [3, 4, 4, 4, 3],
+        'D' => [6, 5, 5, 5, 6],
+        'E' => [7, 4, 6, 4, 7],
+        'F' => [7, 4, 6, 4, 4],
+        'G' => [3, 4, 5, 5, 3],
+        'H' => [5, 5, 7, 5, 5],
+        'I' => [7, 2, 2, 2, 7],
+        'J' => [1, 1, 1, 5, 2],
+        'K' => [5, 5, 6, 5, 5],
+        'L' => [4, 4, 4, 4, 7],
+        'M' => [5, 7, 7, 5, 5],
+        'N' => [6, 5, 5, 5, 5],
+        'O' => [2, 5, 5, 5, 2],
+        'P' => [6, 5, 6, 4, 4],
+        'Q' => [2, 5, 5, 6, 3],
+        'R' => [6, 5, 6, 5, 5],
+        'S' => [3, 4, 2, 1, 6],
+        'T' => [7, 2, 2, 2, 2],
+        'U' => [5, 5, 5, 5, 7],
+        'V' => [5, 5, 5, 5, 2],
+        'W' => [5, 5, 7, 7, 5],
+        'X' => [5, 5, 2, 5, 5],
+        'Y' => [5, 5, 2, 2, 2],
+        'Z' => [7, 1, 2, 4, 7],
+        ' ' => [0, 0, 0, 0, 0],
+        '.' => [0, 0, 0, 0, 2],
+        ',' => [0, 0, 0, 2, 4],
+        ':' => [0, 2, 0, 2, 0],
+        '-' | '⁻' | '–' => [0, 0, 7, 0, 0],
+        '+' => [0, 2, 7, 2, 0],
+        '=' => [0, 7, 0, 7, 0],
+        '?' => [7, 1, 2, 0, 2],
+        '!' => [2, 2, 2, 0, 2],
+        '(' => [1, 2, 2, 2, 1],
+        ')' => [4, 2, 2, 2, 4],
+        '[' => [3, 2, 2, 2, 3],
+        ']' => [6, 2, 2, 2, 6],
+        '/' => [1, 1, 2, 4, 4],
+        '*' => [0, 5, 2, 5, 0],
+        '<' => [1, 2, 4, 2, 1],
+        '>' => [4, 2, 1, 2, 4],
+        '\'' => [2, 2, 0, 0, 0],
+        '"' => [5, 5, 0, 0, 0],
+        '^' => [2, 5, 0, 0, 0],
+        '_' => [0, 0, 0, 0, 7],
+        _ => [7, 7, 7, 7, 7],
+    }
+}
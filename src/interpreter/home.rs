@@ -0,0 +1,285 @@
+//! The `home` module models the calculator's home screen, which is where `Disp`, `Output(`,
+//! `Input` and the other text commands draw.
+//!
+//! The home screen is a grid of characters, 16 columns by 8 rows on monochrome models and 26 by
+//! 10 on color models. `Disp` writes whole lines at a cursor that moves down one row at a time,
+//! scrolling the screen up once the last row has been used, while `Output(` writes anywhere
+//! without moving the cursor or scrolling.
+
+use std::io::{self, Write};
+
+use crate::translation::models::Model;
+
+/// The character shown at the end of a line that is too long to display.
+const ELLIPSIS: char = '…';
+
+/// The calculator's home screen.
+///
+/// # Example
+///
+/// ```
+/// use tio2::interpreter::home::HomeScreen;
+///
+/// let mut home = HomeScreen::default();
+/// home.disp_text("HELLO");
+/// home.disp_number("42");
+/// home.output(1, 10, "WORLD");
+///
+/// assert_eq!(home.snapshot(), "HELLO    WORLD\n              42");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HomeScreen {
+    /// The number of columns
+    width: usize,
+    /// The number of rows
+    height: usize,
+    /// The characters on the screen, row by row
+    cells: Vec<Vec<char>>,
+    /// The row that the next line is displayed on, which is `height` once the last row is used
+    row: usize,
+}
+
+impl Default for HomeScreen {
+    /// Creates the 16 by 8 home screen of the monochrome models.
+    fn default() -> Self {
+        Self::new(16, 8)
+    }
+}
+
+impl HomeScreen {
+    /// Creates an empty home screen with the given number of columns and rows.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![vec![' '; width]; height],
+            row: 0,
+        }
+    }
+
+    /// Creates an empty home screen the size of a model's, which is 26 by 10 on the TI-84 Plus C
+    /// Silver Edition and TI-84 Plus CE and 16 by 8 on the others.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::{interpreter::home::HomeScreen, translation::models::Model};
+    ///
+    /// assert_eq!(HomeScreen::for_model(Model::TI84PlusCE).size(), (26, 10));
+    /// assert_eq!(HomeScreen::for_model(Model::TI84Plus).size(), (16, 8));
+    /// ```
+    pub fn for_model(model: Model) -> Self {
+        if model >= Model::TI84PlusCSE {
+            Self::new(26, 10)
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Returns the number of columns and rows.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Returns the row and column, starting at 1, where the next line will be displayed.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row.min(self.height - 1) + 1, 1)
+    }
+
+    /// Clears the screen and moves the cursor to the first row, like `ClrHome`.
+    pub fn clear(&mut self) {
+        for row in &mut self.cells {
+            row.fill(' ');
+        }
+        self.row = 0;
+    }
+
+    /// Displays text on its own line, aligned to the left, like `Disp` does with strings. Text
+    /// that doesn't fit is cut off with `…`.
+    pub fn disp_text(&mut self, text: &str) {
+        let line = self.fit(text);
+        let row = self.next_row();
+        self.write_row(row, 0, &line);
+    }
+
+    /// Displays a number on its own line, aligned to the right, like `Disp` does with numbers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::interpreter::home::HomeScreen;
+    ///
+    /// let mut home = HomeScreen::default();
+    /// for number in 1..=10 {
+    ///     home.disp_number(&number.to_string());
+    /// }
+    ///
+    /// // the first two lines have scrolled off of the top
+    /// assert_eq!(home.lines()[0], "               3");
+    /// assert_eq!(home.lines()[7], "              10");
+    /// ```
+    pub fn disp_number(&mut self, text: &str) {
+        let line = self.fit(text);
+        let length = line.chars().count();
+        let row = self.next_row();
+        self.write_row(row, self.width - length, &line);
+    }
+
+    /// Writes text starting at a row and column, both starting at 1, like `Output(`. Text that
+    /// reaches the end of a row continues at the start of the next one, and anything past the
+    /// last row is dropped. The cursor doesn't move.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the row or column is outside of the screen, in which case nothing is written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::interpreter::home::HomeScreen;
+    ///
+    /// let mut home = HomeScreen::default();
+    ///
+    /// assert!(home.output(1, 14, "WRAPPED"));
+    /// assert!(!home.output(9, 1, "OFF"));
+    /// assert_eq!(home.lines(), ["             WRA", "PPED", "", "", "", "", "", ""]);
+    /// ```
+    pub fn output(&mut self, row: usize, column: usize, text: &str) -> bool {
+        if !(1..=self.height).contains(&row) || !(1..=self.width).contains(&column) {
+            return false;
+        }
+
+        let start = (row - 1) * self.width + column - 1;
+        for (index, c) in text.chars().enumerate() {
+            let cell = start + index;
+            if cell >= self.width * self.height {
+                break;
+            }
+            self.cells[cell / self.width][cell % self.width] = c;
+        }
+
+        true
+    }
+
+    /// Starts an `Input` or `Prompt` line by displaying its prompt at the start of a new line.
+    ///
+    /// # Returns
+    ///
+    /// The row and column, starting at 1, where the response is typed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::interpreter::home::HomeScreen;
+    ///
+    /// let mut home = HomeScreen::default();
+    ///
+    /// assert_eq!(home.begin_input("A=?"), (1, 4));
+    /// home.end_input("A=?", "12");
+    ///
+    /// assert_eq!(home.snapshot(), "A=?12");
+    /// assert_eq!(home.cursor(), (2, 1));
+    /// ```
+    pub fn begin_input(&mut self, prompt: &str) -> (usize, usize) {
+        if self.row >= self.height {
+            self.scroll();
+        }
+        let row = self.row;
+        self.cells[row].fill(' ');
+        self.write_row(row, 0, prompt);
+
+        (row + 1, prompt.chars().count().min(self.width - 1) + 1)
+    }
+
+    /// Finishes an `Input` or `Prompt` line by writing the response after the prompt and moving
+    /// the cursor to the next line.
+    pub fn end_input(&mut self, prompt: &str, response: &str) {
+        let row = self.row.min(self.height - 1);
+        let column = prompt.chars().count().min(self.width - 1);
+        self.write_row(row, column, response);
+        self.row = row + 1;
+    }
+
+    /// Returns the text on each row, without trailing spaces.
+    pub fn lines(&self) -> Vec<String> {
+        self.cells
+            .iter()
+            .map(|row| row.iter().collect::<String>().trim_end().to_string())
+            .collect()
+    }
+
+    /// Returns the text on the screen as a single string, with rows separated by newlines and
+    /// trailing spaces and empty rows at the bottom removed. This is meant for comparing against
+    /// expected output in tests.
+    pub fn snapshot(&self) -> String {
+        self.lines().join("\n").trim_end().to_string()
+    }
+
+    /// Draws the screen in a terminal, inside of a border, replacing whatever was on the
+    /// terminal before.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn render(&self, out: &mut impl Write) -> io::Result<()> {
+        let border = "─".repeat(self.width);
+
+        write!(out, "\x1b[2J\x1b[H┌{}┐\r\n", border)?;
+        for row in &self.cells {
+            write!(out, "│{}│\r\n", row.iter().collect::<String>())?;
+        }
+        write!(out, "└{}┘\r\n", border)?;
+
+        out.flush()
+    }
+
+    /// Moves the terminal's cursor to a row and column of the screen, starting at 1, as drawn by
+    /// [`HomeScreen::render`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn move_cursor(&self, out: &mut impl Write, row: usize, column: usize) -> io::Result<()> {
+        // the border takes up the first row and column
+        write!(out, "\x1b[{};{}H", row + 1, column + 1)?;
+        out.flush()
+    }
+
+    /// Cuts text down to the width of the screen, ending it with `…` if it doesn't fit.
+    fn fit(&self, text: &str) -> String {
+        if text.chars().count() <= self.width {
+            return text.to_string();
+        }
+
+        let mut line: String = text.chars().take(self.width - 1).collect();
+        line.push(ELLIPSIS);
+        line
+    }
+
+    /// Returns the row for the next line, scrolling if every row has been used, and moves the
+    /// cursor past it.
+    fn next_row(&mut self) -> usize {
+        if self.row >= self.height {
+            self.scroll();
+        }
+        let row = self.row;
+        self.cells[row].fill(' ');
+        self.row += 1;
+
+        row
+    }
+
+    /// Scrolls everything up by one row, leaving the cursor on the empty last row.
+    fn scroll(&mut self) {
+        self.cells.remove(0);
+        self.cells.push(vec![' '; self.width]);
+        self.row = self.height - 1;
+    }
+
+    /// Writes text on a single row starting at a column, dropping anything past the end.
+    fn write_row(&mut self, row: usize, column: usize, text: &str) {
+        for (cell, c) in self.cells[row][column..].iter_mut().zip(text.chars()) {
+            *cell = c;
+        }
+    }
+}
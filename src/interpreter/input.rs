@@ -0,0 +1,350 @@
+//! The `input` module provides the keys and text that a running program reads with `getKey`,
+//! `Input`, `Prompt`, `Pause` and `Menu(`.
+//!
+//! Input comes from an [`Input`] source. [`StdinInput`] reads from whoever is at the terminal,
+//! while [`KeyScript`] replays a script of key presses and typed text so that interactive
+//! programs can be run without anyone at the keyboard.
+//!
+//! Keys are identified by their `getKey` codes, which are the row of the key on the keypad
+//! followed by its column, such as 105 for `ENTER` and 92 for `1`.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, BufRead, Write},
+};
+
+use log::warn;
+
+use crate::errors::TIError;
+
+/// The `getKey` code of the `ENTER` key.
+pub const ENTER: u8 = 105;
+
+/// The names that can be used for keys instead of their codes, in keypad order.
+const KEY_NAMES: &[(&str, u8)] = &[
+    ("y=", 11),
+    ("window", 12),
+    ("zoom", 13),
+    ("trace", 14),
+    ("graph", 15),
+    ("2nd", 21),
+    ("mode", 22),
+    ("del", 23),
+    ("left", 24),
+    ("up", 25),
+    ("right", 26),
+    ("alpha", 31),
+    ("xttn", 32),
+    ("stat", 33),
+    ("down", 34),
+    ("math", 41),
+    ("apps", 42),
+    ("prgm", 43),
+    ("vars", 44),
+    ("clear", 45),
+    ("enter", ENTER),
+];
+
+/// Returns the `getKey` code of a digit key.
+///
+/// # Example
+///
+/// ```
+/// use tio2::interpreter::input::digit_key;
+///
+/// assert_eq!(digit_key(1), Some(92));
+/// assert_eq!(digit_key(0), Some(102));
+/// assert_eq!(digit_key(10), None);
+/// ```
+pub fn digit_key(digit: u32) -> Option<u8> {
+    match digit {
+        0 => Some(102),
+        // the digits are laid out like a phone keypad, with 7 at the top
+        1..=9 => Some(92 - 10 * ((digit as u8 - 1) / 3) + (digit as u8 - 1) % 3),
+        _ => None,
+    }
+}
+
+/// Parses a key, which is either a `getKey` code or a name such as `enter` or `up`.
+///
+/// # Example
+///
+/// ```
+/// use tio2::interpreter::input::parse_key;
+///
+/// assert_eq!(parse_key("105"), Some(105));
+/// assert_eq!(parse_key("ENTER"), Some(105));
+/// assert_eq!(parse_key("up"), Some(25));
+/// assert_eq!(parse_key("106"), None);
+/// ```
+pub fn parse_key(text: &str) -> Option<u8> {
+    let text = text.to_lowercase();
+    if let Some((_, code)) = KEY_NAMES.iter().find(|(name, _)| *name == text) {
+        return Some(*code);
+    }
+
+    // the row of the key followed by its column, where only the arrow keys are in column 6
+    match text.parse::<u8>() {
+        Ok(
+            code @ (11..=15
+            | 21..=26
+            | 31..=34
+            | 41..=45
+            | 51..=55
+            | 61..=65
+            | 71..=75
+            | 81..=85
+            | 91..=95
+            | 102..=105),
+        ) => Some(code),
+        _ => None,
+    }
+}
+
+/// A source of key presses and typed text for a running program.
+pub trait Input: fmt::Debug {
+    /// Returns the code of a key that has been pressed, or 0 if no key is pressed, like
+    /// `getKey`. This doesn't wait for a key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Break`] if there is no more input.
+    fn get_key(&mut self) -> Result<u8, TIError>;
+
+    /// Waits for a key to be pressed and returns its code, for `Pause` and `Menu(`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Break`] if there is no more input.
+    fn wait_for_key(&mut self) -> Result<u8, TIError>;
+
+    /// Reads a line of text typed in response to `Input` or `Prompt`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Break`] if there is no more input.
+    fn read_line(&mut self) -> Result<String, TIError>;
+
+    /// Returns whether the input is typed by someone at a terminal, who can see what they type.
+    /// Input that isn't typed is shown by the interpreter instead.
+    fn is_typed(&self) -> bool {
+        true
+    }
+
+    /// Returns a copy of this input source, so that [`Interpreter`](super::Interpreter)s can be
+    /// cloned.
+    fn clone_box(&self) -> Box<dyn Input>;
+}
+
+impl Clone for Box<dyn Input> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Reads input from stdin, a line at a time.
+///
+/// Since lines can only be read once `ENTER` is pressed, `getKey` never sees a key. Waiting for a
+/// key reads a line, which is `ENTER` if it is empty, a digit key if it is a single digit, and
+/// otherwise a key code or name as read by [`parse_key`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdinInput;
+
+impl Input for StdinInput {
+    fn get_key(&mut self) -> Result<u8, TIError> {
+        Ok(0)
+    }
+
+    fn wait_for_key(&mut self) -> Result<u8, TIError> {
+        io::stdout().flush().map_err(|_| TIError::Break)?;
+        let line = self.read_line()?;
+        let line = line.trim();
+
+        let mut chars = line.chars();
+        let key = match (chars.next(), chars.next()) {
+            (None, _) => Some(ENTER),
+            (Some(c), None) => c.to_digit(10).and_then(digit_key),
+            _ => None,
+        };
+
+        Ok(key.or_else(|| parse_key(line)).unwrap_or(ENTER))
+    }
+
+    fn read_line(&mut self) -> Result<String, TIError> {
+        let mut line = String::new();
+        match io::stdin().lock().read_line(&mut line) {
+            // end of input means there's nobody left to answer
+            Ok(0) | Err(_) => Err(TIError::Break),
+            Ok(_) => Ok(line.trim_end_matches(['\r', '\n']).to_string()),
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn Input> {
+        Box::new(*self)
+    }
+}
+
+/// Something that happens in a [`KeyScript`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyEvent {
+    /// A key is pressed
+    Key(u8),
+    /// `getKey` finds that no key is pressed this many times
+    Wait(usize),
+    /// A line of text is typed and `ENTER` is pressed
+    Type(String),
+}
+
+/// Replays a script of key presses and typed text.
+///
+/// Scripts have one command on each line, and blank lines and lines starting with `#` are
+/// ignored:
+///
+/// * `key 105` or `key enter` presses keys, given as `getKey` codes or names, one after another.
+/// * `wait 3` makes the next three `getKey`s find that no key is pressed.
+/// * `type 42` types the rest of the line into the next `Input` or `Prompt`.
+///
+/// Each `getKey`, `Pause` or `Menu(` uses up the next key, and each `Input` or `Prompt` the next
+/// typed text. Reading past the end of the script, or reading text when the next event is a key
+/// or the other way around, stops the program with [`TIError::Break`].
+///
+/// # Example
+///
+/// ```
+/// use tio2::interpreter::input::{Input, KeyScript};
+///
+/// let mut script: KeyScript = "type 12\nwait 1\nkey 25 enter".parse().unwrap();
+///
+/// assert_eq!(script.read_line().unwrap(), "12");
+/// assert_eq!(script.get_key().unwrap(), 0);
+/// assert_eq!(script.get_key().unwrap(), 25);
+/// assert_eq!(script.wait_for_key().unwrap(), 105);
+/// assert!(script.get_key().is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyScript {
+    /// The events that haven't happened yet
+    events: VecDeque<KeyEvent>,
+}
+
+impl KeyScript {
+    /// Creates a script that replays the given events.
+    pub fn new(events: impl IntoIterator<Item = KeyEvent>) -> Self {
+        Self {
+            events: events.into_iter().collect(),
+        }
+    }
+
+    /// Returns the events that haven't happened yet.
+    pub fn remaining(&self) -> impl Iterator<Item = &KeyEvent> {
+        self.events.iter()
+    }
+
+    /// Stops the program because the script doesn't match what it is reading.
+    fn mismatch(&self, expected: &str) -> TIError {
+        match self.events.front() {
+            Some(event) => warn!("Expected {} in the key script, found {:?}", expected, event),
+            None => warn!("Expected {} in the key script, but it has ended", expected),
+        }
+
+        TIError::Break
+    }
+}
+
+impl std::str::FromStr for KeyScript {
+    type Err = anyhow::Error;
+
+    /// Parses a script in the format described in [`KeyScript`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut events = VecDeque::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let (command, rest) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+            let error = |message: String| {
+                anyhow::Error::msg(format!("Line {} of the key script {}", index + 1, message))
+            };
+
+            match command {
+                "key" => {
+                    for key in rest.split_whitespace() {
+                        let code = parse_key(key)
+                            .ok_or_else(|| error(format!("has an unknown key {:?}", key)))?;
+                        events.push_back(KeyEvent::Key(code));
+                    }
+                }
+                "wait" => {
+                    let count = rest
+                        .trim()
+                        .parse()
+                        .map_err(|_| error(format!("has an invalid wait count {:?}", rest)))?;
+                    if count > 0 {
+                        events.push_back(KeyEvent::Wait(count));
+                    }
+                }
+                // the text is kept exactly, apart from the line ending
+                "type" => events.push_back(KeyEvent::Type(rest.trim_end_matches('\r').into())),
+                _ => {
+                    return Err(error(format!(
+                        "starts with {:?}, expected key, wait or type",
+                        command
+                    )))
+                }
+            }
+        }
+
+        Ok(Self { events })
+    }
+}
+
+impl Input for KeyScript {
+    fn get_key(&mut self) -> Result<u8, TIError> {
+        match self.events.front_mut() {
+            Some(KeyEvent::Key(code)) => {
+                let code = *code;
+                self.events.pop_front();
+                Ok(code)
+            }
+            Some(KeyEvent::Wait(count)) => {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    self.events.pop_front();
+                }
+                Ok(0)
+            }
+            _ => Err(self.mismatch("a key")),
+        }
+    }
+
+    fn wait_for_key(&mut self) -> Result<u8, TIError> {
+        // waiting for a key doesn't care how long it takes
+        while let Some(KeyEvent::Wait(_)) = self.events.front() {
+            self.events.pop_front();
+        }
+
+        self.get_key()
+    }
+
+    fn read_line(&mut self) -> Result<String, TIError> {
+        match self.events.front() {
+            Some(KeyEvent::Type(_)) => match self.events.pop_front() {
+                Some(KeyEvent::Type(text)) => Ok(text),
+                _ => Err(TIError::Break),
+            },
+            _ => Err(self.mismatch("typed text")),
+        }
+    }
+
+    fn is_typed(&self) -> bool {
+        false
+    }
+
+    fn clone_box(&self) -> Box<dyn Input> {
+        Box::new(self.clone())
+    }
+}
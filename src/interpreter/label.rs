@@ -1,5 +1,7 @@
 //! This file contains structs and functions used for handling the Lbl token.
 
+use log::debug;
+
 use crate::{errors, utils};
 
 /// Represents a label in the TI-BASIC bytecode format.
@@ -31,15 +33,14 @@ pub struct Lbl {
 /// ```
 /// use tio2::{
 ///     interpreter::{label::Lbl, Interpreter},
-///     translation::common::TIFile,
+///     translation::common::{TIFile, VarEntry},
 /// };
 ///
 /// // Basic infinite loop program
-/// let bytecode = TIFile {
-///     header: [0; 74],
-///     data: vec![0xd6, 0x41, 0x3f, 0xde, 0x2a, 0x41, 0x2a, 0x3f, 0xd7, 0x41],
-///     footer: vec![],
-/// };
+/// let bytecode = TIFile::new(vec![VarEntry::program(
+///     "LOOP",
+///     &[0xd6, 0x41, 0x3f, 0xde, 0x2a, 0x41, 0x2a, 0x3f, 0xd7, 0x41],
+/// )]);
 /// let interpreter = Interpreter::new(&bytecode).unwrap();
 ///
 /// assert_eq!(
@@ -50,14 +51,22 @@ pub struct Lbl {
 ///     }]
 /// );
 /// ```
-pub fn find_labels(bytes_list: &Vec<u8>) -> Result<Vec<Lbl>, anyhow::Error> {
-    let lbl_addresses: Vec<usize> = bytes_list
-        .iter()
-        .enumerate()
+pub fn find_labels(bytes_list: &[u8]) -> Result<Vec<Lbl>, anyhow::Error> {
+    let mut lbl_addresses: Vec<usize> = Vec::new();
+
+    let mut index = 0;
+    while index < bytes_list.len() {
         // 0xD6 is the hex value for "Lbl"
-        .filter(|(_, &r)| r == 0xD6)
-        .map(|(index, _)| index)
-        .collect();
+        if bytes_list[index] == 0xD6 {
+            lbl_addresses.push(index);
+        }
+
+        // skip over the second byte of two-byte tokens so that it isn't mistaken for a Lbl
+        if utils::DOUBLE_BYTE_TOKEN_IDENT.contains(&bytes_list[index]) {
+            index += 1;
+        }
+        index += 1;
+    }
 
     // TODO: TI-BASIC gives duplicate labels to the first occurance
     let mut lbl_map: Vec<Lbl> = Vec::new();
@@ -81,7 +90,7 @@ pub fn find_labels(bytes_list: &Vec<u8>) -> Result<Vec<Lbl>, anyhow::Error> {
         });
     }
 
-    println!("{:?}", lbl_map);
+    debug!("Found labels: {:?}", lbl_map);
 
     Ok(lbl_map)
 }
@@ -125,7 +134,7 @@ pub fn find_labels(bytes_list: &Vec<u8>) -> Result<Vec<Lbl>, anyhow::Error> {
 /// This function assumes that label names in the bytecode consist of two bytes, where the second
 /// byte can be 0x00 to indicate the end of the label name.
 pub fn get_label_name(
-    bytes_list: &Vec<u8>,
+    bytes_list: &[u8],
     lbl_memory_address: usize,
 ) -> Result<[u8; 2], anyhow::Error> {
     // test that lbl_memory_address is in range
@@ -0,0 +1,133 @@
+//! The `menu` module models the screen shown by `Menu(`, which lists up to seven options under a
+//! title and waits for one of them to be chosen.
+//!
+//! An option is chosen by pressing its number, or by moving to it with the up and down arrows and
+//! pressing `ENTER`. The interpreter then jumps to the `Lbl` that the option names.
+
+use super::{
+    home::HomeScreen,
+    input::{digit_key, ENTER},
+};
+use crate::errors::TIError;
+
+/// The most options that a menu can have.
+pub const MAX_OPTIONS: usize = 7;
+
+/// The `getKey` codes of the up and down arrows.
+const UP: u8 = 25;
+const DOWN: u8 = 34;
+
+/// The screen shown by `Menu(`.
+///
+/// # Example
+///
+/// ```
+/// use tio2::interpreter::{home::HomeScreen, menu::Menu};
+///
+/// let mut menu = Menu::new("GAME", vec!["PLAY".to_string(), "QUIT".to_string()]).unwrap();
+///
+/// // down arrow, then ENTER
+/// assert_eq!(menu.press(34), None);
+/// assert_eq!(menu.press(105), Some(1));
+///
+/// assert_eq!(menu.screen(HomeScreen::default().size()).snapshot(), "GAME\n1:PLAY\n2▸QUIT");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Menu {
+    /// The title shown on the first row
+    title: String,
+    /// The text of each option
+    options: Vec<String>,
+    /// The index of the highlighted option
+    selected: usize,
+}
+
+impl Menu {
+    /// Creates a menu with the first option highlighted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Argument`] if there are no options or more than [`MAX_OPTIONS`].
+    pub fn new(title: impl ToString, options: Vec<String>) -> Result<Self, TIError> {
+        if options.is_empty() || options.len() > MAX_OPTIONS {
+            return Err(TIError::Argument);
+        }
+
+        Ok(Self {
+            title: title.to_string(),
+            options,
+            selected: 0,
+        })
+    }
+
+    /// Returns the index of the highlighted option, starting at 0.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Handles a key press. The arrows move the highlight, wrapping around at the ends, `ENTER`
+    /// chooses the highlighted option and a number key chooses the option with that number.
+    /// Every other key is ignored.
+    ///
+    /// # Returns
+    ///
+    /// The index of the option that was chosen, starting at 0, or [`None`] if the key didn't
+    /// choose one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::interpreter::menu::Menu;
+    ///
+    /// let options = ["A", "B", "C"].map(String::from).to_vec();
+    /// let mut menu = Menu::new("", options).unwrap();
+    ///
+    /// // up wraps around to the last option
+    /// assert_eq!(menu.press(25), None);
+    /// assert_eq!(menu.selected(), 2);
+    /// // 2, then 9, which isn't an option
+    /// assert_eq!(menu.press(93), Some(1));
+    /// assert_eq!(menu.press(74), None);
+    /// ```
+    pub fn press(&mut self, key: u8) -> Option<usize> {
+        let count = self.options.len();
+
+        match key {
+            UP => self.selected = (self.selected + count - 1) % count,
+            DOWN => self.selected = (self.selected + 1) % count,
+            ENTER => return Some(self.selected),
+            _ => {
+                return (1..=count as u32)
+                    .find(|&number| digit_key(number) == Some(key))
+                    .map(|number| number as usize - 1)
+            }
+        }
+
+        None
+    }
+
+    /// Returns the lines of the menu, with the title first and then each option after its
+    /// number. The highlighted option has `▸` after its number instead of `:`, since text can't
+    /// be shown inverted.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![self.title.clone()];
+        lines.extend(self.options.iter().enumerate().map(|(index, option)| {
+            let separator = if index == self.selected { '▸' } else { ':' };
+            format!("{}{}{}", index + 1, separator, option)
+        }));
+
+        lines
+    }
+
+    /// Draws the menu on an empty home screen with the given number of columns and rows. Text
+    /// that doesn't fit on a row is cut off.
+    pub fn screen(&self, (width, height): (usize, usize)) -> HomeScreen {
+        let mut screen = HomeScreen::new(width, height);
+        for (row, line) in self.lines().iter().enumerate().take(height) {
+            let line: String = line.chars().take(width).collect();
+            screen.output(row + 1, 1, &line);
+        }
+
+        screen
+    }
+}
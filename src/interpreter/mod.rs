@@ -0,0 +1,1665 @@
+//! The `interpreter` module runs TI-BASIC programs without needing a calculator.
+//!
+//! The primary struct, [`Interpreter`], parses the data section of a [`TIFile`] into statements
+//! with [`Interpreter::parse_bytes`] and then executes them with [`Interpreter::interpret_bytes`].
+
+pub mod graph;
+pub mod home;
+pub mod input;
+pub mod label;
+pub mod menu;
+pub mod variables;
+
+use std::{
+    fmt,
+    io::{self, IsTerminal, Write},
+};
+
+use log::debug;
+
+use self::{
+    graph::{GraphScreen, Pen, BLACK},
+    home::HomeScreen,
+    input::{Input, StdinInput, ENTER},
+    label::Lbl,
+    menu::Menu,
+    variables::Variables,
+};
+use crate::{
+    errors::{ParseError, TIError},
+    float::{Complex, TIFloat},
+    translation::{
+        asm::{self, Payload},
+        common::{TIFile, VarEntry},
+        compile,
+        parser::{self, BinaryOp, Expr, PostfixOp, Statement, StatementKind, UnaryOp, Variable},
+        tokens::{Byte, BYTE_TOKENS},
+    },
+};
+
+/// A value produced by evaluating an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// A real number
+    Real(TIFloat),
+    /// A complex number, whose imaginary part isn't 0
+    Complex(Complex),
+    /// A string
+    Str(String),
+    /// A list of real numbers
+    List(Vec<TIFloat>),
+    /// A matrix of real numbers, row by row
+    Matrix(Vec<Vec<TIFloat>>),
+}
+
+impl Value {
+    /// Returns the real number held by this value, or [`TIError::DataType`] if it is anything
+    /// else.
+    pub fn as_real(&self) -> Result<TIFloat, TIError> {
+        match self {
+            Value::Real(v) => Ok(*v),
+            _ => Err(TIError::DataType),
+        }
+    }
+
+    /// Returns the number held by this value as a complex number, or [`TIError::DataType`] if it
+    /// isn't a number.
+    pub fn as_complex(&self) -> Result<Complex, TIError> {
+        match self {
+            Value::Real(v) => Ok(Complex::from(*v)),
+            Value::Complex(v) => Ok(*v),
+            _ => Err(TIError::DataType),
+        }
+    }
+
+    /// Creates a value from a complex number, which is a real number if the imaginary part is 0.
+    pub fn from_complex(value: Complex) -> Value {
+        if value.is_real() {
+            Value::Real(value.re)
+        } else {
+            Value::Complex(value)
+        }
+    }
+
+    /// Returns the dimensions of a list or matrix, like `dim(`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::DataType`] if the value isn't a list or matrix.
+    pub fn dim(&self) -> Result<Value, TIError> {
+        match self {
+            Value::List(list) => Ok(Value::Real(TIFloat::from(list.len() as i64))),
+            Value::Matrix(rows) => Ok(Value::List(vec![
+                TIFloat::from(rows.len() as i64),
+                TIFloat::from(rows.first().map_or(0, Vec::len) as i64),
+            ])),
+            _ => Err(TIError::DataType),
+        }
+    }
+
+    /// Rounds numbers to the precision that is kept when storing them into a variable.
+    fn into_stored(self) -> Value {
+        match self {
+            Value::Real(v) => Value::Real(v.to_stored()),
+            Value::Complex(v) => Value::from_complex(v.to_stored()),
+            Value::List(list) => Value::List(list.iter().map(TIFloat::to_stored).collect()),
+            Value::Matrix(rows) => Value::Matrix(
+                rows.iter()
+                    .map(|row| row.iter().map(TIFloat::to_stored).collect())
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    /// Formats the value the way that the calculator displays it, with lists such as `{1 2 3}`
+    /// and matrices such as `[[1 2][3 4]]`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let join = |numbers: &[TIFloat]| {
+            numbers
+                .iter()
+                .map(TIFloat::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        match self {
+            Value::Real(v) => write!(f, "{}", v),
+            Value::Complex(v) => write!(f, "{}", v),
+            Value::Str(s) => write!(f, "{}", s),
+            Value::List(list) => write!(f, "{{{}}}", join(list)),
+            Value::Matrix(rows) => {
+                write!(f, "[")?;
+                for row in rows {
+                    write!(f, "[{}]", join(row))?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// An open control flow block that is waiting for its `End`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Block {
+    /// An `If`/`Then` block. Reaching `Else` while inside of it skips to the `End`.
+    If,
+    /// A `While` loop, storing the statement index of the `While`
+    While(usize),
+    /// A `Repeat` loop, storing the statement index of the `Repeat`
+    Repeat(usize),
+    /// A `For(` loop, storing the loop variable, bounds, and the statement index of the loop body
+    For {
+        variable: u8,
+        end: TIFloat,
+        step: TIFloat,
+        body: usize,
+    },
+}
+
+/// How the home screen is shown while a program runs.
+///
+/// # Example
+///
+/// ```
+/// use tio2::{
+///     interpreter::{Console, Interpreter},
+///     translation::{
+///         common::{TIFile, VarEntry},
+///         compile::compile_to_bytecode,
+///     },
+/// };
+///
+/// let data = compile_to_bytecode(vec!["Disp \"HI\",5", "Output(1,10,\"X\")"]).unwrap();
+/// let program = TIFile::new(vec![VarEntry::program("HOME", &data)]);
+///
+/// let mut interpreter = Interpreter::new(&program).unwrap();
+/// interpreter.console = Console::Hidden;
+/// interpreter.interpret_bytes(None).unwrap();
+///
+/// assert_eq!(interpreter.home.snapshot(), "HI       X\n               5");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Console {
+    /// Redraw the whole home screen in the terminal whenever it changes
+    Terminal,
+    /// Print each line as it is displayed, which works when the output isn't a terminal
+    Lines,
+    /// Don't show anything, so the home screen can only be read through [`Interpreter::home`]
+    Hidden,
+}
+
+/// The variable `X`, which `Shade(` and equations are evaluated with.
+const X: Variable = Variable::Real(0x58);
+
+/// The multipliers and moduli of the two generators that `rand` combines, which are L'Ecuyer's.
+const RAND_MULTIPLIERS: [i64; 2] = [40014, 40692];
+const RAND_MODULI: [i64; 2] = [2147483563, 2147483399];
+
+/// What the interpreter should do after a statement has been executed.
+enum Flow {
+    Continue,
+    Stop,
+}
+
+/// Runs the data section of a TI-BASIC program.
+///
+/// # Example
+///
+/// ```
+/// use tio2::{
+///     float::TIFloat,
+///     interpreter::{Interpreter, Value},
+///     translation::{
+///         common::{TIFile, VarEntry},
+///         parser::Variable,
+///     },
+/// };
+///
+/// // 5→A
+/// // A*2→B
+/// let program = TIFile::new(vec![VarEntry::program(
+///     "DOUBLE",
+///     &[0x35, 0x04, 0x41, 0x3F, 0x41, 0x82, 0x32, 0x04, 0x42],
+/// )]);
+///
+/// let mut interpreter = Interpreter::new(&program).unwrap();
+/// interpreter.parse_bytes().unwrap();
+/// interpreter.interpret_bytes(None).unwrap();
+///
+/// assert_eq!(
+///     interpreter.variable(&Variable::Real(0x42)),
+///     Ok(Value::Real(TIFloat::from(10)))
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Interpreter {
+    /// The data section of the program being interpreted
+    pub data: Vec<u8>,
+    /// All of the labels defined in the program
+    pub labels: Vec<Lbl>,
+    /// The statements of the program. This is empty until [`Interpreter::parse_bytes`] is
+    /// called.
+    pub statements: Vec<Statement>,
+    /// The values of every variable except the window settings, which are in
+    /// [`GraphScreen::window`]. Use [`Interpreter::variable`] to read any variable.
+    pub variables: Variables,
+    /// The home screen that text is displayed on
+    pub home: HomeScreen,
+    /// The graph screen that drawing commands draw on
+    pub graph: GraphScreen,
+    /// Where keys and typed text come from. This is [`StdinInput`] unless a
+    /// [`KeyScript`](input::KeyScript) is used instead.
+    pub input: Box<dyn Input>,
+    /// How the home and graph screens are shown. This is [`Console::Terminal`] if stdout is a terminal and
+    /// [`Console::Lines`] otherwise.
+    pub console: Console,
+    /// The index of the statement currently being executed
+    position: usize,
+    /// The control flow blocks that are currently open
+    blocks: Vec<Block>,
+    /// The two seeds of the random number generator used by `rand`
+    rand_state: [i64; 2],
+}
+
+impl Interpreter {
+    /// Creates a new [`Interpreter`] for the given program.
+    ///
+    /// # Arguments
+    ///
+    /// * `program` - The [`TIFile`] to interpret. If it contains more than one program, the first
+    ///   one is interpreted.
+    ///
+    /// # Returns
+    ///
+    /// A [`Result`] containing the [`Interpreter`], or an error if the file doesn't contain a
+    /// program, the program is an assembly program, or the labels in the program could not be
+    /// read.
+    pub fn new(program: &TIFile) -> Result<Self, anyhow::Error> {
+        let data = match program.programs().next().and_then(VarEntry::program_tokens) {
+            Some(tokens) => tokens.to_vec(),
+            None => return Err(anyhow::Error::msg("File does not contain a program")),
+        };
+        if let Payload::Unsquished(target) | Payload::Squished(target) = asm::detect(&data) {
+            return Err(anyhow::Error::msg(format!(
+                "The program is an {} assembly program, which can't be interpreted",
+                target
+            )));
+        }
+        let labels = label::find_labels(&data)?;
+
+        let mut interpreter = Self {
+            data,
+            labels,
+            statements: Vec::new(),
+            variables: Variables::default(),
+            home: HomeScreen::default(),
+            graph: GraphScreen::default(),
+            input: Box::new(StdinInput),
+            console: match io::stdout().is_terminal() {
+                true => Console::Terminal,
+                false => Console::Lines,
+            },
+            position: 0,
+            blocks: Vec::new(),
+            rand_state: [0; 2],
+        };
+        // the seed that the calculator has after its memory is reset, so runs can be repeated
+        interpreter.seed_random(0);
+
+        Ok(interpreter)
+    }
+
+    /// Seeds the random number generator used by `rand` and `randInt(`, like `seed→rand`. The
+    /// interpreter starts with a seed of 0, like the calculator does after its memory is reset,
+    /// so the same numbers are generated every time a program is run.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::{
+    ///     float::TIFloat,
+    ///     interpreter::{Interpreter, Value},
+    ///     translation::{
+    ///         common::{TIFile, VarEntry},
+    ///         parser::Variable,
+    ///     },
+    /// };
+    ///
+    /// // rand→A
+    /// let program = TIFile::new(vec![VarEntry::program("RANDOM", &[0xAB, 0x04, 0x41])]);
+    /// let a = Variable::Real(0x41);
+    ///
+    /// let mut interpreter = Interpreter::new(&program).unwrap();
+    /// interpreter.parse_bytes().unwrap();
+    /// interpreter.interpret_bytes(None).unwrap();
+    /// let first = interpreter.variable(&a).unwrap();
+    ///
+    /// // the first number after a memory reset
+    /// assert_eq!(first.to_string(), ".9435974025");
+    ///
+    /// interpreter.seed_random(0);
+    /// interpreter.interpret_bytes(None).unwrap();
+    /// assert_eq!(interpreter.variable(&a).unwrap(), first);
+    /// ```
+    pub fn seed_random(&mut self, seed: u64) {
+        self.rand_state = match seed {
+            0 => [12345, 67890],
+            _ => [
+                (RAND_MULTIPLIERS[0] * (seed % RAND_MODULI[0] as u64) as i64) % RAND_MODULI[0],
+                (seed % RAND_MODULI[1] as u64) as i64,
+            ],
+        };
+    }
+
+    /// Parses the data section of the program into [`Statement`]s.
+    ///
+    /// # Returns
+    ///
+    /// A [`Result`] which is a [`ParseError`] if the program isn't valid TI-BASIC.
+    pub fn parse_bytes(&mut self) -> Result<(), ParseError> {
+        self.statements = parser::parse(&self.data)?;
+        debug!("Parsed {} statements", self.statements.len());
+        Ok(())
+    }
+
+    /// Runs the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The byte offset in the data section to start executing from. If [`None`],
+    ///   execution starts at the beginning of the program.
+    ///
+    /// # Returns
+    ///
+    /// A [`Result`] which is an error containing a [`TIError`] if the program errored, or a
+    /// [`ParseError`] if it couldn't be parsed.
+    pub fn interpret_bytes(&mut self, start: Option<usize>) -> Result<(), anyhow::Error> {
+        if self.statements.is_empty() {
+            self.parse_bytes()?;
+        }
+
+        self.blocks.clear();
+        let start = start.unwrap_or(0);
+        self.position = self.statements.partition_point(|s| s.offset < start);
+
+        while self.position < self.statements.len() {
+            let statement = self.position;
+            match self.execute_statement() {
+                Ok(Flow::Continue) => (),
+                Ok(Flow::Stop) => break,
+                Err(e) => {
+                    let offset = self.statements[statement].offset;
+                    return Err(anyhow::Error::new(e).context(format!("Error at byte {}", offset)));
+                }
+            }
+        }
+
+        io::stdout().flush()?;
+        Ok(())
+    }
+
+    /// Skips forward past the `End` that closes the current block.
+    ///
+    /// # Arguments
+    ///
+    /// * `stop_at_else` - If `true`, stop after an `Else` belonging to this block and start
+    ///   executing it as a new `If` block.
+    fn skip_block(&mut self, stop_at_else: bool) {
+        let mut depth = 0;
+        while let Some(statement) = self.statements.get(self.position) {
+            self.position += 1;
+            match statement.kind {
+                StatementKind::Then
+                | StatementKind::While(_)
+                | StatementKind::Repeat(_)
+                | StatementKind::For { .. } => depth += 1,
+                StatementKind::Else if depth == 0 && stop_at_else => {
+                    self.blocks.push(Block::If);
+                    return;
+                }
+                StatementKind::End => {
+                    if depth == 0 {
+                        return;
+                    }
+                    depth -= 1;
+                }
+                _ => (),
+            }
+        }
+
+        // running off of the end of the program just ends it
+    }
+
+    /// Executes the statement at the current position.
+    fn execute_statement(&mut self) -> Result<Flow, TIError> {
+        let index = self.position;
+        let kind = match self.statements.get(index) {
+            Some(statement) => statement.kind.clone(),
+            None => return Ok(Flow::Stop),
+        };
+        self.position += 1;
+
+        match kind {
+            StatementKind::Expr(expr) => self.variables.ans = self.eval(&expr)?.into_stored(),
+            StatementKind::Store { value, target } => {
+                let value = self.eval(&value)?;
+                self.store(&target, value.clone())?;
+                self.variables.ans = value.into_stored();
+            }
+            StatementKind::If(condition) => self.execute_if(&condition)?,
+            // Then without If
+            StatementKind::Then => return Err(TIError::Syntax),
+            // Else is only reached at the end of a Then branch, so skip the rest of the block
+            StatementKind::Else => match self.blocks.pop() {
+                Some(Block::If) => self.skip_block(false),
+                _ => return Err(TIError::Syntax),
+            },
+            StatementKind::While(condition) => {
+                if self.eval_condition(&condition)? {
+                    self.blocks.push(Block::While(index));
+                } else {
+                    self.skip_block(false);
+                }
+            }
+            // the condition of a Repeat loop is only checked at the End
+            StatementKind::Repeat(_) => self.blocks.push(Block::Repeat(index)),
+            StatementKind::For {
+                variable,
+                start,
+                end,
+                step,
+            } => self.execute_for(&variable, &start, &end, step.as_ref())?,
+            StatementKind::End => self.execute_end()?,
+            StatementKind::Stop | StatementKind::Return => return Ok(Flow::Stop),
+            StatementKind::Lbl(_) => (),
+            StatementKind::Goto(name) => self.execute_goto(&name)?,
+            StatementKind::Pause(value) => {
+                if let Some(value) = value {
+                    let value = self.eval(&value)?;
+                    self.display(&value)?;
+                }
+                // Pause only ends when ENTER is pressed
+                while self.input.wait_for_key()? != ENTER {}
+            }
+            StatementKind::Input { prompt, variable } => {
+                let variable = variable
+                    .ok_or_else(|| TIError::Unsupported("Input without a variable".to_string()))?;
+                let prompt = match prompt {
+                    Some(prompt) => self.eval(&prompt)?.to_string(),
+                    None => "?".to_string(),
+                };
+                self.input_into(&prompt, &variable)?;
+            }
+            StatementKind::Prompt(variables) => {
+                for variable in variables {
+                    let name = match &variable {
+                        Expr::Variable(Variable::Real(v)) => label_to_string([*v, 0]),
+                        Expr::Variable(Variable::Str(v)) => format!("Str{}", (v + 1) % 10),
+                        _ => return Err(TIError::Unsupported("Prompt".to_string())),
+                    };
+                    self.input_into(&format!("{}=?", name), &variable)?;
+                }
+            }
+            // Disp with no arguments just shows the home screen
+            StatementKind::Disp(values) => {
+                for value in values {
+                    let value = self.eval(&value)?;
+                    self.display(&value)?;
+                }
+            }
+            StatementKind::Output { row, column, value } => {
+                let row = self.eval(&row)?.as_real()?;
+                let column = self.eval(&column)?.as_real()?;
+                let value = self.eval(&value)?;
+
+                let (row, column) = match (row.to_i64(), column.to_i64()) {
+                    (Some(row @ 1..), Some(column @ 1..)) => (row as usize, column as usize),
+                    _ => return Err(TIError::Domain),
+                };
+
+                let text = value.to_string();
+                if !self.home.output(row, column, &text) {
+                    return Err(TIError::Domain);
+                }
+
+                match self.console {
+                    Console::Terminal => self.show()?,
+                    Console::Lines => println!("{}", text),
+                    Console::Hidden => (),
+                }
+            }
+            StatementKind::ClrHome => {
+                self.home.clear();
+                if self.console == Console::Terminal {
+                    self.show()?;
+                }
+            }
+            StatementKind::Menu { title, options } => self.execute_menu(&title, &options)?,
+            StatementKind::Program(name) => {
+                return Err(TIError::Unsupported(format!("prgm{}", name)))
+            }
+            // ClrDraw, Text(, Line( to Horizontal and DispGraph
+            StatementKind::Command {
+                command: command @ Byte::Single(0x85 | 0x93 | 0x9C..=0xA6 | 0xDF),
+                args,
+            } => self.execute_draw(command, &args)?,
+            StatementKind::Command { command, .. } => {
+                return Err(TIError::Unsupported(token_name(command)))
+            }
+        }
+
+        Ok(Flow::Continue)
+    }
+
+    /// Executes an `If` statement, including a following `Then` if there is one.
+    fn execute_if(&mut self, condition: &Expr) -> Result<(), TIError> {
+        let condition = self.eval_condition(condition)?;
+
+        let then = self
+            .statements
+            .get(self.position)
+            .is_some_and(|s| s.kind == StatementKind::Then);
+
+        if then {
+            self.position += 1;
+            if condition {
+                self.blocks.push(Block::If);
+            } else {
+                self.skip_block(true);
+            }
+        } else if !condition {
+            // a single line If skips the next statement when false
+            self.position += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Executes a `For(` statement.
+    fn execute_for(
+        &mut self,
+        variable: &Variable,
+        start: &Expr,
+        end: &Expr,
+        step: Option<&Expr>,
+    ) -> Result<(), TIError> {
+        let variable = match variable {
+            Variable::Real(v) => *v,
+            _ => return Err(TIError::Syntax),
+        };
+
+        let start = self.eval(start)?.as_real()?.to_stored();
+        let end = self.eval(end)?.as_real()?;
+        let step = match step {
+            Some(step) => self.eval(step)?.as_real()?,
+            None => TIFloat::ONE,
+        };
+
+        self.variables
+            .set(&Variable::Real(variable), Value::Real(start))?;
+        if for_continues(start, end, step) {
+            self.blocks.push(Block::For {
+                variable,
+                end,
+                step,
+                body: self.position,
+            });
+        } else {
+            self.skip_block(false);
+        }
+
+        Ok(())
+    }
+
+    /// Executes an `End` statement, looping back to the start of a loop if needed.
+    fn execute_end(&mut self) -> Result<(), TIError> {
+        match self.blocks.pop() {
+            Some(Block::If) => (),
+            // jump back to the While so that the condition is evaluated again
+            Some(Block::While(start)) => self.position = start,
+            Some(Block::Repeat(start)) => {
+                let condition = match &self.statements[start].kind {
+                    StatementKind::Repeat(condition) => condition.clone(),
+                    _ => return Err(TIError::Syntax),
+                };
+                if !self.eval_condition(&condition)? {
+                    self.blocks.push(Block::Repeat(start));
+                    self.position = start + 1;
+                }
+            }
+            Some(Block::For {
+                variable,
+                end,
+                step,
+                body,
+            }) => {
+                let value = self
+                    .variables
+                    .get(&Variable::Real(variable))?
+                    .as_real()?
+                    .try_add(step)?
+                    .to_stored();
+                self.variables
+                    .set(&Variable::Real(variable), Value::Real(value))?;
+                if for_continues(value, end, step) {
+                    self.blocks.push(Block::For {
+                        variable,
+                        end,
+                        step,
+                        body,
+                    });
+                    self.position = body;
+                }
+            }
+            None => return Err(TIError::Syntax),
+        }
+
+        Ok(())
+    }
+
+    /// Executes a `Goto` statement by jumping to the first matching `Lbl`.
+    fn execute_goto(&mut self, name: &str) -> Result<(), TIError> {
+        let target = self
+            .statements
+            .iter()
+            .position(|s| matches!(&s.kind, StatementKind::Lbl(lbl) if lbl == name))
+            .ok_or_else(|| TIError::Label(name.to_string()))?;
+
+        self.position = target + 1;
+        Ok(())
+    }
+
+    /// Executes `Menu(` by showing the menu, waiting for an option to be chosen and jumping to
+    /// its label. The home screen is shown again afterwards.
+    fn execute_menu(&mut self, title: &Expr, options: &[(Expr, String)]) -> Result<(), TIError> {
+        let title = self.eval(title)?.to_string();
+        let mut texts = Vec::with_capacity(options.len());
+        for (text, _) in options {
+            texts.push(self.eval(text)?.to_string());
+        }
+        let mut menu = Menu::new(title, texts)?;
+
+        match self.console {
+            Console::Terminal => self.show_menu(&menu)?,
+            Console::Lines => {
+                for line in menu.lines() {
+                    println!("{}", line.replace('▸', ":"));
+                }
+            }
+            Console::Hidden => (),
+        }
+
+        let choice = loop {
+            let key = self.input.wait_for_key()?;
+            if let Some(choice) = menu.press(key) {
+                break choice;
+            }
+            if self.console == Console::Terminal {
+                self.show_menu(&menu)?;
+            }
+        };
+
+        if self.console == Console::Terminal {
+            self.show()?;
+        }
+
+        // a missing label is only noticed once its option is chosen
+        self.execute_goto(&options[choice].1)
+    }
+
+    /// Draws a menu in the terminal, in place of the home screen.
+    fn show_menu(&self, menu: &Menu) -> Result<(), TIError> {
+        menu.screen(self.home.size())
+            .render(&mut io::stdout().lock())
+            .map_err(|_| TIError::Break)
+    }
+
+    /// Executes a command that draws on the graph screen, and then shows the graph screen.
+    fn execute_draw(&mut self, command: Byte, args: &[Expr]) -> Result<(), TIError> {
+        match command {
+            // ClrDraw
+            Byte::Single(0x85) => self.graph.clear(),
+            // Text(
+            Byte::Single(0x93) => {
+                let (row, column, values) = match args {
+                    [row, column, values @ ..] if !values.is_empty() => (row, column, values),
+                    _ => return Err(TIError::Argument),
+                };
+                let row = self.eval(row)?.as_real()?;
+                let column = self.eval(column)?.as_real()?;
+                let (row, column) = match (row.to_i64(), column.to_i64()) {
+                    (Some(row @ 0..), Some(column @ 0..)) => (row as usize, column as usize),
+                    _ => return Err(TIError::Domain),
+                };
+
+                let mut text = String::new();
+                for value in values {
+                    text.push_str(&self.eval(value)?.to_string());
+                }
+                let pen = Pen::On(self.graph.default_color());
+                if !self.graph.text(row, column, &text, pen) {
+                    return Err(TIError::Domain);
+                }
+            }
+            // Shade(
+            Byte::Single(0xA4) => self.execute_shade(args)?,
+            Byte::Single(command @ 0x9C..=0xA6) => {
+                let values = args
+                    .iter()
+                    .map(|arg| self.eval(arg)?.as_real())
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.draw(command, &values)?;
+            }
+            // DispGraph only shows the graph screen
+            _ => (),
+        }
+
+        if self.console == Console::Terminal {
+            self.graph
+                .render(&mut io::stdout().lock())
+                .map_err(|_| TIError::Break)?;
+        }
+
+        Ok(())
+    }
+
+    /// Executes one of the commands from `Line(` to `Horizontal`, other than `Shade(`, which only
+    /// take real arguments.
+    fn draw(&mut self, command: u8, values: &[TIFloat]) -> Result<(), TIError> {
+        // the number of coordinates that each command takes, and then the number of optional
+        // arguments after them
+        let (count, optional) = match command {
+            0x9C => (4, 2),
+            0x9D | 0xA6 => (1, 1),
+            0x9E => (2, 2),
+            0x9F..=0xA1 => (2, 1),
+            0xA2 | 0xA3 => (2, 0),
+            0xA5 => (3, 1),
+            _ => return Err(TIError::Unsupported(token_name(Byte::Single(command)))),
+        };
+        if values.len() < count || values.len() > count + optional {
+            return Err(TIError::Argument);
+        }
+        let (coordinates, extra) = values.split_at(count);
+        let c: Vec<f64> = coordinates.iter().map(TIFloat::to_f64).collect();
+
+        match command {
+            // Line(, which erases when its fifth argument is 0
+            0x9C => {
+                let pen = match extra {
+                    [erase, ..] if erase.is_zero() => Pen::Off,
+                    [_, color] => Pen::On(self.color(Some(color))?),
+                    _ => Pen::On(self.color(None)?),
+                };
+                self.graph.line(c[0], c[1], c[2], c[3], pen)
+            }
+            // Vertical
+            0x9D => self
+                .graph
+                .vertical(c[0], Pen::On(self.color(extra.first())?)),
+            // Pt-On( and Pt-Off(, which take a mark
+            0x9E | 0x9F => {
+                let mark = match extra.first() {
+                    None => 1,
+                    // point() rejects anything other than 1, 2 or 3
+                    Some(mark) => mark.to_i64().map_or(0, |mark| mark.clamp(0, 4) as u8),
+                };
+                let pen = match command {
+                    0x9E => Pen::On(self.color(extra.get(1))?),
+                    _ => Pen::Off,
+                };
+                self.graph.point(c[0], c[1], mark, pen)
+            }
+            // Pt-Change(
+            0xA0 => {
+                let pen = Pen::Change(self.color(extra.first())?);
+                self.graph.point(c[0], c[1], 1, pen)
+            }
+            // Pxl-On(, Pxl-Off( and Pxl-Change(, which take a row and column
+            0xA1..=0xA3 => {
+                let (row, column) = match (coordinates[0].to_i64(), coordinates[1].to_i64()) {
+                    (Some(row), Some(column)) => (row, column),
+                    _ => return Err(TIError::Domain),
+                };
+                let pen = match command {
+                    0xA1 => Pen::On(self.color(extra.first())?),
+                    0xA2 => Pen::Off,
+                    _ => Pen::Change(self.color(None)?),
+                };
+                match self.graph.set_pixel(row, column, pen) {
+                    true => Ok(()),
+                    false => Err(TIError::Domain),
+                }
+            }
+            // Horizontal
+            0xA6 => self
+                .graph
+                .horizontal(c[0], Pen::On(self.color(extra.first())?)),
+            // Circle(
+            _ => self
+                .graph
+                .circle(c[0], c[1], c[2], Pen::On(self.color(extra.first())?)),
+        }
+    }
+
+    /// Returns the color number that a drawing command draws with, from its optional color
+    /// argument.
+    fn color(&self, color: Option<&TIFloat>) -> Result<u8, TIError> {
+        match color {
+            None => Ok(self.graph.default_color()),
+            // programs written for color models still run on monochrome screens, in black
+            Some(_) if !self.graph.is_color() => Ok(BLACK),
+            Some(color) => match color.to_i64() {
+                Some(color @ 10..=24) => Ok(color as u8),
+                _ => Err(TIError::Domain),
+            },
+        }
+    }
+
+    /// Executes `Shade(`, which fills the area between two expressions of `X` from `Xleft` to
+    /// `Xright`. Patterns aren't supported, so the area is always filled in completely.
+    fn execute_shade(&mut self, args: &[Expr]) -> Result<(), TIError> {
+        let (lower, upper, rest) = match args {
+            [lower, upper, rest @ ..] if rest.len() <= 4 => (lower, upper, rest),
+            _ => return Err(TIError::Argument),
+        };
+        let mut bounds = Vec::with_capacity(2);
+        for arg in rest.iter().take(2) {
+            bounds.push(self.eval(arg)?.as_real()?.to_f64());
+        }
+        self.graph.check_window()?;
+
+        let window = self.graph.window;
+        let left = bounds.first().copied().unwrap_or(window.xmin);
+        let right = bounds.get(1).copied().unwrap_or(window.xmax);
+
+        // X is used to evaluate the expressions, and is put back afterwards
+        let x = self.variables.get(&X)?;
+        let result = self.shade_columns(lower, upper, left, right);
+        self.variables.set(&X, x)?;
+
+        result
+    }
+
+    /// Fills each column of the graph screen between `left` and `right` for `Shade(`.
+    fn shade_columns(
+        &mut self,
+        lower: &Expr,
+        upper: &Expr,
+        left: f64,
+        right: f64,
+    ) -> Result<(), TIError> {
+        let (width, _) = self.graph.graph_size();
+        let window = self.graph.window;
+        // allow for rounding errors in the x coordinates of the columns at the edges
+        let tolerance = (window.xmax - window.xmin) * 1e-9;
+        let pen = Pen::On(self.graph.default_color());
+
+        for column in 0..width {
+            let x = self.graph.column_x(column);
+            if x < left - tolerance || x > right + tolerance {
+                continue;
+            }
+
+            self.variables.set(&X, Value::Real(TIFloat::from_f64(x)?))?;
+            let lower = self.eval(lower)?.as_real()?.to_f64();
+            let upper = self.eval(upper)?.as_real()?.to_f64();
+            self.graph.shade_column(column, lower, upper, pen);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the window setting that a window variable such as `Xmin` refers to.
+    fn window_variable(&mut self, variable: &Variable) -> Option<&mut f64> {
+        let window = &mut self.graph.window;
+        match variable {
+            Variable::System(Byte::Double([0x63, 0x0A])) => Some(&mut window.xmin),
+            Variable::System(Byte::Double([0x63, 0x0B])) => Some(&mut window.xmax),
+            Variable::System(Byte::Double([0x63, 0x0C])) => Some(&mut window.ymin),
+            Variable::System(Byte::Double([0x63, 0x0D])) => Some(&mut window.ymax),
+            _ => None,
+        }
+    }
+
+    /// Displays a value on its own line of the home screen. Numbers are right aligned and strings
+    /// are left aligned.
+    fn display(&mut self, value: &Value) -> Result<(), TIError> {
+        let text = value.to_string();
+        match value {
+            Value::Str(_) => self.home.disp_text(&text),
+            _ => self.home.disp_number(&text),
+        }
+
+        match self.console {
+            Console::Terminal => self.show(),
+            Console::Lines => {
+                match value {
+                    Value::Str(_) => println!("{}", text),
+                    _ => println!("{:>width$}", text, width = self.home.size().0),
+                }
+                Ok(())
+            }
+            Console::Hidden => Ok(()),
+        }
+    }
+
+    /// Draws the home screen in the terminal.
+    fn show(&self) -> Result<(), TIError> {
+        self.home
+            .render(&mut io::stdout().lock())
+            .map_err(|_| TIError::Break)
+    }
+
+    /// Displays a prompt on the home screen and reads a line of input after it.
+    fn read_line(&mut self, prompt: &str) -> Result<String, TIError> {
+        let (row, column) = self.home.begin_input(prompt);
+        match self.console {
+            Console::Terminal => {
+                self.show()?;
+                self.home
+                    .move_cursor(&mut io::stdout().lock(), row, column)
+                    .map_err(|_| TIError::Break)?;
+            }
+            Console::Lines => {
+                print!("{}", prompt);
+                io::stdout().flush().map_err(|_| TIError::Break)?;
+            }
+            Console::Hidden => (),
+        }
+
+        let line = self.input.read_line()?;
+        // input that isn't typed at the terminal is shown as if it had been
+        if !self.input.is_typed() && self.console == Console::Lines {
+            println!("{}", line);
+        }
+
+        self.home.end_input(prompt, &line);
+        if self.console == Console::Terminal {
+            self.show()?;
+        }
+
+        Ok(line)
+    }
+
+    /// Displays a prompt, reads a line of input, and stores it into a variable.
+    fn input_into(&mut self, prompt: &str, variable: &Expr) -> Result<(), TIError> {
+        let line = self.read_line(prompt)?;
+
+        let value = match variable {
+            // strings are stored exactly as they were typed
+            Expr::Variable(Variable::Str(_)) => Value::Str(line),
+            _ => self.evaluate_text(&line)?,
+        };
+
+        self.store(variable, value)
+    }
+
+    /// Evaluates an expression typed as text, such as a response to `Input`.
+    fn evaluate_text(&mut self, text: &str) -> Result<Value, TIError> {
+        let bytes = compile::compile_to_bytecode(vec![text]).map_err(|_| TIError::Syntax)?;
+        let expr = parser::parse_expression(&bytes).map_err(|_| TIError::Syntax)?;
+
+        self.eval(&expr)
+    }
+
+    /// Stores a value into a variable, an element of a list or matrix, or the dimensions of a
+    /// list or matrix, checking that the types match. Storing to `rand` seeds it.
+    fn store(&mut self, target: &Expr, value: Value) -> Result<(), TIError> {
+        match target {
+            Expr::Variable(variable) => self.set_variable(variable, value),
+            // equations can only be evaluated at a value, not stored into at one
+            Expr::Index {
+                variable: Variable::Equation(_),
+                ..
+            } => Err(TIError::Syntax),
+            Expr::Index { variable, args } => {
+                let indices = self.eval_reals(args)?;
+                self.variables
+                    .set_element(variable, &indices, value.as_real()?)
+            }
+            // dim(
+            Expr::Call {
+                function: Byte::Single(0xB5),
+                args,
+            } => match args.as_slice() {
+                [Expr::Variable(variable)] => self.variables.set_dim(variable, &value),
+                _ => Err(TIError::Syntax),
+            },
+            // rand
+            Expr::Token(Byte::Single(0xAB)) => {
+                let seed = value.as_real()?.abs().ipart().to_i64();
+                self.seed_random(seed.map_or(0, |seed| seed as u64));
+                Ok(())
+            }
+            _ => Err(TIError::Syntax),
+        }
+    }
+
+    /// Returns the value of a variable, including the window settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Undefined`] if nothing has been stored in the variable.
+    pub fn variable(&self, variable: &Variable) -> Result<Value, TIError> {
+        let window = &self.graph.window;
+        let setting = match variable {
+            Variable::System(Byte::Double([0x63, 0x0A])) => window.xmin,
+            Variable::System(Byte::Double([0x63, 0x0B])) => window.xmax,
+            Variable::System(Byte::Double([0x63, 0x0C])) => window.ymin,
+            Variable::System(Byte::Double([0x63, 0x0D])) => window.ymax,
+            _ => return self.variables.get(variable),
+        };
+
+        Ok(Value::Real(TIFloat::from_f64(setting)?))
+    }
+
+    /// Stores a value into a variable, including the window settings, following the rules of
+    /// [`Variables::set`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::DataType`] if the variable can't hold the type of the value.
+    pub fn set_variable(&mut self, variable: &Variable, value: Value) -> Result<(), TIError> {
+        if self.window_variable(variable).is_none() {
+            return self.variables.set(variable, value);
+        }
+
+        let value = value.as_real()?.to_stored().to_f64();
+        if let Some(setting) = self.window_variable(variable) {
+            *setting = value;
+        }
+
+        Ok(())
+    }
+
+    /// Evaluates an equation such as `Y1`, optionally at a value of `X`, which is put back
+    /// afterwards.
+    fn eval_equation(&mut self, equation: &Variable, x: Option<Value>) -> Result<Value, TIError> {
+        let text = match self.variables.get(equation)? {
+            Value::Str(text) => text,
+            _ => return Err(TIError::DataType),
+        };
+
+        let x = match x {
+            Some(x) => x,
+            None => return self.evaluate_text(&text),
+        };
+        let saved = self.variables.get(&X)?;
+        self.variables.set(&X, x)?;
+        let result = self.evaluate_text(&text);
+        self.variables.set(&X, saved)?;
+
+        result
+    }
+
+    /// Evaluates expressions that must all be real numbers, such as the indices of a matrix.
+    fn eval_reals(&mut self, exprs: &[Expr]) -> Result<Vec<TIFloat>, TIError> {
+        exprs
+            .iter()
+            .map(|expr| self.eval(expr)?.as_real())
+            .collect()
+    }
+
+    /// Evaluates an expression and returns whether it is true (non-zero).
+    fn eval_condition(&mut self, expr: &Expr) -> Result<bool, TIError> {
+        Ok(!self.eval(expr)?.as_real()?.is_zero())
+    }
+
+    /// Evaluates an expression.
+    fn eval(&mut self, expr: &Expr) -> Result<Value, TIError> {
+        match expr {
+            Expr::Number(v) => Ok(Value::Real(*v)),
+            Expr::Str(s) => Ok(Value::Str(s.clone())),
+            Expr::Variable(equation @ Variable::Equation(_)) => self.eval_equation(equation, None),
+            Expr::Variable(variable) => self.variable(variable),
+            Expr::Index {
+                variable: equation @ Variable::Equation(_),
+                args,
+            } => match args.as_slice() {
+                [x] => {
+                    let x = self.eval(x)?;
+                    self.eval_equation(equation, Some(x))
+                }
+                _ => Err(TIError::Argument),
+            },
+            Expr::Index { variable, args } => {
+                let indices = self.eval_reals(args)?;
+                Ok(Value::Real(self.variables.element(variable, &indices)?))
+            }
+            Expr::Ans => Ok(self.variables.ans.clone()),
+            Expr::List(items) => {
+                let list = self.eval_reals(items)?;
+                if list.len() > variables::MAX_LIST_LENGTH {
+                    return Err(TIError::InvalidDim);
+                }
+                Ok(Value::List(list))
+            }
+            Expr::Matrix(rows) => {
+                let rows = rows
+                    .iter()
+                    .map(|row| self.eval_reals(row))
+                    .collect::<Result<Vec<_>, _>>()?;
+                variables::check_matrix(&rows)?;
+                Ok(Value::Matrix(rows))
+            }
+            Expr::Token(Byte::Single(0x2C)) => Ok(Value::Complex(Complex::I)),
+            // BLUE to DARKGRAY are the color numbers 10 to 24
+            Expr::Token(Byte::Double([0xEF, color @ 0x41..=0x4F])) => {
+                Ok(Value::Real(TIFloat::from(*color as i64 - 0x41 + 10)))
+            }
+            Expr::Token(Byte::Single(0xAC)) => {
+                Ok(Value::Real(TIFloat::from_f64(std::f64::consts::PI)?))
+            }
+            Expr::Token(Byte::Double([0xBB, 0x31])) => {
+                Ok(Value::Real(TIFloat::from_f64(std::f64::consts::E)?))
+            }
+            Expr::Token(Byte::Single(0xAB)) => Ok(Value::Real(self.next_random())),
+            Expr::Token(Byte::Single(0xAD)) => {
+                Ok(Value::Real(TIFloat::from(self.input.get_key()? as i64)))
+            }
+            Expr::Call { function, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.call_function(*function, args)
+            }
+            Expr::Unary {
+                op: UnaryOp::Negate,
+                operand,
+            } => match self.eval(operand)? {
+                Value::Complex(v) => Ok(Value::Complex(-v)),
+                value => map_reals(value, |v| Ok(-v)),
+            },
+            Expr::Postfix { op, operand } => {
+                let value = self.eval(operand)?;
+                eval_postfix(*op, value)
+            }
+            Expr::Binary { op, left, right } => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                eval_binary(*op, left, right)
+            }
+            Expr::Token(byte) => Err(TIError::Unsupported(token_name(*byte))),
+        }
+    }
+
+    /// Calls the function represented by the given token.
+    fn call_function(&mut self, function: Byte, args: Vec<Value>) -> Result<Value, TIError> {
+        match function {
+            // sub(
+            Byte::Double([0xBB, 0x0C]) => {
+                let (string, start, length) = match args.as_slice() {
+                    [Value::Str(s), start, length] => (s, start.as_real()?, length.as_real()?),
+                    [_, _, _] => return Err(TIError::DataType),
+                    _ => return Err(TIError::Argument),
+                };
+                let chars: Vec<char> = string.chars().collect();
+                let (start, length) = match (start.to_i64(), length.to_i64()) {
+                    (Some(start @ 1..), Some(length @ 1..))
+                        if start + length - 1 <= chars.len() as i64 =>
+                    {
+                        (start as usize - 1, length as usize)
+                    }
+                    _ => return Err(TIError::Domain),
+                };
+                Ok(Value::Str(chars[start..start + length].iter().collect()))
+            }
+            // inString(
+            Byte::Double([0xBB, 0x0F]) => {
+                let (haystack, needle, start) = match args.as_slice() {
+                    [Value::Str(h), Value::Str(n)] => (h, n, TIFloat::ONE),
+                    [Value::Str(h), Value::Str(n), start] => (h, n, start.as_real()?),
+                    [_, _] | [_, _, _] => return Err(TIError::DataType),
+                    _ => return Err(TIError::Argument),
+                };
+                let start = match start.to_i64() {
+                    Some(start @ 1..) => start as usize - 1,
+                    _ => return Err(TIError::Domain),
+                };
+                let haystack: Vec<char> = haystack.chars().collect();
+                let needle: Vec<char> = needle.chars().collect();
+                let found = (start..haystack.len())
+                    .find(|&i| haystack[i..].starts_with(&needle))
+                    .map_or(0, |i| i + 1);
+                Ok(Value::Real(TIFloat::from(found as i64)))
+            }
+            // length(
+            Byte::Double([0xBB, 0x2B]) => match args.as_slice() {
+                [Value::Str(s)] => Ok(Value::Real(TIFloat::from(s.chars().count() as i64))),
+                [_] => Err(TIError::DataType),
+                _ => Err(TIError::Argument),
+            },
+            // dim(
+            Byte::Single(0xB5) => match args.as_slice() {
+                [value] => value.dim(),
+                _ => Err(TIError::Argument),
+            },
+            // functions of one number are applied to each element of a list
+            _ if matches!(args.as_slice(), [Value::List(_)]) => {
+                let list = match args.into_iter().next() {
+                    Some(Value::List(list)) => list,
+                    _ => return Err(TIError::DataType),
+                };
+                let list = list
+                    .into_iter()
+                    .map(|v| self.call_real_function(function, &[v]))
+                    .collect::<Result<_, _>>()?;
+                Ok(Value::List(list))
+            }
+            _ => {
+                let args = args
+                    .iter()
+                    .map(Value::as_real)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Value::Real(self.call_real_function(function, &args)?))
+            }
+        }
+    }
+
+    /// Calls a function that only takes real arguments.
+    fn call_real_function(&mut self, function: Byte, args: &[TIFloat]) -> Result<TIFloat, TIError> {
+        let one = |f: fn(TIFloat) -> Result<TIFloat, TIError>| match args {
+            [x] => f(*x),
+            _ => Err(TIError::Argument),
+        };
+        let two = |f: fn(TIFloat, TIFloat) -> Result<TIFloat, TIError>| match args {
+            [a, b] => f(*a, *b),
+            _ => Err(TIError::Argument),
+        };
+        // functions that are calculated with f64 and are only valid when `valid` is true
+        let approx = |f: fn(f64) -> f64, valid: fn(f64) -> bool| match args {
+            [x] if valid(x.to_f64()) => x.map_f64(f),
+            [_] => Err(TIError::Domain),
+            _ => Err(TIError::Argument),
+        };
+        let any = |_: f64| true;
+
+        match function {
+            // round(
+            Byte::Single(0x12) => {
+                let (value, places) = match args {
+                    [v] => (*v, 9),
+                    [v, p] => match p.to_i64() {
+                        Some(p @ 0..=9) => (*v, p as i32),
+                        _ => return Err(TIError::Domain),
+                    },
+                    _ => return Err(TIError::Argument),
+                };
+                Ok(value.round(places))
+            }
+            // max( and min(
+            Byte::Single(0x19) => two(|a, b| Ok(a.max(b))),
+            Byte::Single(0x1A) => two(|a, b| Ok(a.min(b))),
+            Byte::Single(0xB1) => one(|x| Ok(x.floor())),
+            Byte::Single(0xB2) => one(|x| Ok(x.abs())),
+            Byte::Single(0xB8) => one(|x| Ok(TIFloat::from(x.is_zero()))),
+            Byte::Single(0xB9) => one(|x| Ok(x.ipart())),
+            Byte::Single(0xBA) => one(|x| Ok(x.fpart())),
+            Byte::Single(0xBC) => approx(f64::sqrt, |x| x >= 0.0),
+            Byte::Single(0xBD) => approx(f64::cbrt, any),
+            Byte::Single(0xBE) => approx(f64::ln, |x| x > 0.0),
+            Byte::Single(0xBF) => approx(f64::exp, any),
+            Byte::Single(0xC0) => approx(f64::log10, |x| x > 0.0),
+            Byte::Single(0xC1) => one(|x| TIFloat::from(10).try_pow(x)),
+            Byte::Single(0xC2) => approx(f64::sin, any),
+            Byte::Single(0xC3) => approx(f64::asin, |x| x.abs() <= 1.0),
+            Byte::Single(0xC4) => approx(f64::cos, any),
+            Byte::Single(0xC5) => approx(f64::acos, |x| x.abs() <= 1.0),
+            Byte::Single(0xC6) => approx(f64::tan, any),
+            Byte::Single(0xC7) => approx(f64::atan, any),
+            Byte::Single(0xC8) => approx(f64::sinh, any),
+            Byte::Single(0xC9) => approx(f64::asinh, any),
+            Byte::Single(0xCA) => approx(f64::cosh, any),
+            Byte::Single(0xCB) => approx(f64::acosh, |x| x >= 1.0),
+            Byte::Single(0xCC) => approx(f64::tanh, any),
+            Byte::Single(0xCD) => approx(f64::atanh, |x| x.abs() < 1.0),
+            // lcm( and gcd(
+            Byte::Double([0xBB, 0x08]) => two(|a, b| {
+                let gcd = gcd(a, b)?;
+                if gcd.is_zero() {
+                    Ok(gcd)
+                } else {
+                    a.try_div(gcd)?.try_mul(b)
+                }
+            }),
+            Byte::Double([0xBB, 0x09]) => two(gcd),
+            // randInt(
+            Byte::Double([0xBB, 0x0A]) => {
+                let (low, high) = match args {
+                    [a, b] => match (a.min(b).to_i64(), a.max(b).to_i64()) {
+                        (Some(low), Some(high)) => (low, high),
+                        _ => return Err(TIError::Domain),
+                    },
+                    _ => return Err(TIError::Argument),
+                };
+                let range = TIFloat::from(high - low + 1);
+                let offset = self.next_random().try_mul(range)?.floor();
+                Ok(TIFloat::from(low).try_add(offset)?)
+            }
+            // pxl-Test(
+            Byte::Single(0x13) => {
+                let pixel = match args {
+                    [row, column] => match (row.to_i64(), column.to_i64()) {
+                        (Some(row @ 0..), Some(column @ 0..)) => {
+                            self.graph.pixel(row as usize, column as usize)
+                        }
+                        _ => None,
+                    },
+                    _ => return Err(TIError::Argument),
+                };
+                match pixel {
+                    Some(pixel) => Ok(TIFloat::from(pixel != graph::BACKGROUND)),
+                    None => Err(TIError::Domain),
+                }
+            }
+            // remainder(
+            Byte::Double([0xEF, 0x32]) => two(|a, b| {
+                if b.is_zero() {
+                    return Err(TIError::DivideBy0);
+                }
+                a.try_sub(a.try_div(b)?.ipart().try_mul(b)?)
+            }),
+            other => Err(TIError::Unsupported(
+                BYTE_TOKENS
+                    .get(&other)
+                    .map_or_else(|| format!("{:x?}", other), |s| s.to_string()),
+            )),
+        }
+    }
+
+    /// Returns the next random number in the range `[0, 1)`, using the same combined generator
+    /// as the calculator.
+    fn next_random(&mut self) -> TIFloat {
+        for ((state, multiplier), modulus) in self
+            .rand_state
+            .iter_mut()
+            .zip(RAND_MULTIPLIERS)
+            .zip(RAND_MODULI)
+        {
+            *state = *state * multiplier % modulus;
+        }
+
+        let difference = (self.rand_state[0] - self.rand_state[1]).rem_euclid(RAND_MODULI[0]);
+        TIFloat::from(difference)
+            .try_div(TIFloat::from(RAND_MODULI[0]))
+            .unwrap_or(TIFloat::ZERO)
+    }
+}
+
+/// Applies a postfix operator to a value. Lists have the operator applied to each element.
+fn eval_postfix(op: PostfixOp, value: Value) -> Result<Value, TIError> {
+    match (op, value) {
+        (PostfixOp::Transpose, Value::Matrix(rows)) => Ok(Value::Matrix(transpose(&rows))),
+        (PostfixOp::Square, Value::Matrix(rows)) => {
+            Ok(Value::Matrix(matrix_product(&rows, &rows)?))
+        }
+        (PostfixOp::Square, Value::Complex(v)) => Ok(Value::from_complex(v.try_mul(v)?)),
+        (PostfixOp::Reciprocal, Value::Complex(v)) => {
+            Ok(Value::from_complex(Complex::from(TIFloat::ONE).try_div(v)?))
+        }
+        (PostfixOp::Transpose, _) | (_, Value::Matrix(_) | Value::Complex(_)) => {
+            Err(TIError::DataType)
+        }
+        (op, value) => map_reals(value, |v| real_postfix(op, v)),
+    }
+}
+
+/// Applies a postfix operator to a real number.
+fn real_postfix(op: PostfixOp, v: TIFloat) -> Result<TIFloat, TIError> {
+    Ok(match op {
+        PostfixOp::Square => v.try_mul(v)?,
+        PostfixOp::Cube => v.try_pow(TIFloat::from(3))?,
+        PostfixOp::Reciprocal => TIFloat::ONE.try_div(v)?,
+        PostfixOp::Factorial => factorial(v)?,
+        // converts degrees to radians since we're always in radian mode
+        PostfixOp::Degree => v.map_f64(f64::to_radians)?,
+        // real numbers are already decimals
+        PostfixOp::ToDec => v,
+        PostfixOp::Transpose => return Err(TIError::DataType),
+        PostfixOp::ToDMS | PostfixOp::ToFrac => {
+            return Err(TIError::Unsupported(format!("{:?}", op)))
+        }
+    })
+}
+
+/// Applies a function to a real number, or to each element of a list or matrix.
+fn map_reals(
+    value: Value,
+    mut f: impl FnMut(TIFloat) -> Result<TIFloat, TIError>,
+) -> Result<Value, TIError> {
+    Ok(match value {
+        Value::List(list) => Value::List(list.into_iter().map(f).collect::<Result<_, _>>()?),
+        Value::Matrix(rows) => Value::Matrix(
+            rows.into_iter()
+                .map(|row| row.into_iter().map(&mut f).collect())
+                .collect::<Result<_, _>>()?,
+        ),
+        value => Value::Real(f(value.as_real()?)?),
+    })
+}
+
+/// Applies a binary operator to two values. Operators between a list and a number, or two lists
+/// of the same length, are applied to each element.
+fn eval_binary(op: BinaryOp, left: Value, right: Value) -> Result<Value, TIError> {
+    match (op, &left, &right) {
+        (BinaryOp::Add, Value::Str(a), Value::Str(b)) => return Ok(Value::Str(a.clone() + b)),
+        (BinaryOp::Equal, Value::Str(a), Value::Str(b)) => return Ok(bool_value(a == b)),
+        (BinaryOp::NotEqual, Value::Str(a), Value::Str(b)) => return Ok(bool_value(a != b)),
+        _ => (),
+    }
+
+    match (left, right) {
+        (Value::List(a), Value::List(b)) => {
+            if a.len() != b.len() {
+                return Err(TIError::DimMismatch);
+            }
+            let list = a
+                .into_iter()
+                .zip(b)
+                .map(|(a, b)| real_binary(op, a, b))
+                .collect::<Result<_, _>>()?;
+            Ok(Value::List(list))
+        }
+        (Value::List(a), b) => {
+            let b = b.as_real()?;
+            map_reals(Value::List(a), |a| real_binary(op, a, b))
+        }
+        (a, Value::List(b)) => {
+            let a = a.as_real()?;
+            map_reals(Value::List(b), |b| real_binary(op, a, b))
+        }
+        (Value::Matrix(a), Value::Matrix(b)) => matrix_binary(op, &a, &b),
+        (Value::Matrix(m), scalar) | (scalar, Value::Matrix(m)) => match op {
+            BinaryOp::Multiply | BinaryOp::ImpliedMultiply => {
+                let scalar = scalar.as_real()?;
+                map_reals(Value::Matrix(m), |v| v.try_mul(scalar))
+            }
+            _ => Err(TIError::DataType),
+        },
+        (a @ Value::Complex(_), b) | (a, b @ Value::Complex(_)) => {
+            complex_binary(op, a.as_complex()?, b.as_complex()?)
+        }
+        (a, b) => Ok(Value::Real(real_binary(op, a.as_real()?, b.as_real()?)?)),
+    }
+}
+
+/// Applies a binary operator to two real numbers.
+fn real_binary(op: BinaryOp, a: TIFloat, b: TIFloat) -> Result<TIFloat, TIError> {
+    let truthy = |v: TIFloat| !v.is_zero();
+
+    Ok(match op {
+        BinaryOp::Add => a.try_add(b)?,
+        BinaryOp::Subtract => a.try_sub(b)?,
+        BinaryOp::Multiply | BinaryOp::ImpliedMultiply => a.try_mul(b)?,
+        BinaryOp::Divide => a.try_div(b)?,
+        BinaryOp::Power => a.try_pow(b)?,
+        // the xth root of b
+        BinaryOp::XRoot => b.try_pow(TIFloat::ONE.try_div(a)?)?,
+        BinaryOp::Permutations => permutations(a, b)?,
+        BinaryOp::Combinations => permutations(a, b)?.try_div(factorial(b)?)?,
+        BinaryOp::Equal => TIFloat::from(a == b),
+        BinaryOp::NotEqual => TIFloat::from(a != b),
+        BinaryOp::Less => TIFloat::from(a < b),
+        BinaryOp::Greater => TIFloat::from(a > b),
+        BinaryOp::LessEqual => TIFloat::from(a <= b),
+        BinaryOp::GreaterEqual => TIFloat::from(a >= b),
+        BinaryOp::And => TIFloat::from(truthy(a) && truthy(b)),
+        BinaryOp::Or => TIFloat::from(truthy(a) || truthy(b)),
+        BinaryOp::Xor => TIFloat::from(truthy(a) != truthy(b)),
+    })
+}
+
+/// Applies a binary operator to two complex numbers. Complex numbers can't be compared with
+/// `<` and `>`, and can only be raised to whole powers.
+fn complex_binary(op: BinaryOp, a: Complex, b: Complex) -> Result<Value, TIError> {
+    let value = match op {
+        BinaryOp::Add => a.try_add(b)?,
+        BinaryOp::Subtract => a.try_sub(b)?,
+        BinaryOp::Multiply | BinaryOp::ImpliedMultiply => a.try_mul(b)?,
+        BinaryOp::Divide => a.try_div(b)?,
+        BinaryOp::Power => match b.re.to_i64() {
+            Some(power) if b.is_real() && power.abs() <= 1000 => {
+                let mut result = Complex::from(TIFloat::ONE);
+                for _ in 0..power.abs() {
+                    result = result.try_mul(a)?;
+                }
+                if power < 0 {
+                    result = Complex::from(TIFloat::ONE).try_div(result)?;
+                }
+                result
+            }
+            _ => return Err(TIError::DataType),
+        },
+        BinaryOp::Equal => return Ok(bool_value(a == b)),
+        BinaryOp::NotEqual => return Ok(bool_value(a != b)),
+        _ => return Err(TIError::DataType),
+    };
+
+    Ok(Value::from_complex(value))
+}
+
+/// Applies a binary operator to two matrices, which must have matching dimensions.
+fn matrix_binary(op: BinaryOp, a: &[Vec<TIFloat>], b: &[Vec<TIFloat>]) -> Result<Value, TIError> {
+    let same_size = a.len() == b.len() && a.first().map(Vec::len) == b.first().map(Vec::len);
+
+    match op {
+        BinaryOp::Add | BinaryOp::Subtract => {
+            if !same_size {
+                return Err(TIError::DimMismatch);
+            }
+            let rows = a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| {
+                    a.iter()
+                        .zip(b)
+                        .map(|(a, b)| real_binary(op, *a, *b))
+                        .collect()
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Value::Matrix(rows))
+        }
+        BinaryOp::Multiply | BinaryOp::ImpliedMultiply => Ok(Value::Matrix(matrix_product(a, b)?)),
+        BinaryOp::Equal => Ok(bool_value(same_size && a == b)),
+        BinaryOp::NotEqual => Ok(bool_value(!(same_size && a == b))),
+        _ => Err(TIError::DataType),
+    }
+}
+
+/// Multiplies two matrices, where the first must have as many columns as the second has rows.
+fn matrix_product(a: &[Vec<TIFloat>], b: &[Vec<TIFloat>]) -> Result<Vec<Vec<TIFloat>>, TIError> {
+    if a.first().map_or(0, Vec::len) != b.len() {
+        return Err(TIError::DimMismatch);
+    }
+    let columns = b.first().map_or(0, Vec::len);
+
+    a.iter()
+        .map(|row| {
+            (0..columns)
+                .map(|column| {
+                    row.iter()
+                        .zip(b)
+                        .try_fold(TIFloat::ZERO, |sum, (v, b_row)| {
+                            sum.try_add(v.try_mul(b_row[column])?)
+                        })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Swaps the rows and columns of a matrix.
+fn transpose(rows: &[Vec<TIFloat>]) -> Vec<Vec<TIFloat>> {
+    let columns = rows.first().map_or(0, Vec::len);
+    (0..columns)
+        .map(|column| rows.iter().map(|row| row[column]).collect())
+        .collect()
+}
+
+/// Returns the display name of a token, for error messages.
+fn token_name(byte: Byte) -> String {
+    BYTE_TOKENS
+        .get(&byte)
+        .map_or_else(|| format!("{:x?}", byte), |s| s.to_string())
+}
+
+/// Checks if a `For(` loop with the given end and step should run with its variable at `value`.
+fn for_continues(value: TIFloat, end: TIFloat, step: TIFloat) -> bool {
+    if step.is_negative() {
+        value >= end
+    } else {
+        value <= end
+    }
+}
+
+/// Converts a boolean into the calculator's `1` or `0`.
+fn bool_value(value: bool) -> Value {
+    Value::Real(TIFloat::from(value))
+}
+
+fn factorial(value: TIFloat) -> Result<TIFloat, TIError> {
+    let value = match value.to_i64() {
+        Some(v @ 0..) => v,
+        _ => return Err(TIError::Domain),
+    };
+
+    (1..=value).try_fold(TIFloat::ONE, |product, n| product.try_mul(TIFloat::from(n)))
+}
+
+/// Calculates `n nPr r`, the number of ways to arrange `r` of `n` items.
+fn permutations(n: TIFloat, r: TIFloat) -> Result<TIFloat, TIError> {
+    let (n, r) = match (n.to_i64(), r.to_i64()) {
+        (Some(n @ 0..), Some(r @ 0..)) => (n, r),
+        _ => return Err(TIError::Domain),
+    };
+
+    if r > n {
+        return Ok(TIFloat::ZERO);
+    }
+    (n - r + 1..=n).try_fold(TIFloat::ONE, |product, k| product.try_mul(TIFloat::from(k)))
+}
+
+fn gcd(a: TIFloat, b: TIFloat) -> Result<TIFloat, TIError> {
+    let (mut a, mut b) = match (a.to_i64(), b.to_i64()) {
+        (Some(a @ 0..), Some(b @ 0..)) => (a, b),
+        _ => return Err(TIError::Domain),
+    };
+
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    Ok(TIFloat::from(a))
+}
+
+/// Converts a NULL padded label name into a [`String`].
+fn label_to_string(name: [u8; 2]) -> String {
+    name.iter()
+        .filter(|&&b| b != 0)
+        .map(|&b| if b == 0x5B { 'θ' } else { b as char })
+        .collect()
+}
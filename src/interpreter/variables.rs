@@ -0,0 +1,345 @@
+//! The `variables` module stores the values of every kind of variable that a program can use,
+//! following the calculator's rules for what can be stored where.
+//!
+//! Each [`Variable`] holds one type of [`Value`]:
+//!
+//! * `A`-`Z` and `θ` hold real or complex numbers, and are 0 until something is stored in them.
+//! * `l1`-`l6` and custom lists such as `ʟABC` hold lists of real numbers. The built-in lists
+//!   start out empty, while custom lists are created when they are first stored to.
+//! * `[A]`-`[J]` hold matrices of real numbers.
+//! * `Str0`-`Str9` hold strings.
+//! * Equations such as `Y1` hold the text of an expression, which is stored from a string.
+//! * System variables such as `Xscl` and the statistics results hold real numbers.
+//!
+//! Storing a value of the wrong type is an `ERR:DATA TYPE`, and using an index or dimension
+//! outside of a list or matrix is an `ERR:INVALID DIM`.
+
+use std::collections::HashMap;
+
+use super::Value;
+use crate::{
+    errors::TIError,
+    float::TIFloat,
+    translation::{parser::Variable, tokens::Byte},
+};
+
+/// The most elements that a list can have.
+pub const MAX_LIST_LENGTH: usize = 999;
+
+/// The most rows or columns that a matrix can have.
+pub const MAX_MATRIX_SIZE: usize = 99;
+
+/// The values of a program's variables.
+///
+/// # Example
+///
+/// ```
+/// use tio2::{
+///     errors::TIError,
+///     float::TIFloat,
+///     interpreter::{variables::Variables, Value},
+///     translation::parser::Variable,
+/// };
+///
+/// let mut variables = Variables::default();
+/// let l1 = Variable::List(0x00);
+///
+/// variables.set(&l1, Value::List(vec![TIFloat::from(4), TIFloat::from(5)])).unwrap();
+/// variables.set_element(&l1, &[TIFloat::from(3)], TIFloat::from(6)).unwrap();
+///
+/// assert_eq!(variables.dim(&l1), Ok(Value::Real(TIFloat::from(3))));
+/// assert_eq!(variables.element(&l1, &[TIFloat::from(3)]), Ok(TIFloat::from(6)));
+/// assert_eq!(variables.element(&l1, &[TIFloat::from(4)]), Err(TIError::InvalidDim));
+/// assert_eq!(
+///     variables.set(&l1, Value::Str("HELLO".to_string())),
+///     Err(TIError::DataType)
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variables {
+    /// Every variable that has a value
+    values: HashMap<Variable, Value>,
+    /// The result of the last evaluated expression
+    pub ans: Value,
+}
+
+impl Default for Variables {
+    /// Creates the variables of a calculator that has just been reset, where `l1`-`l6` are empty
+    /// and nothing else is defined.
+    fn default() -> Self {
+        Self {
+            values: (0x00..=0x05)
+                .map(|list| (Variable::List(list), Value::List(Vec::new())))
+                .collect(),
+            ans: Value::Real(TIFloat::ZERO),
+        }
+    }
+}
+
+impl Variables {
+    /// Returns the value of a variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Undefined`] if nothing has been stored in the variable. Real
+    /// variables and graph settings are never undefined.
+    pub fn get(&self, variable: &Variable) -> Result<Value, TIError> {
+        if let Some(value) = self.values.get(variable) {
+            return Ok(value.clone());
+        }
+
+        match variable {
+            Variable::Real(_) => Ok(Value::Real(TIFloat::ZERO)),
+            Variable::System(byte) => match default_setting(*byte) {
+                Some(value) => Ok(Value::Real(TIFloat::from(value))),
+                None => Err(TIError::Undefined),
+            },
+            _ => Err(TIError::Undefined),
+        }
+    }
+
+    /// Stores a value into a variable, rounding numbers to the digits that are kept in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::DataType`] if the variable can't hold the type of the value, or
+    /// [`TIError::InvalidDim`] if a list or matrix is too large or a matrix's rows have
+    /// different lengths.
+    pub fn set(&mut self, variable: &Variable, value: Value) -> Result<(), TIError> {
+        let allowed = match (variable, &value) {
+            (Variable::Real(_), Value::Real(_) | Value::Complex(_)) => true,
+            (Variable::List(_) | Variable::CustomList(_), Value::List(list)) => {
+                if list.len() > MAX_LIST_LENGTH {
+                    return Err(TIError::InvalidDim);
+                }
+                true
+            }
+            (Variable::Matrix(_), Value::Matrix(rows)) => {
+                check_matrix(rows)?;
+                true
+            }
+            (Variable::Str(_) | Variable::Equation(_), Value::Str(_)) => true,
+            (Variable::System(_), Value::Real(_)) => true,
+            _ => false,
+        };
+        if !allowed {
+            return Err(TIError::DataType);
+        }
+
+        self.values.insert(variable.clone(), value.into_stored());
+        Ok(())
+    }
+
+    /// Deletes a variable, like `DelVar`, returning the value it had. Real variables go back to
+    /// 0 and the built-in lists become empty.
+    pub fn remove(&mut self, variable: &Variable) -> Option<Value> {
+        let value = self.values.remove(variable);
+        if let Variable::List(0x00..=0x05) = variable {
+            self.values
+                .insert(variable.clone(), Value::List(Vec::new()));
+        }
+
+        value
+    }
+
+    /// Returns every variable that has been stored to, along with its value, in no particular
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Variable, &Value)> {
+        self.values.iter()
+    }
+
+    /// Returns an element of a list or matrix, such as `l1(2)` or `[A](1,2)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Undefined`] if the variable doesn't exist, [`TIError::Argument`] if the
+    /// number of indices is wrong for the variable, [`TIError::Domain`] if an index isn't a
+    /// whole number, [`TIError::InvalidDim`] if an index is outside of the list or matrix, or
+    /// [`TIError::DataType`] if the variable isn't a list or matrix.
+    pub fn element(&self, variable: &Variable, indices: &[TIFloat]) -> Result<TIFloat, TIError> {
+        match (self.get(variable)?, indices) {
+            (Value::List(list), [index]) => {
+                let index = to_index(*index)?;
+                list.get(index).copied().ok_or(TIError::InvalidDim)
+            }
+            (Value::Matrix(rows), [row, column]) => {
+                let (row, column) = (to_index(*row)?, to_index(*column)?);
+                rows.get(row)
+                    .and_then(|row| row.get(column))
+                    .copied()
+                    .ok_or(TIError::InvalidDim)
+            }
+            (Value::List(_) | Value::Matrix(_), _) => Err(TIError::Argument),
+            _ => Err(TIError::DataType),
+        }
+    }
+
+    /// Stores into an element of a list or matrix, such as `5→l1(2)`. Storing one past the end
+    /// of a list adds an element to it, and storing into the first element of a custom list that
+    /// doesn't exist yet creates it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Variables::element`].
+    pub fn set_element(
+        &mut self,
+        variable: &Variable,
+        indices: &[TIFloat],
+        value: TIFloat,
+    ) -> Result<(), TIError> {
+        let current = match (self.get(variable), variable) {
+            (Err(TIError::Undefined), Variable::CustomList(_)) => Value::List(Vec::new()),
+            (current, _) => current?,
+        };
+        let value = value.to_stored();
+
+        match (current, indices) {
+            (Value::List(mut list), [index]) => {
+                let index = to_index(*index)?;
+                if index == list.len() && index < MAX_LIST_LENGTH {
+                    list.push(value);
+                } else {
+                    *list.get_mut(index).ok_or(TIError::InvalidDim)? = value;
+                }
+                self.values.insert(variable.clone(), Value::List(list));
+            }
+            (Value::Matrix(mut rows), [row, column]) => {
+                let (row, column) = (to_index(*row)?, to_index(*column)?);
+                *rows
+                    .get_mut(row)
+                    .and_then(|row| row.get_mut(column))
+                    .ok_or(TIError::InvalidDim)? = value;
+                self.values.insert(variable.clone(), Value::Matrix(rows));
+            }
+            (Value::List(_) | Value::Matrix(_), _) => return Err(TIError::Argument),
+            _ => return Err(TIError::DataType),
+        }
+
+        Ok(())
+    }
+
+    /// Returns the dimensions of a list or matrix, like `dim(`, which is the length of a list or
+    /// a list of the number of rows and columns of a matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::Undefined`] if the variable doesn't exist, or [`TIError::DataType`] if
+    /// it isn't a list or matrix.
+    pub fn dim(&self, variable: &Variable) -> Result<Value, TIError> {
+        self.get(variable)?.dim()
+    }
+
+    /// Resizes a list or matrix, like storing into `dim(`. New elements are 0, and lists and
+    /// matrices that don't exist yet are created.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The new length of a list, or a list of the number of rows and columns of a
+    ///   matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TIError::DataType`] if the variable isn't a list or matrix or `dim` is the
+    /// wrong type for it, or [`TIError::InvalidDim`] if the size is 0, isn't a whole number or is
+    /// too large.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::{
+    ///     float::TIFloat,
+    ///     interpreter::{variables::Variables, Value},
+    ///     translation::parser::Variable,
+    /// };
+    ///
+    /// let mut variables = Variables::default();
+    /// let matrix = Variable::Matrix(0x00);
+    /// let size = Value::List(vec![TIFloat::from(2), TIFloat::from(3)]);
+    ///
+    /// variables.set_dim(&matrix, &size).unwrap();
+    ///
+    /// assert_eq!(variables.dim(&matrix), Ok(size));
+    /// assert_eq!(variables.element(&matrix, &[TIFloat::from(2), TIFloat::from(3)]), Ok(TIFloat::ZERO));
+    /// ```
+    pub fn set_dim(&mut self, variable: &Variable, dim: &Value) -> Result<(), TIError> {
+        let size = |value: TIFloat, max: usize| match value.to_i64() {
+            Some(size) if (1..=max as i64).contains(&size) => Ok(size as usize),
+            _ => Err(TIError::InvalidDim),
+        };
+
+        match (variable, dim) {
+            (Variable::List(_) | Variable::CustomList(_), Value::Real(length)) => {
+                let length = size(*length, MAX_LIST_LENGTH)?;
+                let mut list = match self.get(variable) {
+                    Ok(Value::List(list)) => list,
+                    _ => Vec::new(),
+                };
+                list.resize(length, TIFloat::ZERO);
+                self.values.insert(variable.clone(), Value::List(list));
+            }
+            (Variable::Matrix(_), Value::List(dims)) => {
+                let (rows, columns) = match dims.as_slice() {
+                    [rows, columns] => (
+                        size(*rows, MAX_MATRIX_SIZE)?,
+                        size(*columns, MAX_MATRIX_SIZE)?,
+                    ),
+                    _ => return Err(TIError::InvalidDim),
+                };
+                let mut matrix = match self.get(variable) {
+                    Ok(Value::Matrix(matrix)) => matrix,
+                    _ => Vec::new(),
+                };
+                matrix.resize(rows, Vec::new());
+                for row in &mut matrix {
+                    row.resize(columns, TIFloat::ZERO);
+                }
+                self.values.insert(variable.clone(), Value::Matrix(matrix));
+            }
+            _ => return Err(TIError::DataType),
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that a matrix isn't empty, isn't too large and has rows that are all the same length.
+///
+/// # Errors
+///
+/// Returns [`TIError::InvalidDim`] if the matrix isn't valid.
+pub fn check_matrix(rows: &[Vec<TIFloat>]) -> Result<(), TIError> {
+    let columns = rows.first().map_or(0, Vec::len);
+    if !(1..=MAX_MATRIX_SIZE).contains(&rows.len())
+        || !(1..=MAX_MATRIX_SIZE).contains(&columns)
+        || rows.iter().any(|row| row.len() != columns)
+    {
+        return Err(TIError::InvalidDim);
+    }
+
+    Ok(())
+}
+
+/// Converts an index starting at 1 into one starting at 0.
+fn to_index(index: TIFloat) -> Result<usize, TIError> {
+    if !index.is_integer() {
+        return Err(TIError::Domain);
+    }
+
+    match index.to_i64() {
+        Some(index @ 1..) => Ok(index as usize - 1),
+        _ => Err(TIError::InvalidDim),
+    }
+}
+
+/// Returns the value that a graph setting has after a reset, or [`None`] for other system
+/// variables, such as the statistics results, which are undefined until they are calculated.
+fn default_setting(byte: Byte) -> Option<i64> {
+    match byte {
+        // Xscl, Yscl, ΔTbl and Xres
+        Byte::Double([0x63, 0x02 | 0x03 | 0x21 | 0x36]) => Some(1),
+        // XFact and YFact
+        Byte::Double([0x63, 0x28 | 0x29]) => Some(4),
+        Byte::Double([0x63, _]) => Some(0),
+        _ => None,
+    }
+}
@@ -2,13 +2,21 @@
 /// for TI-84 Plus calculators. It includes utilities for managing byte tokens, compiling source code
 /// into bytecode, and decompiling bytecode into source code.
 pub mod translation {
+    pub mod asm;
     pub mod common;
     pub mod compile;
     pub mod decompile;
+    pub mod languages;
+    pub mod metadata;
+    pub mod models;
+    pub mod parser;
+    pub mod sheets;
     pub mod tokens;
+    pub mod trie;
 }
 
 pub mod errors;
+pub mod float;
 #[cfg(feature = "interpreter")]
 pub mod interpreter;
 pub mod utils;
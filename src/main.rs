@@ -1,42 +1,134 @@
-use std::{fs, process};
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+};
 
 use clap::{arg, ArgGroup};
-use log::error;
+use log::{error, warn};
+#[cfg(feature = "interpreter")]
+use tio2::interpreter::{self, graph::GraphScreen, home::HomeScreen, input::KeyScript};
 use tio2::{
-    translation::{common::TIFile, compile, decompile},
+    errors::CompileErrors,
+    translation::{
+        asm,
+        common::TIFile,
+        compile,
+        decompile::{self, DecompileOptions, DecompiledProgram, Spelling},
+        languages::Language,
+        models::Model,
+        sheets::{self, SheetFormat, TokenSheet},
+    },
     utils,
 };
 
 fn main() {
-    env_logger::init();
+    // show warnings by default, such as problems found when reading files leniently
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
 
     // define the CLI interface
     let matches = clap::command!()
         .args(&[
-            // arg!(-r --run <INFILE> "Interpret an input file. Can be a .8XP file or decompiled TI-BASIC text."),
+            arg!(-r --run <INFILE> "Interpret an 8XP file."),
             arg!(-d --decompile <INFILE> "Decompile an input file and write to an output file. Defaults to stdout."),
             arg!(-c --compile <INFILE> "Compile a TI-BASIC text file into an 8XP file.").requires("name"),
-            arg!(-o --out <OUTFILE> "Specify a file to output to, if applicable (decompilation)."),
-            arg!(-n --name <NAME> "Specify the program name to use when compiling."),
+            arg!(-o --out <OUTFILE> "Specify a file or directory to output to. Use `-` for stdout. Decompiled files with several programs are written to this directory."),
+            arg!(-f --force "Overwrite output files that already exist."),
+            arg!(-n --name <NAME> "Specify the program name to use when compiling.")
+                .conflicts_with_all(["run", "decompile", "export-tokens"]),
+            arg!(--comment <COMMENT> "Specify the comment to store in the file when compiling. At most 42 bytes.")
+                .conflicts_with_all(["run", "decompile", "export-tokens"]),
+            arg!(--locked "Compile a locked program that can't be edited on the calculator.")
+                .conflicts_with_all(["run", "decompile", "export-tokens"]),
+            arg!(--archived "Compile a program that is stored in the archive.")
+                .conflicts_with_all(["run", "decompile", "export-tokens"]),
+            arg!(--"version-byte" <BYTE> "Specify the version byte to use when compiling.")
+                .value_parser(clap::value_parser!(u8))
+                .conflicts_with_all(["run", "decompile", "export-tokens"]),
+            arg!(-m --model <MODEL> "Specify the calculator model to target: 83+, 84+, 84+SE, 84+CSE or 84+CE. Compiling fails if the program uses tokens the model doesn't support, decompiling warns about them, and running uses the size of its home and graph screens.")
+                .value_parser(clap::value_parser!(Model)),
+            arg!(--keys <FILE> "Read the keys pressed and text typed while running a program from a key script instead of stdin.")
+                .conflicts_with_all(["decompile", "compile", "export-tokens"]),
+            arg!(--screenshot <FILE> "Save the graph screen as a .png or .pbm image after running a program.")
+                .conflicts_with_all(["decompile", "compile", "export-tokens"]),
+            arg!(--seed <SEED> "Seed the random number generator when running a program, like SEED->rand. Defaults to 0, the seed after a memory reset.")
+                .value_parser(clap::value_parser!(u64))
+                .conflicts_with_all(["decompile", "compile", "export-tokens"]),
+            arg!(--lenient "Load 8XP files with incorrect sizes or checksums, printing warnings instead of failing.")
+                .conflicts_with_all(["compile", "export-tokens"]),
+            arg!(--unicode "Decompile tokens the way they are displayed on the calculator, such as → and ≥.")
+                .conflicts_with_all(["ascii", "run", "compile", "export-tokens"]),
+            arg!(--ascii "Decompile tokens using only ASCII characters where possible, such as -> and >=.")
+                .conflicts_with_all(["run", "compile", "export-tokens"]),
+            arg!(--markup "Decompile tokens that would run into the tokens after them as \\{..} markup, such as \\{l}1, instead of hexadecimal escapes.")
+                .conflicts_with_all(["run", "compile", "export-tokens"]),
+            arg!(--"string-tokens" "Keep commands, functions and operators such as Disp and sin( as tokens inside strings when compiling and decompiling, the way they are when typed with their keys.")
+                .conflicts_with_all(["run", "export-tokens"]),
+            arg!(-l --language <LANGUAGE> "Write and read token names in another language when decompiling and compiling: en, fr, de or es.")
+                .value_parser(clap::value_parser!(Language))
+                .conflicts_with_all(["run", "export-tokens"]),
+            arg!(--asm "Decompile assembly programs as an AsmPrgm line followed by their machine code in hexadecimal.")
+                .conflicts_with_all(["run", "compile", "export-tokens"]),
+            arg!(--squish "Compile assembly listings into squished programs containing the machine code itself, instead of hexadecimal tokens.")
+                .conflicts_with_all(["run", "decompile", "export-tokens"]),
+            arg!(--ambiguities "Warn about text that compiles to a single token but could also be read as several, such as l1 or Sx.")
+                .conflicts_with_all(["run", "decompile", "export-tokens"]),
+            arg!(--tokens <SHEET> "Load a TokenIDE or TI-Toolkit .xml token sheet, or a .json sheet, that adds tokens or renames built-in ones."),
+            arg!(--"export-tokens" <FORMAT> "Write every known token as a TokenIDE xml or json token sheet.")
+                .value_parser(clap::value_parser!(SheetFormat)),
         ])
+        // options for one action are rejected with the others by conflicting with them, since
+        // `requires` is satisfied by any action that conflicts with the required one
         .group(
             ArgGroup::new("action")
             .args([
-                // "run",
+                "run",
                 "decompile",
-                "compile"
+                "compile",
+                "export-tokens"
             ])
             .required(true),
         )
         .get_matches();
 
+    // Token sheets have to be installed before any tokens are used
+    if let Some(path) = matches.get_one::<String>("tokens") {
+        match TokenSheet::read(path).and_then(sheets::install) {
+            Ok(_) => (),
+            Err(e) => {
+                error!("Could not load token sheet {}: {}", path, e);
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Some(&format) = matches.get_one::<SheetFormat>("export-tokens") {
+        let sheet = TokenSheet::byte_tokens().export(format);
+        let written = match matches.get_one::<String>("out") {
+            Some(path) if path != "-" => utils::create_output_file(path, matches.get_flag("force"))
+                .and_then(|mut f| Ok(f.write_all(sheet.as_bytes())?)),
+            _ => io::stdout()
+                .lock()
+                .write_all(sheet.as_bytes())
+                .map_err(anyhow::Error::from),
+        };
+
+        if let Err(e) = written {
+            error!("Unable to write token sheet: {}", e);
+            process::exit(1);
+        }
+        process::exit(0);
+    }
+
     // Attempt to read the content of the specified input file
     let filename = if let Some(filename) = matches.get_one::<String>("decompile") {
         filename
     } else if let Some(filename) = matches.get_one::<String>("compile") {
         filename
-    // } else if let Some(filename) = matches.get_one::<String>("run") {
-    //     filename
+    } else if let Some(filename) = matches.get_one::<String>("run") {
+        filename
     } else {
         error!("Something has gone terribly wrong and the infile name couldn't be read");
         process::exit(1);
@@ -53,8 +145,26 @@ fn main() {
             }
         };
 
-        let ti_file_string = match decompile::decompile(file_data) {
-            Ok(v) => v.join("\n"), // Success, join the result into a string
+        let options = DecompileOptions {
+            lenient: matches.get_flag("lenient"),
+            spelling: if matches.get_flag("unicode") {
+                Spelling::Unicode
+            } else if matches.get_flag("ascii") {
+                Spelling::Ascii
+            } else {
+                Spelling::Names
+            },
+            markup: matches.get_flag("markup"),
+            tokens_in_strings: matches.get_flag("string-tokens"),
+            language: matches
+                .get_one::<Language>("language")
+                .copied()
+                .unwrap_or_default(),
+            asm: matches.get_flag("asm"),
+        };
+
+        let programs = match decompile::decompile_with_options(file_data, &options) {
+            Ok(v) => v,
             Err(e) => {
                 // Error, log the message and exit the program with an 1
                 error!("Could not decompile 8Xp file: {}", e);
@@ -62,10 +172,34 @@ fn main() {
             }
         };
 
+        if let Some(&model) = matches.get_one::<Model>("model") {
+            for program in programs.iter().filter(|p| p.model > model) {
+                warn!(
+                    "{} requires a {}, but the target is a {}",
+                    program.name, program.model, model
+                );
+            }
+        }
+
+        // Files with several programs have each one written separately
+        if programs.len() > 1 {
+            write_programs(
+                &programs,
+                matches.get_one::<String>("out"),
+                matches.get_flag("force"),
+            );
+            process::exit(0);
+        }
+
+        let ti_file_string = programs
+            .first()
+            .map(|program| program.lines.join("\n"))
+            .unwrap_or_default();
+
         // We're decompiling a given input file
         let outfile = match matches.get_one::<String>("out") {
-            Some(v) => v,
-            None => {
+            Some(v) if v != "-" => v,
+            _ => {
                 // If no output file is specified, print to stdout and exit
                 println!("{}", ti_file_string);
                 process::exit(0);
@@ -73,7 +207,9 @@ fn main() {
         };
 
         // Write the decompiled content to the specified output file
-        match fs::write(outfile, ti_file_string) {
+        let written = utils::create_output_file(outfile, matches.get_flag("force"))
+            .and_then(|mut f| Ok(f.write_all(ti_file_string.as_bytes())?));
+        match written {
             Ok(_) => (),
             Err(e) => {
                 error!("Unable to write file: {}", e);
@@ -103,58 +239,96 @@ fn main() {
             }
         };
 
-        let res = match compile::compile_to_bytecode(file_data.iter().map(|s| s.as_str()).collect())
-        {
+        let tokenize = compile::TokenizeOptions {
+            tokens_in_strings: matches.get_flag("string-tokens"),
+            language: matches
+                .get_one::<Language>("language")
+                .copied()
+                .unwrap_or_default(),
+        };
+
+        if matches.get_flag("ambiguities") {
+            for ambiguity in
+                compile::find_ambiguities(file_data.iter().map(|s| s.as_str()).collect(), &tokenize)
+            {
+                warn!("{}", ambiguity);
+            }
+        }
+
+        let lines: Vec<&str> = file_data.iter().map(|s| s.as_str()).collect();
+
+        // Assembly listings are machine code rather than tokens
+        let compiled = if asm::is_listing(&lines) {
+            asm::compile_listing(lines, matches.get_flag("squish"))
+        } else {
+            compile::compile_to_bytecode_with(lines, &tokenize)
+        };
+
+        let res = match compiled {
             Ok(v) => v,
             Err(e) => {
-                error!("Error when compiling: {}", e);
+                match e.downcast_ref::<CompileErrors>() {
+                    Some(errors) => {
+                        for error in &errors.0 {
+                            eprintln!("{}\n", error.render());
+                        }
+                        error!(
+                            "Could not compile {}: found {} unknown token(s)",
+                            filename,
+                            errors.0.len()
+                        );
+                    }
+                    None => error!("Error when compiling: {}", e),
+                }
                 process::exit(1);
             }
         };
 
-        let (header, footer) = match compile::create_metadata(&res, program_name) {
-            Ok((h, f)) => (h, f),
+        let mut options = compile::CompileOptions::new(program_name);
+        if let Some(comment) = matches.get_one::<String>("comment") {
+            options.comment = comment.clone();
+        }
+        options.locked = matches.get_flag("locked");
+        options.archived = matches.get_flag("archived");
+        if let Some(&version) = matches.get_one::<u8>("version-byte") {
+            options.version = version;
+        }
+        if let Some(&model) = matches.get_one::<Model>("model") {
+            options.model = model;
+        }
+
+        let ti_file = match compile::create_file(&res, &options) {
+            Ok(v) => v,
             Err(e) => {
                 error!("Error when compiling: {}", e);
                 process::exit(1);
             }
         };
 
-        let ti_file = TIFile {
-            header,
-            data: res,
-            footer: footer.to_vec(),
+        let written = match matches.get_one::<String>("out").map(String::as_str) {
+            Some("-") => ti_file.write_to(&mut io::stdout().lock()),
+            out => match compiled_file_path(out, &ti_file) {
+                Ok(path) => ti_file.write_to_path(path, matches.get_flag("force")),
+                Err(e) => Err(e),
+            },
         };
 
-        match ti_file.write_to_file() {
+        match written {
             Ok(_) => (),
             Err(e) => {
                 error!("Error when writing to file: {}", e);
                 process::exit(1);
             }
         };
-        // } else if matches.contains_id("run") {
-        //     let file_data = match utils::read_file_bytes(filename) {
-        //         Ok(v) => v, // Success, store the file data
-        //         Err(e) => {
-        //             // Error, log the message and exit the program with an 1
-        //             error!("Could not read file: {}", e);
-        //             process::exit(1);
-        //         }
-        //     };
-
-        //     let ti_program = match decompile::read_binary_data(file_data) {
-        //         Ok(v) => v,
-        //         Err(e) => {
-        //             error!("Could not parse binary data: {}", e);
-        //             process::exit(1);
-        //         }
-        //     };
-
-        //     let mut bytecode = interpreter::Interpreter::new(&ti_program).unwrap();
-        //     bytecode.parse_bytes();
-        //     println!("{:?}", bytecode);
-        //     bytecode.interpret_bytes(None);
+    } else if matches.contains_id("run") {
+        run_file(
+            filename,
+            matches.get_flag("lenient"),
+            matches.get_one::<Model>("model").copied(),
+            matches.get_one::<String>("screenshot").map(String::as_str),
+            matches.get_one::<String>("keys").map(String::as_str),
+            matches.get_one::<u64>("seed").copied(),
+        );
     }
 
     // // Check if the file data is valid UTF-8 or not
@@ -214,3 +388,169 @@ fn main() {
     //     println!("{:?}", ti_file.write_to_file());
     // }
 }
+
+/// Finds the path that a compiled file should be written to. Without `out`, this is `NAME.8XP` in
+/// the current directory. If `out` is a directory or ends with a path separator, the file is
+/// written to `NAME.8XP` inside of it. Missing directories are created.
+fn compiled_file_path(out: Option<&str>, ti_file: &TIFile) -> Result<PathBuf, anyhow::Error> {
+    let out = match out {
+        Some(v) => v,
+        None => return Ok(PathBuf::from(ti_file.default_filename()?)),
+    };
+
+    let path = Path::new(out);
+    if path.is_dir() || out.ends_with(std::path::is_separator) {
+        fs::create_dir_all(path)?;
+        return Ok(path.join(ti_file.default_filename()?));
+    }
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    Ok(path.to_path_buf())
+}
+
+/// Writes the programs decompiled from a file containing more than one program. If `outdir` is
+/// given, each program is written to `NAME.txt` inside of it, with the name made safe by
+/// [`utils::file_stem`] and `NAME-2.txt` and so on used for repeated names. Otherwise they are
+/// printed to stdout one after another. Existing files are only replaced if `force` is true.
+fn write_programs(programs: &[DecompiledProgram], outdir: Option<&String>, force: bool) {
+    let outdir = match outdir {
+        Some(v) if v != "-" => v,
+        _ => {
+            for program in programs {
+                println!("==> {} <==", program.name);
+                println!("{}\n", program.lines.join("\n"));
+            }
+            return;
+        }
+    };
+
+    if let Err(e) = fs::create_dir_all(outdir) {
+        error!("Unable to create directory: {}", e);
+        process::exit(1);
+    }
+
+    // programs with the same name are written to NAME.txt, NAME-2.txt and so on
+    let mut used = HashSet::new();
+    for program in programs {
+        let stem = utils::file_stem(&program.name);
+        let mut filename = format!("{}.txt", stem);
+        let mut count = 1;
+        while !used.insert(filename.clone()) {
+            count += 1;
+            filename = format!("{}-{}.txt", stem, count);
+        }
+
+        let path = Path::new(outdir).join(filename);
+        let written = utils::create_output_file(&path, force)
+            .and_then(|mut f| Ok(f.write_all(program.lines.join("\n").as_bytes())?));
+        if let Err(e) = written {
+            error!("Unable to write file {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Interprets the 8XP file at `filename`, exiting with an error message if it fails. If `lenient`
+/// is true, files with incorrect sizes or checksums are still run. The home and graph screens are
+/// the size of `model`'s, or a TI-84 Plus's if no model is given. If `screenshot` is given, the
+/// graph screen is saved to it once the program stops, even if it stopped with an error. If `keys`
+/// is given, input is read from the key script in that file instead of stdin. If `seed` is given,
+/// the random number generator is seeded with it.
+#[cfg(feature = "interpreter")]
+fn run_file(
+    filename: &str,
+    lenient: bool,
+    model: Option<Model>,
+    screenshot: Option<&str>,
+    keys: Option<&str>,
+    seed: Option<u64>,
+) {
+    let file_data = match utils::read_file_bytes(filename) {
+        Ok(v) => v, // Success, store the file data
+        Err(e) => {
+            // Error, log the message and exit the program with an 1
+            error!("Could not read file: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let ti_program = if lenient {
+        decompile::read_binary_data_lenient(file_data).map(|(file, _)| file)
+    } else {
+        decompile::read_binary_data(file_data)
+    };
+
+    let ti_program = match ti_program {
+        Ok(v) => v,
+        Err(e) => {
+            error!("Could not parse binary data: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let mut interpreter = match interpreter::Interpreter::new(&ti_program) {
+        Ok(v) => v,
+        Err(e) => {
+            error!("Could not load program: {}", e);
+            process::exit(1);
+        }
+    };
+
+    if let Some(model) = model {
+        interpreter.home = HomeScreen::for_model(model);
+        interpreter.graph = GraphScreen::for_model(model);
+    }
+
+    if let Some(seed) = seed {
+        interpreter.seed_random(seed);
+    }
+
+    if let Some(keys) = keys {
+        let script = fs::read_to_string(keys)
+            .map_err(anyhow::Error::from)
+            .and_then(|script| script.parse::<KeyScript>());
+        match script {
+            Ok(script) => interpreter.input = Box::new(script),
+            Err(e) => {
+                error!("Could not read key script {}: {}", keys, e);
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Err(e) = interpreter.parse_bytes() {
+        error!("Could not parse program: {}", e);
+        process::exit(1);
+    }
+
+    let result = interpreter.interpret_bytes(None);
+
+    if let Some(path) = screenshot {
+        if let Err(e) = interpreter.graph.save(path) {
+            error!("Could not save screenshot: {}", e);
+            process::exit(1);
+        }
+    }
+
+    if let Err(e) = result {
+        error!("{:#}", e);
+        process::exit(1);
+    }
+}
+
+/// Exits with an error message, since the interpreter was not compiled in.
+#[cfg(not(feature = "interpreter"))]
+fn run_file(
+    _filename: &str,
+    _lenient: bool,
+    _model: Option<Model>,
+    _screenshot: Option<&str>,
+    _keys: Option<&str>,
+    _seed: Option<u64>,
+) {
+    error!("TiO2 was built without the `interpreter` feature, so programs cannot be run.");
+    process::exit(1);
+}
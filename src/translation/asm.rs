@@ -0,0 +1,315 @@
+//! The `asm` module detects programs that contain machine code instead of TI-BASIC, and converts
+//! them to and from hexadecimal listings.
+//!
+//! Assembly programs are stored in one of two forms:
+//!
+//! * Unsquished programs start with a token such as `AsmPrgm`, followed by the machine code
+//!   written in hexadecimal with the digit and letter tokens, one or more lines at a time. This
+//!   is the form that is typed into the program editor and run with `Asm(`.
+//! * Squished programs start with two magic bytes, such as `BB6D`, followed by the machine code
+//!   itself. This is the form that `AsmComp(` and assemblers produce.
+//!
+//! The TI-84 Plus CE only runs squished programs, which start with `Asm84CECmp`.
+
+use std::fmt;
+
+use super::{models::Model, tokens::Byte};
+
+/// The calculators that an assembly program is written for, which decides how it starts.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{asm::AsmTarget, tokens::Byte};
+///
+/// assert_eq!(AsmTarget::TI83Plus.header(), Byte::Double([0xBB, 0x6C]));
+/// assert_eq!(AsmTarget::TI83Plus.magic(), [0xBB, 0x6D]);
+/// assert_eq!(AsmTarget::TI84PlusCE.to_string(), "Asm84CEPrgm");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AsmTarget {
+    /// The TI-83 Plus and TI-84 Plus, which run Z80 code
+    TI83Plus,
+    /// The TI-84 Plus C Silver Edition, which runs Z80 code
+    TI84PlusCSE,
+    /// The TI-84 Plus CE, which runs eZ80 code
+    TI84PlusCE,
+}
+
+impl AsmTarget {
+    /// Every target, from oldest to newest.
+    pub const ALL: [AsmTarget; 3] = [
+        AsmTarget::TI83Plus,
+        AsmTarget::TI84PlusCSE,
+        AsmTarget::TI84PlusCE,
+    ];
+
+    /// Returns the token that starts unsquished programs and hexadecimal listings.
+    pub fn header(self) -> Byte {
+        match self {
+            AsmTarget::TI83Plus => Byte::Double([0xBB, 0x6C]),
+            AsmTarget::TI84PlusCSE => Byte::Double([0xEF, 0x68]),
+            AsmTarget::TI84PlusCE => Byte::Double([0xEF, 0x7A]),
+        }
+    }
+
+    /// Returns the bytes that start squished programs.
+    pub fn magic(self) -> [u8; 2] {
+        match self {
+            AsmTarget::TI83Plus => [0xBB, 0x6D],
+            AsmTarget::TI84PlusCSE => [0xEF, 0x69],
+            AsmTarget::TI84PlusCE => [0xEF, 0x7B],
+        }
+    }
+
+    /// Returns whether programs for this target can be stored unsquished, which is every target
+    /// except the TI-84 Plus CE.
+    pub fn can_unsquish(self) -> bool {
+        self != AsmTarget::TI84PlusCE
+    }
+
+    /// Returns the oldest calculator model that runs programs for this target.
+    pub fn model(self) -> Model {
+        match self {
+            AsmTarget::TI83Plus => Model::TI83Plus,
+            AsmTarget::TI84PlusCSE => Model::TI84PlusCSE,
+            AsmTarget::TI84PlusCE => Model::TI84PlusCE,
+        }
+    }
+}
+
+impl fmt::Display for AsmTarget {
+    /// Writes the name of the [`AsmTarget::header`] token, which is the first line of a listing.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            AsmTarget::TI83Plus => "AsmPrgm",
+            AsmTarget::TI84PlusCSE => "Asm84CPrgm",
+            AsmTarget::TI84PlusCE => "Asm84CEPrgm",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// What the data of a program contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Payload {
+    /// TI-BASIC tokens
+    Basic,
+    /// Machine code written in hexadecimal after [`AsmTarget::header`]
+    Unsquished(AsmTarget),
+    /// Machine code after [`AsmTarget::magic`]
+    Squished(AsmTarget),
+}
+
+/// Works out whether the data of a program is TI-BASIC or an assembly program.
+///
+/// # Arguments
+///
+/// * `data` - The tokens of a program, without the header or the length of the program.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::asm::{detect, AsmTarget, Payload};
+///
+/// // AsmPrgm, C9
+/// assert_eq!(detect(&[0xBB, 0x6C, 0x3F, 0x43, 0x39]), Payload::Unsquished(AsmTarget::TI83Plus));
+/// // ret
+/// assert_eq!(detect(&[0xBB, 0x6D, 0xC9]), Payload::Squished(AsmTarget::TI83Plus));
+/// assert_eq!(detect(&[0xEF, 0x7B, 0xC9]), Payload::Squished(AsmTarget::TI84PlusCE));
+/// // Disp 1
+/// assert_eq!(detect(&[0xDE, 0x31]), Payload::Basic);
+/// ```
+pub fn detect(data: &[u8]) -> Payload {
+    for target in AsmTarget::ALL {
+        if data.starts_with(&target.magic()) {
+            return Payload::Squished(target);
+        }
+        if target.can_unsquish() && data.starts_with(&target.header().to_bytes()) {
+            return Payload::Unsquished(target);
+        }
+    }
+
+    Payload::Basic
+}
+
+/// The number of bytes of machine code on each line of a listing of a squished program.
+const BYTES_PER_LINE: usize = 16;
+
+/// Converts an assembly program into a hexadecimal listing, which is the name of its
+/// [`AsmTarget::header`] followed by the machine code in hexadecimal, the way unsquished programs
+/// are written on the calculator.
+///
+/// Unsquished programs keep their lines. Squished programs are written with 16 bytes on each
+/// line.
+///
+/// # Arguments
+///
+/// * `data` - The tokens of a program, without the header or the length of the program.
+///
+/// # Returns
+///
+/// The lines of the listing, or [`None`] if the program is TI-BASIC, or an unsquished program that
+/// contains something other than hexadecimal, such as data after an `End` line.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::asm::to_listing;
+///
+/// // ld a,1 / ret
+/// assert_eq!(to_listing(&[0xBB, 0x6D, 0x3E, 0x01, 0xC9]).unwrap(), vec!["AsmPrgm", "3E01C9"]);
+/// assert_eq!(
+///     to_listing(&[0xBB, 0x6C, 0x3F, 0x33, 0x45, 0x30, 0x31, 0x3F, 0x43, 0x39]).unwrap(),
+///     vec!["AsmPrgm", "3E01", "C9"]
+/// );
+/// assert_eq!(to_listing(&[0xDE, 0x31]), None);
+/// ```
+pub fn to_listing(data: &[u8]) -> Option<Vec<String>> {
+    let mut lines = Vec::new();
+
+    match detect(data) {
+        Payload::Basic => return None,
+        Payload::Squished(target) => {
+            lines.push(target.to_string());
+            lines.extend(data[2..].chunks(BYTES_PER_LINE).map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|byte| format!("{:02X}", byte))
+                    .collect::<String>()
+            }));
+        }
+        Payload::Unsquished(target) => {
+            let code = &data[target.header().to_bytes().len()..];
+            // the digits and letters are the tokens with the same ASCII codes
+            let is_hex = |byte: &u8| byte.is_ascii_digit() || (b'A'..=b'F').contains(byte);
+            if !code.iter().all(|byte| *byte == 0x3F || is_hex(byte)) {
+                return None;
+            }
+
+            // the header is followed by a newline, which starts the first line of code
+            lines.push(target.to_string());
+            let mut code_lines = code.split(|byte| *byte == 0x3F);
+            if let Some(first) = code_lines.next() {
+                if !first.is_empty() {
+                    return None;
+                }
+            }
+            lines.extend(code_lines.map(|line| String::from_utf8_lossy(line).into_owned()));
+        }
+    }
+
+    Some(lines)
+}
+
+/// Checks whether source code is a hexadecimal listing, which is a line containing the name of
+/// an [`AsmTarget::header`] followed by lines of hexadecimal digits.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::asm::is_listing;
+///
+/// assert!(is_listing(&["AsmPrgm", "3E01", "c9"]));
+/// assert!(!is_listing(&["AsmPrgm", "Disp 1"]));
+/// assert!(!is_listing(&["Disp 1"]));
+/// ```
+pub fn is_listing(lines: &[&str]) -> bool {
+    listing_target(lines).is_some()
+        && lines[1..]
+            .iter()
+            .all(|line| line.trim().chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Finds the target of a listing from its first line.
+fn listing_target(lines: &[&str]) -> Option<AsmTarget> {
+    let first = lines.first()?.trim();
+
+    AsmTarget::ALL
+        .into_iter()
+        .find(|target| target.to_string() == first)
+}
+
+/// Compiles a hexadecimal listing, such as the output of [`to_listing`], into the data of an
+/// assembly program.
+///
+/// # Arguments
+///
+/// * `lines` - The lines of the listing, starting with the name of an [`AsmTarget::header`].
+/// * `squish` - Whether the program is stored squished, with the machine code itself, or
+///   unsquished, with the hexadecimal digits as tokens. Programs for the TI-84 Plus CE are always
+///   squished.
+///
+/// # Returns
+///
+/// The tokens of the program, without the length of the program.
+///
+/// # Errors
+///
+/// Returns an error if the listing doesn't start with a header, if a line contains something
+/// other than hexadecimal digits, or if a squished program has an odd number of digits.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::asm::compile_listing;
+///
+/// let listing = vec!["AsmPrgm", "3E01", "C9"];
+///
+/// assert_eq!(compile_listing(listing.clone(), true).unwrap(), [0xBB, 0x6D, 0x3E, 0x01, 0xC9]);
+/// assert_eq!(
+///     compile_listing(listing, false).unwrap(),
+///     [0xBB, 0x6C, 0x3F, 0x33, 0x45, 0x30, 0x31, 0x3F, 0x43, 0x39]
+/// );
+/// assert!(compile_listing(vec!["AsmPrgm", "C"], true).is_err());
+/// ```
+pub fn compile_listing(lines: Vec<&str>, squish: bool) -> Result<Vec<u8>, anyhow::Error> {
+    let target = match listing_target(&lines) {
+        Some(target) => target,
+        None => {
+            return Err(anyhow::Error::msg(
+                "Assembly listings must start with AsmPrgm, Asm84CPrgm or Asm84CEPrgm",
+            ))
+        }
+    };
+
+    let mut digits: Vec<&str> = Vec::with_capacity(lines.len() - 1);
+    for (index, line) in lines.iter().enumerate().skip(1) {
+        let line = line.trim();
+        if let Some(c) = line.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(anyhow::Error::msg(format!(
+                "Line {} of the assembly listing contains {:?}, which is not a hexadecimal digit",
+                index + 1,
+                c
+            )));
+        }
+        digits.push(line);
+    }
+
+    if squish || !target.can_unsquish() {
+        let hex = digits.concat();
+        if hex.len() % 2 != 0 {
+            return Err(anyhow::Error::msg(
+                "Squished assembly programs must have an even number of hexadecimal digits",
+            ));
+        }
+
+        let mut data = target.magic().to_vec();
+        for pair in hex.as_bytes().chunks(2) {
+            // the pair is two ASCII hexadecimal digits, which was checked above
+            let pair = std::str::from_utf8(pair).unwrap_or_default();
+            data.push(u8::from_str_radix(pair, 16)?);
+        }
+
+        return Ok(data);
+    }
+
+    let mut data = target.header().to_bytes();
+    for line in digits {
+        data.push(0x3F);
+        // the digits and letters are the tokens with the same ASCII codes
+        data.extend(line.to_ascii_uppercase().bytes());
+    }
+
+    Ok(data)
+}
@@ -2,59 +2,749 @@
 //! for managing TI-84 Plus calculator files in the 8XP format. It includes
 //! methods for writing data to a file in the appropriate format and extracting
 //! the program name from the file header.
+//!
+//! A file is made up of a 55-byte [`FileHeader`] followed by one or more variable entries, each
+//! of which is represented by a [`VarEntry`] with its own [`VarHeader`]. Most files contain a
+//! single program, but group exports and multi-program transfers contain several entries.
+//!
+//! The headers are parsed and serialized losslessly, so reading a file and writing it back out
+//! produces the same header bytes, even if some of the size fields are wrong. Use
+//! [`TIFile::update_sizes`] to recalculate them after editing a file.
 
-use std::{fs::File, io::Write, string::FromUtf8Error};
+use std::{fmt, io::Write, path::Path, string::FromUtf8Error};
+
+use crate::{errors::FileError, utils::create_output_file};
 
 /// A helper struct for managing TI-84 Plus calculator files (8XP format).
 ///
 /// [`TIFile`] represents a file in the 8XP format used by TI-84 Plus calculators. It provides
 /// methods for writing data to a file and extracting the program name from the file header.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::common::{TIFile, VarEntry};
+///
+/// // Disp "HI"
+/// let file = TIFile::new(vec![VarEntry::program("hello", &[0xDE, 0x2A, 0x48, 0x49, 0x2A])]);
+///
+/// assert_eq!(file.extract_program_name().unwrap(), "HELLO");
+/// assert_eq!(file.header.entries_length, 19 + 5);
+/// assert_eq!(file.to_bytes().len(), 55 + 19 + 5 + 2);
+/// ```
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct TIFile {
-    /// The header that provides information on the compiled file
-    pub header: [u8; 74],
-    /// The core file data
+    /// The header at the start of the file
+    pub header: FileHeader,
+    /// The variables stored in the file
+    pub entries: Vec<VarEntry>,
+}
+
+/// The 55-byte header at the start of every TI-8XP file.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::common::FileHeader;
+///
+/// let header = FileHeader::default();
+/// let bytes = header.to_bytes();
+///
+/// assert_eq!(FileHeader::parse(&bytes), header);
+/// assert_eq!(header.comment(), "File compiled by TiO2 from TabulateJarl8");
+/// ```
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct FileHeader {
+    /// The signature, which should be [`FILE_HEADER`] followed by a NULL byte
+    pub signature: [u8; 11],
+    /// The NULL padded comment
+    pub comment: [u8; 42],
+    /// The combined length of every variable entry in the file
+    pub entries_length: u16,
+}
+
+/// The type of a variable, stored in its [`VarHeader`].
+///
+/// See the "File Types" table in `8xp_file_structure.md`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum VarType {
+    Real,
+    List,
+    Matrix,
+    /// Called a "Y-Var" on older calculators
+    Equation,
+    String,
+    Program,
+    /// A program that can't be edited on the calculator. Assembly programs usually use this.
+    LockedProgram,
+    Picture,
+    GraphDatabase,
+    Complex,
+    WindowSetup,
+    ZoomSettings,
+    TableSetup,
+    Backup,
+    AppVar,
+    Group,
+    FlashApplication,
+    /// A type byte that isn't in the table
+    Unknown(u8),
+}
+
+/// The header of a single variable entry.
+///
+/// Entries from older calculators have a shorter header without the version and flag bytes,
+/// which is recorded in `header_length` so that it can be written back out the same way.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::common::{VarHeader, VarType};
+///
+/// // the header of a program called A, containing a single token
+/// let bytes = [
+///     0x0D, 0x00, 0x03, 0x00, 0x05, 0x41, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x80, 0x03, 0x00,
+/// ];
+/// let header = VarHeader::parse(&bytes).unwrap();
+///
+/// assert_eq!(header.var_type, VarType::Program);
+/// assert_eq!(header.extract_name().unwrap(), "A");
+/// assert!(header.is_archived());
+/// assert_eq!(header.to_bytes(), bytes);
+/// ```
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct VarHeader {
+    /// The length of the header, not including this field or the repeated data length. This is
+    /// [`VAR_HEADER_LENGTH`], or [`SHORT_VAR_HEADER_LENGTH`] for older calculators.
+    pub header_length: u16,
+    /// The length of the variable's data
+    pub data_length: u16,
+    /// The type of the variable
+    pub var_type: VarType,
+    /// The NULL padded name of the variable
+    pub name: [u8; 8],
+    /// The version byte, which is `0x00` for everything on the TI-84
+    pub version: u8,
+    /// The archived flag, which is `0x80` for archived variables and `0x00` otherwise
+    pub flag: u8,
+    /// The length of the variable's data, repeated after the header
+    pub data_length_repeated: u16,
+}
+
+/// A single variable stored in a [`TIFile`], such as a program or a list.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct VarEntry {
+    /// The header describing the variable
+    pub header: VarHeader,
+    /// The data of the variable. For programs, this starts with the two byte length of the
+    /// program, followed by its tokens.
     pub data: Vec<u8>,
-    /// The 2-byte footer (file checksum)
-    pub footer: Vec<u8>,
 }
 
 impl TIFile {
+    /// Creates a new [`TIFile`] containing the given entries, with the default comment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the entries are too large for the size fields. Use [`TIFile::try_new`] for
+    /// entries that might be.
+    pub fn new(entries: Vec<VarEntry>) -> Self {
+        Self::try_new(entries).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a new [`TIFile`] containing the given entries, with the default comment.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FileError::TooLarge`] if the entries are too large for the size fields, as
+    /// described in [`TIFile::update_sizes`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::common::{TIFile, VarEntry};
+    ///
+    /// // the entries take up 2 * (17 + 2 + 40000) bytes, more than the 65535 that fit in the
+    /// // entries length
+    /// let program = VarEntry::program("BIG", &[0x30; 40000]);
+    ///
+    /// assert!(TIFile::try_new(vec![program.clone()]).is_ok());
+    /// assert!(TIFile::try_new(vec![program.clone(), program]).is_err());
+    /// ```
+    pub fn try_new(entries: Vec<VarEntry>) -> Result<Self, FileError> {
+        let mut file = Self {
+            header: FileHeader::default(),
+            entries,
+        };
+        file.update_sizes()?;
+
+        Ok(file)
+    }
+
+    /// Recalculates every size field in the file header and the headers of the entries from the
+    /// data they describe.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FileError::TooLarge`] if the data of an entry is longer than 65535 bytes, or
+    /// the entries take up more than 65535 bytes altogether, since the size fields are two bytes
+    /// long. The sizes that fit are still updated.
+    pub fn update_sizes(&mut self) -> Result<(), FileError> {
+        let mut result = Ok(());
+        let mut position = FileHeader::LENGTH;
+        for entry in &mut self.entries {
+            if let Err(FileError::TooLarge {
+                field,
+                offset,
+                size,
+            }) = entry.update_sizes()
+            {
+                result = result.and(Err(FileError::TooLarge {
+                    field,
+                    offset: position + offset,
+                    size,
+                }));
+            }
+            position += entry.byte_length();
+        }
+
+        let entries_length: usize = self.entries.iter().map(VarEntry::byte_length).sum();
+        match u16::try_from(entries_length) {
+            Ok(length) => self.header.entries_length = length,
+            Err(_) => {
+                result = result.and(Err(FileError::TooLarge {
+                    field: "Entries length",
+                    offset: 53,
+                    size: entries_length,
+                }))
+            }
+        }
+
+        result
+    }
+
+    /// Converts the [`TIFile`] into the bytes of an 8XP file.
+    ///
+    /// The headers are written exactly as they are stored, and the checksum in the footer is
+    /// calculated from the entries.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let entries: Vec<u8> = self.entries.iter().flat_map(VarEntry::to_bytes).collect();
+
+        [
+            &self.header.to_bytes()[..],
+            &entries,
+            &checksum(&entries).to_le_bytes(),
+        ]
+        .concat()
+    }
+
+    /// Write the bytes of the [`TIFile`] to any [`Write`] sink, such as a file, a buffer or
+    /// standard output.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The sink that the bytes returned by [`TIFile::to_bytes`] are written to.
+    ///
+    /// # Returns
+    ///
+    /// A [`Result`] indicating success, or an [`anyhow::Error`] if writing failed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::common::{TIFile, VarEntry};
+    ///
+    /// let file = TIFile::new(vec![VarEntry::program("HI", &[0xDE, 0x2A, 0x48, 0x49, 0x2A])]);
+    ///
+    /// let mut buffer = Vec::new();
+    /// file.write_to(&mut buffer).unwrap();
+    ///
+    /// assert_eq!(buffer, file.to_bytes());
+    /// ```
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), anyhow::Error> {
+        writer.write_all(&self.to_bytes())?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Write the content of the [`TIFile`] to the file at `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to write.
+    /// * `overwrite` - Whether an existing file at `path` should be replaced.
+    ///
+    /// # Returns
+    ///
+    /// A [`Result`] indicating success, or an [`anyhow::Error`] if the file already exists and
+    /// `overwrite` is `false`, or if the file couldn't be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::common::{TIFile, VarEntry};
+    ///
+    /// let file = TIFile::new(vec![VarEntry::program("HI", &[0xDE, 0x2A, 0x48, 0x49, 0x2A])]);
+    /// let path = std::env::temp_dir().join("tio2_write_to_path_doctest.8xp");
+    /// let _ = std::fs::remove_file(&path);
+    ///
+    /// file.write_to_path(&path, false).unwrap();
+    /// assert!(file.write_to_path(&path, false).is_err());
+    /// file.write_to_path(&path, true).unwrap();
+    ///
+    /// assert_eq!(std::fs::read(&path).unwrap(), file.to_bytes());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn write_to_path(
+        &self,
+        path: impl AsRef<Path>,
+        overwrite: bool,
+    ) -> Result<(), anyhow::Error> {
+        let mut f = create_output_file(path, overwrite)?;
+        self.write_to(&mut f)
+    }
+
+    /// Returns the default filename for the [`TIFile`], which is the name of the first variable
+    /// followed by the `.8XP` extension.
+    pub fn default_filename(&self) -> Result<String, FromUtf8Error> {
+        Ok(self.extract_program_name()? + ".8XP")
+    }
+
     /// Write the content of the [`TIFile`] to a file with the appropriate extension.
     ///
-    /// This method creates a file with the program's name in the 8XP format used by TI-84 Plus
-    /// calculators. It writes the header, data, and footer to the file.
+    /// This method creates a file with the name of the first variable in the 8XP format used by
+    /// TI-84 Plus calculators in the current directory, replacing it if it already exists. Use
+    /// [`TIFile::write_to_path`] to choose the path or to avoid replacing existing files.
     ///
     /// # Returns
     ///
     /// A [`Result`] indicating success if the data was successfully written to the file,
     /// or an [`anyhow::Error`] if an error occurred.
     pub fn write_to_file(&self) -> Result<(), anyhow::Error> {
-        let program_name = self.extract_program_name()?;
-        let mut f = File::create(program_name + ".8XP")?;
-        f.write_all(&self.header)?;
-        f.write_all(&self.data)?;
-        f.write_all(&self.footer)?;
-
-        Ok(())
+        self.write_to_path(self.default_filename()?, true)
     }
 
-    /// Extract the program name from the file header.
+    /// Extract the name of the first variable in the file.
     ///
-    /// This method extracts the program name from the header of the [`TIFile`] and returns it as a
-    /// [`String`]. It trims any NULL bytes from the result.
+    /// This method extracts the name of the first [`VarEntry`] and returns it as a [`String`].
+    /// If the file has no entries, an empty [`String`] is returned.
     ///
     /// # Returns
     ///
     /// A [`Result`] containing the program name as a [`String`] if successful, or a
     /// [`FromUtf8Error`] if the extraction fails.
     pub fn extract_program_name(&self) -> Result<String, FromUtf8Error> {
-        let result = String::from_utf8(self.header[60..68].to_vec())?;
+        match self.entries.first() {
+            Some(entry) => entry.header.extract_name(),
+            None => Ok(String::new()),
+        }
+    }
+
+    /// Returns an iterator over the entries in the file that are programs.
+    pub fn programs(&self) -> impl Iterator<Item = &VarEntry> {
+        self.entries.iter().filter(|e| e.is_program())
+    }
+}
+
+impl FileHeader {
+    /// The length of the file header in bytes
+    pub const LENGTH: usize = 55;
+
+    /// Parses a file header. The signature isn't checked.
+    pub fn parse(bytes: &[u8; Self::LENGTH]) -> Self {
+        let mut signature = [0; 11];
+        signature.copy_from_slice(&bytes[..11]);
+        let mut comment = [0; 42];
+        comment.copy_from_slice(&bytes[11..53]);
+
+        Self {
+            signature,
+            comment,
+            entries_length: u16::from_le_bytes([bytes[53], bytes[54]]),
+        }
+    }
+
+    /// Converts the header into bytes.
+    pub fn to_bytes(&self) -> [u8; Self::LENGTH] {
+        let mut bytes = [0; Self::LENGTH];
+        bytes[..11].copy_from_slice(&self.signature);
+        bytes[11..53].copy_from_slice(&self.comment);
+        bytes[53..].copy_from_slice(&self.entries_length.to_le_bytes());
+
+        bytes
+    }
+
+    /// Checks if the signature is [`FILE_HEADER`]. The NULL byte that follows it isn't checked.
+    pub fn has_valid_signature(&self) -> bool {
+        self.signature.starts_with(&FILE_HEADER)
+    }
+
+    /// Returns the comment, with any NULL padding removed. Bytes that aren't valid UTF-8 are
+    /// replaced.
+    pub fn comment(&self) -> String {
+        String::from_utf8_lossy(&self.comment)
+            .trim_end_matches(char::from(0))
+            .to_string()
+    }
+
+    /// Sets the comment, padding it with NULL bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the comment is longer than the 42-byte comment field.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::common::FileHeader;
+    ///
+    /// let mut header = FileHeader::default();
+    /// header.set_comment("Built by CI").unwrap();
+    ///
+    /// assert_eq!(header.comment(), "Built by CI");
+    /// assert!(header.set_comment(&"x".repeat(43)).is_err());
+    /// ```
+    pub fn set_comment(&mut self, comment: &str) -> Result<(), anyhow::Error> {
+        let bytes = comment.as_bytes();
+        if bytes.len() > self.comment.len() {
+            return Err(anyhow::Error::msg(format!(
+                "Comment is {} bytes long, but can be at most {} bytes",
+                bytes.len(),
+                self.comment.len()
+            )));
+        }
+
+        self.comment = [0; 42];
+        self.comment[..bytes.len()].copy_from_slice(bytes);
+
+        Ok(())
+    }
+}
+
+impl Default for FileHeader {
+    /// Creates a header with a valid signature, the default comment, and no entries.
+    fn default() -> Self {
+        let mut signature = [0; 11];
+        signature[..10].copy_from_slice(&FILE_HEADER);
+
+        Self {
+            signature,
+            comment: *DEFAULT_COMMENT,
+            entries_length: 0,
+        }
+    }
+}
+
+impl VarType {
+    /// Checks if the type is in the table of known types.
+    pub fn is_known(&self) -> bool {
+        !matches!(self, VarType::Unknown(_))
+    }
+}
+
+impl From<u8> for VarType {
+    fn from(byte: u8) -> Self {
+        match byte {
+            0x00 => VarType::Real,
+            0x01 => VarType::List,
+            0x02 => VarType::Matrix,
+            0x03 => VarType::Equation,
+            0x04 => VarType::String,
+            0x05 => VarType::Program,
+            0x06 => VarType::LockedProgram,
+            0x07 => VarType::Picture,
+            0x08 => VarType::GraphDatabase,
+            0x0C => VarType::Complex,
+            0x0F => VarType::WindowSetup,
+            0x10 => VarType::ZoomSettings,
+            0x11 => VarType::TableSetup,
+            0x13 => VarType::Backup,
+            0x15 => VarType::AppVar,
+            0x17 => VarType::Group,
+            0x24 => VarType::FlashApplication,
+            other => VarType::Unknown(other),
+        }
+    }
+}
+
+impl From<VarType> for u8 {
+    fn from(var_type: VarType) -> Self {
+        match var_type {
+            VarType::Real => 0x00,
+            VarType::List => 0x01,
+            VarType::Matrix => 0x02,
+            VarType::Equation => 0x03,
+            VarType::String => 0x04,
+            VarType::Program => 0x05,
+            VarType::LockedProgram => 0x06,
+            VarType::Picture => 0x07,
+            VarType::GraphDatabase => 0x08,
+            VarType::Complex => 0x0C,
+            VarType::WindowSetup => 0x0F,
+            VarType::ZoomSettings => 0x10,
+            VarType::TableSetup => 0x11,
+            VarType::Backup => 0x13,
+            VarType::AppVar => 0x15,
+            VarType::Group => 0x17,
+            VarType::FlashApplication => 0x24,
+            VarType::Unknown(byte) => byte,
+        }
+    }
+}
+
+impl fmt::Display for VarType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VarType::Unknown(byte) => write!(f, "Unknown (0x{:02X})", byte),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+impl VarHeader {
+    /// Creates a header for a variable of the given type, with sizes for `data_length` bytes of
+    /// data.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_type` - The type of the variable.
+    /// * `name` - The name of the variable. This is converted to uppercase and truncated to 8
+    ///   characters.
+    /// * `data_length` - The length of the variable's data.
+    pub fn new(var_type: VarType, name: &str, data_length: u16) -> Self {
+        let mut name_bytes = [0; 8];
+        for (byte, c) in name_bytes.iter_mut().zip(name.to_ascii_uppercase().bytes()) {
+            *byte = c;
+        }
+
+        Self {
+            header_length: VAR_HEADER_LENGTH,
+            data_length,
+            var_type,
+            name: name_bytes,
+            version: 0x00,
+            flag: 0x00,
+            data_length_repeated: data_length,
+        }
+    }
+
+    /// Parses a variable header from the start of `data`.
+    ///
+    /// # Returns
+    ///
+    /// The header, or [`None`] if `data` is too short or the header length isn't
+    /// [`VAR_HEADER_LENGTH`] or [`SHORT_VAR_HEADER_LENGTH`].
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+
+        if data.len() < 2 {
+            return None;
+        }
+
+        let header_length = read_u16(0);
+        let long = match header_length {
+            VAR_HEADER_LENGTH => true,
+            SHORT_VAR_HEADER_LENGTH => false,
+            _ => return None,
+        };
+
+        if data.len() < 2 + header_length as usize + 2 {
+            return None;
+        }
+
+        let mut name = [0; 8];
+        name.copy_from_slice(&data[5..13]);
+
+        let (version, flag) = if long { (data[13], data[14]) } else { (0, 0) };
+
+        Some(Self {
+            header_length,
+            data_length: read_u16(2),
+            var_type: VarType::from(data[4]),
+            name,
+            version,
+            flag,
+            data_length_repeated: read_u16(2 + header_length as usize),
+        })
+    }
+
+    /// Converts the header into bytes, including the header length and the repeated data length.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.byte_length());
+        bytes.extend_from_slice(&self.header_length.to_le_bytes());
+        bytes.extend_from_slice(&self.data_length.to_le_bytes());
+        bytes.push(self.var_type.into());
+        bytes.extend_from_slice(&self.name);
+        if self.header_length != SHORT_VAR_HEADER_LENGTH {
+            bytes.push(self.version);
+            bytes.push(self.flag);
+        }
+        bytes.extend_from_slice(&self.data_length_repeated.to_le_bytes());
+
+        bytes
+    }
+
+    /// Returns the number of bytes that the header takes up, including the header length and
+    /// the repeated data length.
+    pub fn byte_length(&self) -> usize {
+        2 + self.header_length as usize + 2
+    }
+
+    /// Checks if the variable is archived.
+    pub fn is_archived(&self) -> bool {
+        self.flag & 0x80 != 0
+    }
+
+    /// Extract the name of the variable, trimming any NULL bytes.
+    ///
+    /// # Returns
+    ///
+    /// A [`Result`] containing the name as a [`String`] if successful, or a [`FromUtf8Error`] if
+    /// the extraction fails.
+    pub fn extract_name(&self) -> Result<String, FromUtf8Error> {
+        let result = String::from_utf8(self.name.to_vec())?;
 
         // String NULL bytes
         Ok(result.trim_matches(char::from(0)).to_string())
     }
+
+    /// Extract the name of the variable like [`VarHeader::extract_name`], replacing bytes that
+    /// aren't valid UTF-8 with `�` instead of failing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::common::{VarHeader, VarType};
+    ///
+    /// let mut header = VarHeader::new(VarType::Program, "AB", 0);
+    /// header.name[1] = 0xFF;
+    ///
+    /// assert!(header.extract_name().is_err());
+    /// assert_eq!(header.lossy_name(), "A\u{FFFD}");
+    /// ```
+    pub fn lossy_name(&self) -> String {
+        String::from_utf8_lossy(&self.name)
+            .trim_matches(char::from(0))
+            .to_string()
+    }
 }
 
+impl VarEntry {
+    /// Creates an entry for an editable program.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the program. This is converted to uppercase and truncated to 8
+    ///   characters.
+    /// * `tokens` - The tokens of the program.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than [`MAX_PROGRAM_SIZE`] tokens.
+    pub fn program(name: &str, tokens: &[u8]) -> Self {
+        let mut entry = Self {
+            header: VarHeader::new(VarType::Program, name, 0),
+            data: [&[0, 0][..], tokens].concat(),
+        };
+        entry.update_sizes().unwrap_or_else(|e| panic!("{}", e));
+
+        entry
+    }
+
+    /// Checks if the entry is a program, either editable or locked.
+    pub fn is_program(&self) -> bool {
+        matches!(
+            self.header.var_type,
+            VarType::Program | VarType::LockedProgram
+        )
+    }
+
+    /// Returns the tokens of a program, which follow the two byte length at the start of its
+    /// data, or [`None`] if the entry isn't a program.
+    pub fn program_tokens(&self) -> Option<&[u8]> {
+        if self.is_program() {
+            self.data.get(2..)
+        } else {
+            None
+        }
+    }
+
+    /// Recalculates the size fields in the header, and the length at the start of a program's
+    /// data, from the data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FileError::TooLarge`] if the data is longer than 65535 bytes, with the offset of
+    /// the field from the start of the entry. Nothing is updated in that case.
+    pub fn update_sizes(&mut self) -> Result<(), FileError> {
+        let length = u16::try_from(self.data.len()).map_err(|_| FileError::TooLarge {
+            field: "Variable length",
+            offset: 2,
+            size: self.data.len(),
+        })?;
+
+        // the data length fits, so the program size, which is 2 less, fits too
+        if self.is_program() && length >= 2 {
+            self.data[..2].copy_from_slice(&(length - 2).to_le_bytes());
+        }
+        self.header.data_length = length;
+        self.header.data_length_repeated = length;
+
+        Ok(())
+    }
+
+    /// Returns the number of bytes that the entry takes up, including its header.
+    pub fn byte_length(&self) -> usize {
+        self.header.byte_length() + self.data.len()
+    }
+
+    /// Converts the entry into bytes, including its header.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.header.to_bytes(), self.data.clone()].concat()
+    }
+}
+
+/// The most tokens that a program can contain, so that its data, which starts with the two byte
+/// length of the program, fits in the two byte variable length
+pub const MAX_PROGRAM_SIZE: usize = u16::MAX as usize - 2;
+
 /// The 10-byte header for TI-8XP files
 pub const FILE_HEADER: [u8; 10] = [0x2A, 0x2A, 0x54, 0x49, 0x38, 0x33, 0x46, 0x2A, 0x1A, 0xA];
+
+/// The comment that is written to files created by TiO2
+pub const DEFAULT_COMMENT: &[u8; 42] = b"File compiled by TiO2 from TabulateJarl8\x00\x00";
+
+/// The length of the header of a variable entry, not including the length field itself or the
+/// repeated data length
+pub const VAR_HEADER_LENGTH: u16 = 0x0D;
+
+/// The length of the header of a variable entry from older calculators, which don't store the
+/// version and archived flag
+pub const SHORT_VAR_HEADER_LENGTH: u16 = 0x0B;
+
+/// Calculates the checksum stored in the footer of a TI-8XP file.
+///
+/// The checksum is the sum of every byte in the variable entries, which start after the 55-byte
+/// file header, truncated to 16 bits.
+///
+/// # Arguments
+///
+/// * `entries` - The bytes of every variable entry in the file, including their headers.
+///
+/// # Returns
+///
+/// The checksum as a [`u16`], which is stored in the footer in little endian order.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::common::checksum;
+///
+/// assert_eq!(checksum(&[0x0D, 0xFF, 0xFF]), 0x0D + 0xFF + 0xFF);
+/// ```
+pub fn checksum(entries: &[u8]) -> u16 {
+    entries
+        .iter()
+        .fold(0u16, |sum, &byte| sum.wrapping_add(byte as u16))
+}
@@ -1,14 +1,20 @@
 //! The `compile` module provides functions for converting source code into TI-8XP bytecode programs
 //! for TI-84 Plus calculators. It includes functionality for calculating file size bytes, creating
 //! metadata headers and footers, and compiling source code into bytecode.
-use log::{debug, error};
+use std::{collections::HashMap, fmt, ops::Range};
 
-use crate::utils::copy_into_index;
+use log::debug;
 
 use super::{
-    common::FILE_HEADER,
-    tokens::{get_inverse_tokens_as_str, Byte},
+    common::{FileHeader, TIFile, VarEntry, VarType, MAX_PROGRAM_SIZE},
+    languages::Language,
+    metadata::{self, Category},
+    models::{unsupported_tokens, Model},
+    parser::ends_operand,
+    tokens::{get_inverse_tokens_as_str, Byte, BYTE_TOKENS},
+    trie::{TokenTrie, TOKEN_TRIE},
 };
+use crate::errors::{CompileError, CompileErrors};
 
 /// Calculate the bytes and carry bit for a given size.
 ///
@@ -42,9 +48,144 @@ pub fn int_to_bytes(size: usize) -> [u8; 2] {
     bytes
 }
 
+/// Options for the header of a compiled program.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::compile::CompileOptions;
+///
+/// let options = CompileOptions {
+///     comment: "Release build".to_string(),
+///     locked: true,
+///     ..CompileOptions::new("GAME")
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileOptions {
+    /// The name of the program, which is converted to uppercase and truncated to 8 characters
+    pub name: String,
+    /// The comment stored in the file header, which can be at most 42 bytes long
+    pub comment: String,
+    /// Whether the program should be locked (type `0x06`) so that it can't be edited on the
+    /// calculator
+    pub locked: bool,
+    /// Whether the program should be sent to the archive (flag `0x80`)
+    pub archived: bool,
+    /// The version byte
+    pub version: u8,
+    /// The calculator model that the program will run on. Tokens that the model doesn't support
+    /// are rejected.
+    pub model: Model,
+}
+
+impl CompileOptions {
+    /// Creates options for an unlocked, unarchived program with the default comment.
+    pub fn new(name: impl ToString) -> Self {
+        Self {
+            name: name.to_string(),
+            comment: FileHeader::default().comment(),
+            locked: false,
+            archived: false,
+            version: 0x00,
+            model: Model::default(),
+        }
+    }
+}
+
+/// Creates a [`TIFile`] containing a single program.
+///
+/// # Arguments
+///
+/// * `ti_basic_data` - The tokens of the program, such as the output of [`compile_to_bytecode`].
+/// * `options` - The name of the program and the values of the other header fields.
+///
+/// # Returns
+///
+/// A [`Result`] containing the [`TIFile`], or an error if the comment is too long, the program is
+/// too long for the size fields of the file, or the program uses tokens that `options.model`
+/// doesn't support.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{
+///     common::VarType,
+///     compile::{create_file, CompileOptions},
+///     models::Model,
+/// };
+///
+/// let options = CompileOptions {
+///     locked: true,
+///     archived: true,
+///     ..CompileOptions::new("HI")
+/// };
+/// let file = create_file(&[0xDE, 0x2A, 0x48, 0x49, 0x2A], &options).unwrap();
+///
+/// assert_eq!(file.entries[0].header.var_type, VarType::LockedProgram);
+/// assert!(file.entries[0].header.is_archived());
+///
+/// let options = CompileOptions {
+///     comment: "x".repeat(43),
+///     ..CompileOptions::new("HI")
+/// };
+/// assert!(create_file(&[], &options).is_err());
+///
+/// // the entry is 17 header bytes, 2 length bytes and the tokens, and can be at most 65535 bytes
+/// assert!(create_file(&[0x30; 65516], &CompileOptions::new("HI")).is_ok());
+/// assert!(create_file(&[0x30; 65517], &CompileOptions::new("HI")).is_err());
+/// assert!(create_file(&[0x30; 70000], &CompileOptions::new("HI")).is_err());
+///
+/// // TextColor( isn't available on the TI-84 Plus
+/// let options = CompileOptions {
+///     model: Model::TI84Plus,
+///     ..CompileOptions::new("HI")
+/// };
+/// assert!(create_file(&[0xEF, 0x67], &options).is_err());
+/// ```
+pub fn create_file(
+    ti_basic_data: &[u8],
+    options: &CompileOptions,
+) -> Result<TIFile, anyhow::Error> {
+    let unsupported = unsupported_tokens(ti_basic_data, options.model);
+    if !unsupported.is_empty() {
+        let tokens: Vec<String> = unsupported.iter().map(|t| format!("  {}", t)).collect();
+        return Err(anyhow::Error::msg(format!(
+            "The {} doesn't support {} token(s) used by the program:\n{}",
+            options.model,
+            unsupported.len(),
+            tokens.join("\n")
+        )));
+    }
+
+    if ti_basic_data.len() > MAX_PROGRAM_SIZE {
+        return Err(anyhow::Error::msg(format!(
+            "The program is {} bytes long, but programs can be at most {} bytes long",
+            ti_basic_data.len(),
+            MAX_PROGRAM_SIZE
+        )));
+    }
+
+    let mut entry = VarEntry::program(&options.name, ti_basic_data);
+    if options.locked {
+        entry.header.var_type = VarType::LockedProgram;
+    }
+    if options.archived {
+        entry.header.flag = 0x80;
+    }
+    entry.header.version = options.version;
+
+    let mut file = TIFile::try_new(vec![entry])?;
+    file.header.set_comment(&options.comment)?;
+
+    Ok(file)
+}
+
 /// Create a metadata header and footer for a TI-8XP program.
 ///
-/// This function generates a metadata header and footer (checksum) for a TI-8XP program.
+/// This function generates a metadata header and footer (checksum) for a TI-8XP program. It is
+/// equivalent to splitting the output of [`TIFile::to_bytes`] for a file containing a single
+/// program around the program's tokens.
 ///
 /// [This](https://github.com/SmellyModder/TI8xp/blob/main/src/main/java/net/smelly/tieightxp/TIPrgmCompiler.java#L105) GitHub project was a good reference in making this.
 ///
@@ -59,7 +200,7 @@ pub fn int_to_bytes(size: usize) -> [u8; 2] {
 ///
 /// # Errors
 ///
-/// Returns an error if the header length is incorrect.
+/// This function currently never returns an error, since the default comment always fits.
 ///
 /// # Example
 /// ```
@@ -82,93 +223,18 @@ pub fn int_to_bytes(size: usize) -> [u8; 2] {
 /// assert_eq!(metadata.1, [101, 3]);
 /// ```
 pub fn create_metadata(
-    ti_basic_data: &Vec<u8>,
+    ti_basic_data: &[u8],
     program_name: &str,
 ) -> Result<([u8; 74], [u8; 2]), anyhow::Error> {
-    let mut header: [u8; 74] = [0x0; 74];
-    let mut index_pointer: usize = 0;
-
-    // initialize with the 10 bytes that every file has
-    index_pointer = copy_into_index(&mut header, &FILE_HEADER, index_pointer);
-
-    //  Appends a comment area of metadata to the header
-    //  Follows the form [NULL]40 characters[NULL]character[NULL][NULL][hex code][NEWLINE]
-    //  If the comment contains fewer than 40 characters, the unused
-    //  characters are filled with null characters.  It appears that
-    //  more than 40 characters can be put here, but then the hex codes
-    //  at the end change. It doesn't seem to do anything,
-    //  but with over 40 characters it doesn't seem to be needed.
-    //  So, using the extra characters this section of the header becomes
-    //  [NULL]comment string, 42 chars[DC4][NULL][NEWLINE]
-
-    //  I've seen other people use 0xA here, so maybe that would fix it if it breaks
-    header[index_pointer] = 0x0;
-    index_pointer += 1;
-
-    index_pointer = copy_into_index(
-        &mut header,
-        b"File compiled by TiO2 from TabulateJarl8\x00\x00",
-        index_pointer,
-    );
-
-    // data.len() + 19, carry byte
-    debug!("Size + 19: {}", ti_basic_data.len() + 19);
-    index_pointer = copy_into_index(
-        &mut header,
-        &int_to_bytes(ti_basic_data.len() + 19),
-        index_pointer,
-    );
-
-    index_pointer = copy_into_index(
-        &mut header,
-        &[
-            // some junk im not sure about
-            0xD, 0x0,
-        ],
-        index_pointer,
-    );
-
-    let size = ti_basic_data.len() + 2;
-    index_pointer = copy_into_index(&mut header, &int_to_bytes(size), index_pointer);
-
-    // 0x05 means editable program, 0x06 means uneditable
-    header[index_pointer] = 0x05;
-    index_pointer += 1;
-
-    // append truncated name to end of header
-    // name is 8 characters long at most, and its followed by 2 NULL characters,
-    // so we create an array of length 10 (8 + 2)
-    let mut string_bytes: [u8; 10] = [0x0; 10];
-    let truncated_program_name = if program_name.len() > 8 {
-        program_name.to_ascii_uppercase().chars().take(8).collect()
-    } else {
-        program_name.to_ascii_uppercase()
-    };
-
-    debug!("truncated name: {}", truncated_program_name);
-
-    copy_into_index(&mut string_bytes, truncated_program_name.as_bytes(), 0);
-    index_pointer = copy_into_index(&mut header, &string_bytes, index_pointer);
+    let file = create_file(ti_basic_data, &CompileOptions::new(program_name))?;
+    let bytes = file.to_bytes();
 
-    // Adding the size a second time as it is repeated after the name
-    index_pointer = copy_into_index(&mut header, &int_to_bytes(size), index_pointer);
-
-    index_pointer = copy_into_index(&mut header, &int_to_bytes(size - 2), index_pointer);
-
-    if index_pointer != 74 {
-        return Err(anyhow::Error::msg(format!(
-            "Fatal error: header length is incorrect: {}",
-            index_pointer
-        )));
-    }
-
-    let checksum = [ti_basic_data, &header[55..]]
-        .concat()
-        .iter()
-        .map(|&x| x as u32)
-        .sum::<u32>() as usize;
+    // the file header and the header of the only entry, followed by the program length
+    let mut header: [u8; 74] = [0x0; 74];
+    header.copy_from_slice(&bytes[..74]);
 
-    let footer = int_to_bytes(checksum);
+    let mut footer: [u8; 2] = [0x0; 2];
+    footer.copy_from_slice(&bytes[bytes.len() - 2..]);
 
     debug!("Generated header: {:x?}", header);
     debug!("Generated footer: {:x?}", footer);
@@ -179,8 +245,47 @@ pub fn create_metadata(
 /// Compile a Vec of strings into a Vec of bytes, representing a TI-8XP bytecode program.
 ///
 /// This function takes a [`Vec`] of `&str` containing the source code lines and attempts to convert
-/// it into a sequence of bytes that represent a bytecode program. It replaces the `→` character
-/// with `->` in the input and uses a provided set of tokens to map substrings to bytes.
+/// it into a sequence of bytes that represent a bytecode program. Every token can be written with
+/// its name, the way it's displayed on the calculator, or one of its aliases from
+/// [`metadata`], so source code can be written with only ASCII characters:
+///
+/// | Token | Name | Display | ASCII aliases |
+/// |-------|------|---------|---------------|
+/// | `04` | `->` | `→` | |
+/// | `0B` | `°` | | `^^o` |
+/// | `0C`, `0D`, `0E`, `0F` | `^-1`, `^2`, `^T`, `^3` | `⁻¹`, `²`, `ᵀ`, `³` | `^^-1`, `^^2`, `^^T`, `^^3` |
+/// | `3B` | `10^` | `ᴇ` | `\|E`, or `E` inside a number |
+/// | `5B` | `θ` | | `theta` |
+/// | `6D`, `6E`, `6F` | `<=`, `>=`, `!=` | `≤`, `≥`, `≠` | |
+/// | `71` | `–` | `-` | `-` after an operand |
+/// | `AC` | `π` | | `pi` |
+/// | `B0` | `-` | `⁻` | |
+/// | `BC` | `sqrt(` | `√(` | |
+/// | `EB` | `ʟ` | | `\|L` |
+/// | `BB31` | `𝑒` | | `\|e` |
+///
+/// Tokens containing `θ`, such as `θmin` and `R>Pθ(`, can also be written with `theta`.
+///
+/// Two aliases depend on what is around them. `-` is subtraction after something that ends an
+/// operand (see [`ends_operand`]), such as a number, variable or closing parenthesis, and negation
+/// anywhere else. `E` is scientific notation between a digit or `.` and a digit or `-`, as in
+/// `1E-5`, and the variable `E` anywhere else.
+///
+/// Inside strings, each character is its own token, the way the calculator's editor stores typed
+/// text: uppercase letters and digits are their own tokens, lowercase letters are the two-byte
+/// tokens from `BBB0`, a space is `29`, and other characters such as `→` are converted if they
+/// are a token on their own. To keep commands such as `Disp ` as tokens inside strings, use
+/// [`compile_to_bytecode_with`].
+///
+/// Any token or byte can also be written as an escape with its bytes in hexadecimal, such as
+/// `\x{EF9A}` or `\x{3F}`, which works inside strings too. Decompiling uses escapes for bytes
+/// that can't be decoded and for tokens whose text would compile differently, so that decompiled
+/// programs always compile back to the same bytes.
+///
+/// Text that would otherwise be read as a longer token, or as several tokens, can be marked as
+/// a single token with `\{` and `}`. For example, `\{l}1` is the lowercase letter `l` followed
+/// by `1` rather than the list `l1`, and `"\{Disp }"` is a string containing the `Disp` token.
+/// Use [`find_ambiguities`] to find text that could be read more than one way.
 ///
 /// # Arguments
 ///
@@ -191,6 +296,12 @@ pub fn create_metadata(
 /// A [`Result`] containing a [`Vec`] of [`u8`] bytes representing the bytecode program if compilation
 /// is successful, or an [`anyhow::Error`] if an error occurs during compilation.
 ///
+/// # Errors
+///
+/// If any text can't be converted into tokens, the error is a [`CompileErrors`] containing a
+/// [`CompileError`] for every piece of unknown text in the file, which can be retrieved with
+/// [`anyhow::Error::downcast_ref`].
+///
 /// # Examples
 ///
 /// ```
@@ -204,78 +315,677 @@ pub fn create_metadata(
 ///     [0xD6, 0x41, 0x3F, 0xDE, 0x2A, 0x48, 0x45, 0x4C, 0x4C, 0x4F, 0x2A, 0x3F, 0xD7, 0x41]
 /// );
 /// ```
+///
+/// Every unknown piece of text is reported:
+///
+/// ```
+/// use tio2::{errors::CompileErrors, translation::compile::compile_to_bytecode};
+///
+/// let error = compile_to_bytecode(vec!["Disp ✓A", "1+2¤¤3"]).unwrap_err();
+/// let errors = error.downcast_ref::<CompileErrors>().unwrap();
+///
+/// assert_eq!(errors.0.len(), 2);
+/// assert_eq!((errors.0[0].line, errors.0[0].column), (1, 6));
+/// assert_eq!(errors.0[1].text, "¤¤");
+/// assert_eq!((errors.0[1].line, errors.0[1].column), (2, 4));
+/// ```
+///
+/// ASCII aliases compile to the same bytes as the Unicode spellings:
+///
+/// ```
+/// use tio2::translation::compile::compile_to_bytecode;
+///
+/// assert_eq!(
+///     compile_to_bytecode(vec!["If theta>=pi and sqrt(A^^2)!=1E-5", "B-1->C"]).unwrap(),
+///     compile_to_bytecode(vec!["If θ≥π and √(A²)≠1ᴇ⁻5", "B-1→C"]).unwrap(),
+/// );
+///
+/// // subtraction, then negation
+/// assert_eq!(compile_to_bytecode(vec!["1-(-1)"]).unwrap(), [0x31, 0x71, 0x10, 0xB0, 0x31, 0x11]);
+/// // the variable E, then scientific notation
+/// assert_eq!(compile_to_bytecode(vec!["2E+3E4"]).unwrap(), [0x32, 0x45, 0x70, 0x33, 0x3B, 0x34]);
+/// ```
+///
+/// Escapes and markup choose exact tokens:
+///
+/// ```
+/// use tio2::translation::compile::compile_to_bytecode;
+///
+/// assert_eq!(compile_to_bytecode(vec!["l1"]).unwrap(), [0x5D, 0x00]);
+/// assert_eq!(compile_to_bytecode(vec!["\\{l}1"]).unwrap(), [0xBB, 0xBC, 0x31]);
+/// assert_eq!(compile_to_bytecode(vec!["\\x{BBBC}1"]).unwrap(), [0xBB, 0xBC, 0x31]);
+/// assert_eq!(compile_to_bytecode(vec!["\"\\{Disp }"]).unwrap(), [0x2A, 0xDE]);
+/// ```
+///
+/// Strings contain the tokens that typing their text on the calculator would give:
+///
+/// ```
+/// use tio2::translation::compile::compile_to_bytecode;
+///
+/// assert_eq!(
+///     compile_to_bytecode(vec!["\"Disp a\""]).unwrap(),
+///     [0x2A, 0x44, 0xBB, 0xB8, 0xBB, 0xC3, 0xBB, 0xC0, 0x29, 0xBB, 0xB0, 0x2A]
+/// );
+/// ```
 pub fn compile_to_bytecode(file_contents: Vec<&str>) -> Result<Vec<u8>, anyhow::Error> {
-    let program_string = file_contents.join("\n").replace('→', "->");
+    compile_to_bytecode_with(file_contents, &TokenizeOptions::default())
+}
+
+/// Options for how source code is split into tokens.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{compile::TokenizeOptions, languages::Language};
+///
+/// let options = TokenizeOptions {
+///     tokens_in_strings: true,
+///     language: Language::French,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TokenizeOptions {
+    /// Whether commands, functions and operators with names longer than one character, such as
+    /// `Disp `, `sin(` and ` and `, are kept as tokens inside strings, the way they are when they
+    /// are typed with their keys on the calculator. This is useful for strings that are
+    /// evaluated with `expr(`. Otherwise every character in a string is its own token.
+    pub tokens_in_strings: bool,
+    /// The language that token names are written in. Both its names and the English names are
+    /// accepted.
+    pub language: Language,
+}
+
+/// Compiles source code like [`compile_to_bytecode`], with options for how it is split into
+/// tokens.
+///
+/// # Arguments
+///
+/// * `file_contents`: A [`Vec`] of `&str` containing the source code lines.
+/// * `options`: How the source code is split into tokens.
+///
+/// # Returns
+///
+/// The same as [`compile_to_bytecode`].
+///
+/// # Errors
+///
+/// The same as [`compile_to_bytecode`].
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::compile::{compile_to_bytecode_with, TokenizeOptions};
+///
+/// let options = TokenizeOptions {
+///     tokens_in_strings: true,
+///     ..TokenizeOptions::default()
+/// };
+///
+/// // expr("sin(X)")
+/// assert_eq!(
+///     compile_to_bytecode_with(vec!["expr(\"sin(X)\")"], &options).unwrap(),
+///     [0xBB, 0x2A, 0x2A, 0xC2, 0x58, 0x11, 0x2A, 0x11]
+/// );
+/// ```
+///
+/// Programs can be written in another [`Language`]:
+///
+/// ```
+/// use tio2::translation::{
+///     compile::{compile_to_bytecode, compile_to_bytecode_with, TokenizeOptions},
+///     languages::Language,
+/// };
+///
+/// let french = TokenizeOptions {
+///     language: Language::French,
+///     ..TokenizeOptions::default()
+/// };
+///
+/// assert_eq!(
+///     compile_to_bytecode_with(vec!["Si A et B", "Alors", "EffÉcr", "Fin"], &french).unwrap(),
+///     compile_to_bytecode(vec!["If A and B", "Then", "ClrHome", "End"]).unwrap()
+/// );
+/// ```
+pub fn compile_to_bytecode_with(
+    file_contents: Vec<&str>,
+    options: &TokenizeOptions,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let program_string = file_contents.join("\n");
+
+    let mut program_data: Vec<u8> = Vec::with_capacity(program_string.len());
+    let mut errors: Vec<CompileError> = Vec::new();
+
+    // the byte offset of the text that couldn't be matched to a token, if we're in it
+    let mut unknown_start: Option<usize> = None;
+
+    for token in SourceTokens::new(&program_string, options) {
+        let byte = match token.byte {
+            Some(byte) => byte,
+            None => {
+                unknown_start.get_or_insert(token.span.start);
+                continue;
+            }
+        };
+
+        debug!("token: {:?}", &program_string[token.span.clone()]);
+
+        if let Some(start) = unknown_start.take() {
+            errors.push(unknown_token_error(
+                &program_string,
+                start..token.span.start,
+            ));
+        }
 
-    let tokens = get_inverse_tokens_as_str();
+        program_data.extend(byte.to_bytes());
+    }
 
-    // keep track of when we're in strings for parsing
-    let mut in_string = false;
+    if let Some(start) = unknown_start {
+        errors.push(unknown_token_error(
+            &program_string,
+            start..program_string.len(),
+        ));
+    }
 
-    let longest_program_string = tokens.keys().map(|k| k.len()).max().unwrap();
+    if !errors.is_empty() {
+        return Err(CompileErrors(errors).into());
+    }
 
-    let mut program_data: Vec<&Byte> = Default::default();
+    debug!("Generated data bytes: {:x?}", program_data);
 
-    let mut current_char = 0;
+    Ok(program_data)
+}
 
-    while current_char < program_string.len() {
-        let mut found = false;
-        let mut chars_further = longest_program_string;
+/// A token in source code whose text could also be read as several other tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ambiguity {
+    /// The line that the token is on, starting at 1
+    pub line: usize,
+    /// The column of the first character of the token, in characters and starting at 1
+    pub column: usize,
+    /// The text of the token
+    pub text: String,
+    /// The token that the text is compiled to
+    pub token: Byte,
+    /// Other tokens that the text could be read as
+    pub alternative: Vec<Byte>,
+}
 
-        if in_string {
-            // match the first character
-            // this might break, but i think we have all uppercase/lowercase
-            // covered so hopefully it'll be fine
-            chars_further = 1;
+impl Ambiguity {
+    /// Returns the text marked up so that it compiles to [`Ambiguity::alternative`], such as
+    /// `\{S}\{x}`.
+    pub fn markup(&self) -> String {
+        self.alternative
+            .iter()
+            .map(|byte| format!("\\{{{}}}", BYTE_TOKENS.get(byte).copied().unwrap_or("?")))
+            .collect()
+    }
+}
+
+impl fmt::Display for Ambiguity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let alternative: Vec<String> = self
+            .alternative
+            .iter()
+            .map(|byte| format!("{:?}", BYTE_TOKENS.get(byte).copied().unwrap_or("?")))
+            .collect();
+
+        write!(
+            f,
+            "line {}, column {}: {:?} is read as a single token, but could also be {}. Write {} to use those instead",
+            self.line,
+            self.column,
+            self.text,
+            alternative.join(", "),
+            self.markup()
+        )
+    }
+}
+
+/// Finds the tokens in source code whose text could also be read as several other tokens, such
+/// as the list `l1`, which could also be the lowercase letter `l` followed by `1`.
+///
+/// Only variables, constants and operators made of words, such as ` or `, are reported, since
+/// those are the tokens that are easily confused with letters, digits and spaces. Tokens inside
+/// strings, escapes and markup are never ambiguous.
+///
+/// # Arguments
+///
+/// * `file_contents`: A [`Vec`] of `&str` containing the source code lines.
+/// * `options`: How the source code is split into tokens.
+///
+/// # Returns
+///
+/// Every ambiguous token, in the order that they appear.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{
+///     compile::{find_ambiguities, TokenizeOptions},
+///     tokens::Byte,
+/// };
+///
+/// let ambiguities = find_ambiguities(
+///     vec!["Disp Sx", "Disp \"Sx\",\\{S}x", "\"Sx->Sx"],
+///     &TokenizeOptions::default(),
+/// );
+///
+/// // the store closes the string on the last line
+/// assert_eq!(ambiguities.len(), 2);
+/// assert_eq!((ambiguities[0].line, ambiguities[0].column), (1, 6));
+/// assert_eq!((ambiguities[1].line, ambiguities[1].column), (3, 6));
+/// assert_eq!(ambiguities[0].alternative, [Byte::Single(0x53), Byte::Double([0xBB, 0xC8])]);
+/// assert_eq!(ambiguities[0].markup(), "\\{S}\\{x}");
+/// ```
+pub fn find_ambiguities(file_contents: Vec<&str>, options: &TokenizeOptions) -> Vec<Ambiguity> {
+    let program_string = file_contents.join("\n");
+    let trie = options.language.trie();
+    let mut ambiguities = Vec::new();
+
+    for token in SourceTokens::new(&program_string, options) {
+        let (byte, text) = match token.byte {
+            Some(byte) => (byte, &program_string[token.span.clone()]),
+            None => continue,
+        };
+        if token.in_string || text.starts_with('\\') && trie.get(text) != Some(byte) {
+            continue;
         }
 
-        // Greedily start with the maximum size string we have and back
-        // down until we get to something that we can create a token from.
-        while !found && chars_further > 0 {
-            let sliced_string: String = program_string
-                .chars()
-                .take(current_char + chars_further)
-                .skip(current_char)
-                .collect();
-
-            // entering or exiting a string
-            if sliced_string.starts_with('"') {
-                in_string = !in_string;
-            }
+        if let Some(alternative) = alternative_tokens(byte, text, trie) {
+            let (line, column) = position(&program_string, token.span.start);
+            ambiguities.push(Ambiguity {
+                line,
+                column,
+                text: text.to_string(),
+                token: byte,
+                alternative,
+            });
+        }
+    }
 
-            // end of line, strings close automatically
-            if sliced_string.starts_with('\n') {
-                in_string = false;
-            }
+    ambiguities
+}
+
+/// Splits the text of a token into other tokens that it could be confused with, preferring the
+/// longest tokens first.
+///
+/// # Returns
+///
+/// The other tokens, or [`None`] if the token isn't easily confused, or its text can't be split.
+fn alternative_tokens(byte: Byte, text: &str, trie: &TokenTrie) -> Option<Vec<Byte>> {
+    let category = metadata::get(byte)?.category;
+    let confusable = match category {
+        Category::Variable | Category::Constant => true,
+        Category::Operator => text.chars().any(char::is_alphabetic),
+        _ => false,
+    };
+    if !confusable {
+        return None;
+    }
 
-            match tokens.get(sliced_string.as_str()) {
-                Some(token) => {
-                    found = true;
-                    debug!("token: {:?}", sliced_string);
-                    program_data.push(token);
-                    current_char += chars_further;
-                }
-                None => chars_further -= 1,
+    // letters, digits and spaces, which are what the token could be mistaken for
+    let is_piece = |byte: Byte| {
+        byte == Byte::Single(0x29)
+            || metadata::get(byte).is_some_and(|token| {
+                matches!(
+                    token.category,
+                    Category::Variable
+                        | Category::Constant
+                        | Category::Literal
+                        | Category::Character
+                )
+            })
+    };
+
+    // splits[i] is the best way to split the text from byte i onwards
+    let mut splits: Vec<Option<Vec<Byte>>> = vec![None; text.len() + 1];
+    splits[text.len()] = Some(Vec::new());
+
+    for start in (0..text.len()).rev().filter(|&i| text.is_char_boundary(i)) {
+        for (piece, length) in trie.prefixes(&text[start..]).into_iter().rev() {
+            if (start == 0 && length == text.len()) || !is_piece(piece) {
+                continue;
             }
 
-            if chars_further == 0 {
-                error!("remainder: {:?}", &program_string[current_char..]);
-                return Err(anyhow::Error::msg(
-                    "Something went horribly wrong while compiling.",
-                ));
+            if let Some(rest) = &splits[start + length] {
+                let mut split = vec![piece];
+                split.extend(rest);
+                splits[start] = Some(split);
+                break;
             }
         }
     }
 
-    let program_data_bytes = program_data
-        .iter()
-        .flat_map(|byte| match byte {
-            Byte::Single(val) => vec![*val].into_iter(),
-            Byte::Double(arr) => arr.to_vec().into_iter(),
+    splits.swap_remove(0)
+}
+
+/// A piece of source code found by [`SourceTokens`].
+struct SourceToken {
+    /// The bytes of the source code that the piece covers
+    span: Range<usize>,
+    /// The token, or [`None`] for a character that doesn't start a token
+    byte: Option<Byte>,
+    /// Whether the piece is inside a string
+    in_string: bool,
+}
+
+/// Splits source code into tokens, one character at a time where there is no token, keeping
+/// track of strings and of the previous token the way [`compile_to_bytecode`] does.
+struct SourceTokens<'a> {
+    source: &'a str,
+    options: &'a TokenizeOptions,
+    current: usize,
+    in_string: bool,
+    /// The last token, which decides what `-` and `E` mean
+    previous: Option<Byte>,
+}
+
+impl<'a> SourceTokens<'a> {
+    fn new(source: &'a str, options: &'a TokenizeOptions) -> Self {
+        Self {
+            source,
+            options,
+            current: 0,
+            in_string: false,
+            previous: None,
+        }
+    }
+}
+
+impl Iterator for SourceTokens<'_> {
+    type Item = SourceToken;
+
+    fn next(&mut self) -> Option<SourceToken> {
+        let remaining = self.source.get(self.current..).filter(|s| !s.is_empty())?;
+        let start = self.current;
+        let in_string = self.in_string;
+
+        let (byte, length) =
+            match match_token(remaining, self.in_string, self.previous, self.options) {
+                Some((byte, length)) => (Some(byte), length),
+                None => (None, remaining.chars().next().map_or(1, char::len_utf8)),
+            };
+
+        if let Some(byte) = byte {
+            self.in_string = string_state(self.in_string, byte);
+            self.previous = Some(byte);
+        }
+        self.current += length;
+
+        Some(SourceToken {
+            span: start..self.current,
+            byte,
+            in_string,
+        })
+    }
+}
+
+/// Works out whether the tokens after a token are inside a string, the way the calculator does.
+/// A `"` enters or exits a string, while a new line or `→` closes it.
+///
+/// # Arguments
+///
+/// * `in_string` - Whether the token is inside a string.
+/// * `byte` - The token.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{compile::string_state, tokens::Byte};
+///
+/// assert!(string_state(false, Byte::Single(0x2A)));
+/// assert!(string_state(true, Byte::Single(0x41)));
+/// // "HI→Str1
+/// assert!(!string_state(true, Byte::Single(0x04)));
+/// assert!(!string_state(true, Byte::Single(0x3F)));
+/// ```
+pub fn string_state(in_string: bool, byte: Byte) -> bool {
+    match byte {
+        // entering or exiting a string
+        Byte::Single(0x2A) => !in_string,
+        // strings close automatically at the end of a line or a store
+        Byte::Single(0x3F | 0x04) => false,
+        _ => in_string,
+    }
+}
+
+/// Finds the token at the start of some source code, the same way that [`compile_to_bytecode`]
+/// does.
+///
+/// # Arguments
+///
+/// * `remaining` - The source code from the start of the token to the end of the program.
+/// * `in_string` - Whether the token is inside a string, where each character is its own token.
+/// * `previous` - The token before it, if there is one, which decides what `-` and `E` mean.
+/// * `options` - How source code is split into tokens, which matters inside strings.
+///
+/// # Returns
+///
+/// The token and the length of its text in bytes, or [`None`] if the text isn't a token.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{
+///     compile::{match_token, TokenizeOptions},
+///     tokens::Byte,
+/// };
+///
+/// let options = TokenizeOptions::default();
+///
+/// assert_eq!(match_token("Disp A", false, None, &options), Some((Byte::Single(0xDE), 5)));
+/// assert_eq!(match_token("Disp A", true, None, &options), Some((Byte::Single(0x44), 1)));
+/// // stores close strings, so they are still tokens inside of them
+/// assert_eq!(match_token("->Str1", true, None, &options), Some((Byte::Single(0x04), 2)));
+/// assert_eq!(match_token("→Str1", true, None, &options), Some((Byte::Single(0x04), 3)));
+/// assert_eq!(
+///     match_token("\\x{EF9A}", true, None, &options),
+///     Some((Byte::Double([0xEF, 0x9A]), 8))
+/// );
+/// // subtraction after a number
+/// assert_eq!(
+///     match_token("-1", false, Some(Byte::Single(0x31)), &options),
+///     Some((Byte::Single(0x71), 1))
+/// );
+///
+/// let options = TokenizeOptions {
+///     tokens_in_strings: true,
+///     ..TokenizeOptions::default()
+/// };
+///
+/// assert_eq!(match_token("Disp A", true, None, &options), Some((Byte::Single(0xDE), 5)));
+/// ```
+pub fn match_token(
+    remaining: &str,
+    in_string: bool,
+    previous: Option<Byte>,
+    options: &TokenizeOptions,
+) -> Option<(Byte, usize)> {
+    let trie = options.language.trie();
+
+    if let Some(escaped) = escaped_token(remaining).or_else(|| marked_token(remaining, trie)) {
+        return Some(escaped);
+    }
+
+    // Inside of strings, each character is its own token apart from the store arrow, otherwise
+    // take the longest token
+    if in_string {
+        if let Some(arrow) = ["->", "→"]
+            .into_iter()
+            .find(|arrow| remaining.starts_with(arrow))
+        {
+            return Some((Byte::Single(0x04), arrow.len()));
+        }
+
+        let character = remaining.chars().next()?;
+        let kept = match options.tokens_in_strings {
+            true => kept_token(remaining, trie),
+            false => None,
+        };
+
+        return kept.or_else(|| string_token(character).map(|byte| (byte, character.len_utf8())));
+    }
+
+    trie.longest_match(remaining).map(|(byte, length)| {
+        (
+            disambiguate(byte, &remaining[..length], previous, &remaining[length..]),
+            length,
+        )
+    })
+}
+
+/// Finds the token for a single character inside a string, which is the token that typing the
+/// character on the calculator gives.
+fn string_token(character: char) -> Option<Byte> {
+    match character {
+        'A'..='Z' | '0'..='9' => Some(Byte::Single(character as u8)),
+        ' ' => Some(Byte::Single(0x29)),
+        // the lowercase letters skip BBBB
+        'a'..='k' => Some(Byte::Double([0xBB, 0xB0 + (character as u8 - b'a')])),
+        'l'..='z' => Some(Byte::Double([0xBB, 0xBC + (character as u8 - b'l')])),
+        _ => TOKEN_TRIE.get(character.encode_utf8(&mut [0; 4])),
+    }
+}
+
+/// Finds the longest command, function or operator at the start of `text` whose name is longer
+/// than one character, for [`TokenizeOptions::tokens_in_strings`].
+fn kept_token(text: &str, trie: &TokenTrie) -> Option<(Byte, usize)> {
+    let first = text.chars().next()?.len_utf8();
+
+    trie.prefixes(text)
+        .into_iter()
+        .rev()
+        .take_while(|&(_, length)| length > first)
+        .find(|&(byte, _)| {
+            metadata::get(byte).is_some_and(|token| {
+                matches!(
+                    token.category,
+                    Category::Command | Category::Function | Category::Operator
+                )
+            })
+        })
+}
+
+/// Reads a `\x{..}` escape at the start of `text`, containing the bytes of a token or a single
+/// byte in hexadecimal.
+fn escaped_token(text: &str) -> Option<(Byte, usize)> {
+    let hex = text.strip_prefix("\\x{")?;
+    let end = hex.find('}')?;
+
+    Byte::from_hex(&hex[..end]).map(|byte| (byte, "\\x{".len() + end + 1))
+}
+
+/// Reads a `\{..}` markup at the start of `text`, containing the text of a single token.
+fn marked_token(text: &str, trie: &TokenTrie) -> Option<(Byte, usize)> {
+    let inner = text.strip_prefix("\\{")?;
+
+    // the token may contain `}` itself, so try each one in turn
+    inner.match_indices('}').find_map(|(end, _)| {
+        trie.get(&inner[..end])
+            .map(|byte| (byte, "\\{".len() + end + 1))
+    })
+}
+
+/// Picks the token for text that means different things depending on what is around it, which is
+/// `-` for subtraction or negation and `E` for scientific notation or the variable `E`.
+///
+/// # Arguments
+///
+/// * `byte` - The token that the text was matched to.
+/// * `text` - The text of the token.
+/// * `previous` - The token before it, if there is one.
+/// * `rest` - The source code after it.
+fn disambiguate(byte: Byte, text: &str, previous: Option<Byte>, rest: &str) -> Byte {
+    match text {
+        "-" if previous.is_some_and(ends_operand) => Byte::Single(0x71),
+        "E" if matches!(previous, Some(Byte::Single(0x30..=0x3A)))
+            && rest.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '⁻') =>
+        {
+            Byte::Single(0x3B)
+        }
+        _ => byte,
+    }
+}
+
+/// Creates a [`CompileError`] for the bytes in `span` of the program, which couldn't be
+/// converted into tokens.
+fn unknown_token_error(program_string: &str, span: Range<usize>) -> CompileError {
+    let line_start = program_string[..span.start]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let line_end = program_string[span.start..]
+        .find('\n')
+        .map_or(program_string.len(), |index| span.start + index);
+
+    let text = &program_string[span.clone()];
+    let (line, column) = position(program_string, span.start);
+
+    CompileError {
+        line,
+        column,
+        text: text.to_string(),
+        source_line: program_string[line_start..line_end].to_string(),
+        suggestions: suggest_tokens(text, &get_inverse_tokens_as_str()),
+    }
+}
+
+/// Works out the line and column of a byte offset in the program, both starting at 1. Columns
+/// are counted in characters.
+fn position(program_string: &str, offset: usize) -> (usize, usize) {
+    let line_start = program_string[..offset]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+
+    (
+        program_string[..offset].matches('\n').count() + 1,
+        program_string[line_start..offset].chars().count() + 1,
+    )
+}
+
+/// Finds up to three known tokens that are similar to `text`. Tokens containing the text are
+/// preferred, followed by tokens with a small edit distance from it.
+fn suggest_tokens(text: &str, tokens: &HashMap<&'static str, Byte>) -> Vec<String> {
+    let text = text.to_lowercase();
+    let length = text.chars().count();
+    let max_distance = (length / 3).max(1);
+
+    let mut candidates: Vec<(usize, &str)> = tokens
+        .keys()
+        .filter(|token| !token.trim().is_empty())
+        .filter_map(|token| {
+            let lowercase = token.to_lowercase();
+            if lowercase.contains(&text) {
+                return Some((0, *token));
+            }
+
+            let distance = edit_distance(&text, &lowercase);
+            (distance <= max_distance && distance < length).then_some((distance, *token))
         })
-        .collect::<Vec<u8>>();
+        .collect();
+
+    candidates
+        .sort_by(|a, b| (a.0, a.1.chars().count(), a.1).cmp(&(b.0, b.1.chars().count(), b.1)));
+
+    candidates
+        .into_iter()
+        .take(3)
+        .map(|(_, token)| token.to_string())
+        .collect()
+}
+
+/// Calculates the Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
 
-    debug!("Generated data bytes: {:x?}", program_data_bytes);
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
 
-    Ok(program_data_bytes)
+    previous[b.len()]
 }
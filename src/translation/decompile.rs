@@ -2,11 +2,19 @@
 //! The primary struct, [`TIFile`], represents the structure of a TI-8XP file.
 //! The primary function that should be used in this module is [`decompile`]
 
-use log::{debug, error};
+use log::{debug, warn};
 
-use crate::translation::{
-    common::{self, TIFile},
-    tokens::{self, Byte},
+use crate::{
+    errors::FileError,
+    translation::{
+        asm::{self, Payload},
+        common::{self, FileHeader, TIFile, VarEntry, VarHeader},
+        compile::{match_token, string_state, TokenizeOptions},
+        languages::Language,
+        metadata,
+        models::{required_model, Model},
+        tokens::{self, Byte},
+    },
 };
 
 /// Checks if the given header is a valid TI 8XP header.
@@ -22,107 +30,809 @@ use crate::translation::{
 ///
 /// # Arguments
 ///
-/// * `header` - A 74-byte array representing the header of a TI-8XP file.
+/// * `header` - The start of a TI-8XP file, such as its 55-byte file header.
 ///
 /// # Returns
 ///
 /// Returns true if the header is valid, otherwise false.
-pub fn valid_8xp_header(header: [u8; 74]) -> bool {
-    header[..10] == common::FILE_HEADER
+pub fn valid_8xp_header(header: &[u8]) -> bool {
+    header.starts_with(&common::FILE_HEADER)
+}
+
+/// Reads a two byte little endian integer, in the format described in `8xp_file_structure.md`.
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+/// Checks binary data from a TI-8XP file for problems.
+///
+/// The signature, the length of the entries at offset 53, the header, size fields and type of
+/// each variable entry, and the checksum in the footer are all checked. For a file containing a
+/// single program, the size fields are at offsets 57, 70 and 72, and the type is at offset 59.
+///
+/// # Arguments
+///
+/// * `data` - A slice of bytes containing binary data from a TI-8XP file.
+///
+/// # Returns
+///
+/// Every problem that was found, which is empty if the file is valid. If a problem stops the
+/// file from being read (see [`FileError::is_fatal`]), it is the last problem returned.
+///
+/// # Example
+///
+/// ```
+/// use tio2::{
+///     errors::FileError,
+///     translation::{
+///         common::{TIFile, VarEntry},
+///         decompile::validate_binary_data,
+///     },
+/// };
+///
+/// let mut file = TIFile::new(vec![VarEntry::program("HI", &[0xDE, 0x2A, 0x48, 0x49, 0x2A])])
+///     .to_bytes();
+///
+/// assert_eq!(validate_binary_data(&file), vec![]);
+///
+/// // corrupt the last byte of the checksum
+/// *file.last_mut().unwrap() ^= 0xFF;
+/// assert!(matches!(
+///     validate_binary_data(&file)[..],
+///     [FileError::ChecksumMismatch { .. }]
+/// ));
+///
+/// // 65536 more `0` tokens, which wrap every size field around to the same value and leave the
+/// // checksum unchanged
+/// let mut file = TIFile::new(vec![VarEntry::program("HI", &[])]).to_bytes();
+/// let checksum = file.split_off(file.len() - 2);
+/// file.extend([0x30; 0x10000]);
+/// file.extend(checksum);
+/// assert!(validate_binary_data(&file)
+///     .iter()
+///     .any(|error| matches!(error, FileError::TooLarge { .. })));
+/// ```
+pub fn validate_binary_data(data: &[u8]) -> Vec<FileError> {
+    let mut errors = Vec::new();
+    parse_binary_data(data, &mut errors);
+    errors
 }
 
 /// Reads binary data and constructs a [`TIFile`] struct from it.
 ///
+/// The data is validated with [`validate_binary_data`], and any problem causes an error. Use
+/// [`read_binary_data_lenient`] to load files that are slightly corrupt.
+///
 /// # Arguments
 ///
 /// * `data` - A vector of bytes containing binary data from a TI-8XP file.
 ///
 /// # Returns
 ///
-/// Returns a [`Result`] containing a [`TIFile`] if successful, or an error if the data is invalid.
+/// Returns a [`Result`] containing a [`TIFile`] if successful, or a [`FileError`] describing the
+/// first problem with the data.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{
+///     common::{TIFile, VarEntry},
+///     decompile::read_binary_data,
+/// };
+///
+/// let file = TIFile::new(vec![
+///     VarEntry::program("ONE", &[0x31]),
+///     VarEntry::program("TWO", &[0x32]),
+/// ]);
+///
+/// let read = read_binary_data(file.to_bytes()).unwrap();
+///
+/// assert_eq!(read.entries, file.entries);
+/// assert_eq!(read.to_bytes(), file.to_bytes());
+/// ```
 pub fn read_binary_data(data: Vec<u8>) -> Result<TIFile, anyhow::Error> {
-    if data.len() < 74 {
+    let mut errors = Vec::new();
+    let file = parse_binary_data(&data, &mut errors);
+
+    match (errors.into_iter().next(), file) {
+        (None, Some(file)) => Ok(file),
+        (Some(error), _) => Err(error.into()),
+        (None, None) => unreachable!("a file that can't be read always has an error"),
+    }
+}
+
+/// Reads binary data and constructs a [`TIFile`] struct from it, allowing problems that don't
+/// stop the file from being read.
+///
+/// Incorrect size fields, an unknown file type and an incorrect checksum are logged as warnings
+/// and returned alongside the file. If the size fields of the last entry are wrong, its data is
+/// taken to be everything up to the footer.
+///
+/// # Arguments
+///
+/// * `data` - A vector of bytes containing binary data from a TI-8XP file.
+///
+/// # Returns
+///
+/// Returns a [`Result`] containing the [`TIFile`] and the problems that were found, or an error
+/// if the file can't be read at all.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{
+///     common::{TIFile, VarEntry},
+///     decompile::read_binary_data_lenient,
+/// };
+///
+/// let tokens = [0xDE, 0x2A, 0x48, 0x49, 0x2A];
+/// let mut file = TIFile::new(vec![VarEntry::program("HI", &tokens)]).to_bytes();
+/// // claim that the program is a different length
+/// file[72] = 0x10;
+///
+/// let (ti_file, warnings) = read_binary_data_lenient(file).unwrap();
+///
+/// assert_eq!(ti_file.entries[0].program_tokens(), Some(&tokens[..]));
+/// assert_eq!(warnings.len(), 2);
+/// ```
+pub fn read_binary_data_lenient(data: Vec<u8>) -> Result<(TIFile, Vec<FileError>), anyhow::Error> {
+    let mut errors = Vec::new();
+
+    let file = match parse_binary_data(&data, &mut errors) {
+        Some(file) => file,
+        None => {
+            let fatal = errors
+                .pop()
+                .expect("a file that can't be read has an error");
+            return Err(fatal.into());
+        }
+    };
+
+    for error in &errors {
+        warn!("{}", error);
+    }
+
+    Ok((file, errors))
+}
+
+/// Reads binary data into a [`TIFile`], adding every problem found to `errors`.
+///
+/// # Returns
+///
+/// The [`TIFile`], or [`None`] if a fatal problem was found, which is then the last item in
+/// `errors`.
+fn parse_binary_data(data: &[u8], errors: &mut Vec<FileError>) -> Option<TIFile> {
+    // the file header and footer
+    if data.len() < 57 {
         debug!("{:?}", data);
-        return Err(anyhow::Error::msg(
-            "File is only long enough to contain metadata.",
-        ));
+        errors.push(FileError::TooShort { length: data.len() });
+        return None;
+    }
+
+    if !valid_8xp_header(data) {
+        debug!("{:?}", &data[..10]);
+        errors.push(FileError::BadSignature {
+            found: data[..10].to_vec(),
+        });
+        return None;
+    }
+
+    let mut header_bytes = [0; FileHeader::LENGTH];
+    header_bytes.clone_from_slice(&data[..FileHeader::LENGTH]);
+    let header = FileHeader::parse(&header_bytes);
+
+    let body = &data[FileHeader::LENGTH..data.len() - 2];
+    check_size(
+        errors,
+        "Entries length",
+        53,
+        body.len(),
+        header.entries_length,
+    );
+
+    let mut entries = Vec::new();
+    let mut position = 0;
+    while position < body.len() {
+        let entry = parse_entry(&body[position..], FileHeader::LENGTH + position, errors)?;
+        position += entry.byte_length();
+        entries.push(entry);
     }
 
-    let mut header: [u8; 74] = [0; 74];
-    header.clone_from_slice(&data[..74]);
+    let expected = common::checksum(body);
+    let actual = read_u16(data, data.len() - 2);
+    if actual != expected {
+        errors.push(FileError::ChecksumMismatch { expected, actual });
+    }
+
+    Some(TIFile { header, entries })
+}
 
-    if !valid_8xp_header(header) {
-        debug!("{:?}", &header[..10]);
-        return Err(anyhow::Error::msg(
-            "file is not a valid 8XP file: invalid header",
-        ));
+/// Adds a [`FileError::LengthMismatch`] to `errors` if a size field doesn't match the size it
+/// describes, or a [`FileError::TooLarge`] if the size doesn't fit in the field at all.
+fn check_size(
+    errors: &mut Vec<FileError>,
+    field: &'static str,
+    offset: usize,
+    size: usize,
+    actual: u16,
+) {
+    let expected = match u16::try_from(size) {
+        Ok(expected) => expected,
+        Err(_) => {
+            errors.push(FileError::TooLarge {
+                field,
+                offset,
+                size,
+            });
+            return;
+        }
+    };
+    if actual != expected {
+        errors.push(FileError::LengthMismatch {
+            field,
+            offset,
+            expected,
+            actual,
+        });
     }
+}
+
+/// Checks if `data` is empty or starts with something that looks like the header of a variable
+/// entry.
+fn starts_entry(data: &[u8]) -> bool {
+    data.is_empty() || VarHeader::parse(data).is_some()
+}
 
-    let data: Vec<u8> = data[74..data.len() - 2].to_vec();
-    let footer: Vec<u8> = data[data.len() - 2..data.len()].to_vec();
+/// Reads a single variable entry from the start of `data`.
+///
+/// # Arguments
+///
+/// * `data` - The bytes from the start of the entry to the footer.
+/// * `offset` - The offset of the entry in the file, used in errors.
+/// * `errors` - The list that problems with the entry are added to.
+///
+/// # Returns
+///
+/// The entry, or [`None`] if the entry can't be read.
+fn parse_entry(data: &[u8], offset: usize, errors: &mut Vec<FileError>) -> Option<VarEntry> {
+    let header = match VarHeader::parse(data) {
+        Some(header) => header,
+        None => {
+            errors.push(FileError::InvalidEntry {
+                offset,
+                message: "the header is too short or has an unknown length".to_string(),
+            });
+            return None;
+        }
+    };
 
-    Ok(TIFile {
+    let data_start = header.byte_length();
+    let declared = header.data_length as usize;
+    let remaining = data.len() - data_start;
+    let data_length = if declared > remaining || !starts_entry(&data[data_start + declared..]) {
+        // the size is wrong, so everything up to the footer is taken to belong to this entry
+        remaining
+    } else {
+        declared
+    };
+
+    check_size(
+        errors,
+        "Variable length",
+        offset + 2,
+        data_length,
+        header.data_length,
+    );
+    check_size(
+        errors,
+        "Variable length (repeated)",
+        offset + data_start - 2,
+        data_length,
+        header.data_length_repeated,
+    );
+
+    if !header.var_type.is_known() {
+        errors.push(FileError::UnknownType(header.var_type.into()));
+    }
+
+    let entry = VarEntry {
         header,
+        data: data[data_start..data_start + data_length].to_vec(),
+    };
+
+    // programs start with the length of their tokens
+    if entry.is_program() {
+        let tokens = entry.data.len().saturating_sub(2);
+        let actual = match entry.data.get(..2) {
+            Some(size) => read_u16(size, 0),
+            None => 0,
+        };
+        check_size(errors, "Program size", offset + data_start, tokens, actual);
+    }
+
+    Some(entry)
+}
+
+/// A program decompiled from a TI-8XP file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecompiledProgram {
+    /// The name of the program
+    pub name: String,
+    /// The lines of source code
+    pub lines: Vec<String>,
+    /// The oldest calculator model that supports every token in the program
+    pub model: Model,
+}
+
+/// How tokens are spelled in decompiled source code.
+///
+/// Every spelling can be compiled again with
+/// [`compile_to_bytecode`](super::compile::compile_to_bytecode), which also documents the aliases
+/// used by [`Spelling::Ascii`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Spelling {
+    /// The names from [`BYTE_TOKENS`](tokens::BYTE_TOKENS), such as `->`, `>=` and `θ`
+    #[default]
+    Names,
+    /// The way tokens are displayed on the calculator, such as `→`, `≥` and `√(`
+    Unicode,
+    /// Only ASCII characters where possible, such as `->`, `>=`, `sqrt(` and `theta`
+    Ascii,
+}
+
+/// Options for decompiling a TI-8XP file.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::decompile::{DecompileOptions, Spelling};
+///
+/// let options = DecompileOptions {
+///     spelling: Spelling::Unicode,
+///     ..DecompileOptions::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DecompileOptions {
+    /// Whether to read the file with [`read_binary_data_lenient`], so that slightly corrupt files
+    /// can still be decompiled
+    pub lenient: bool,
+    /// How tokens are spelled
+    pub spelling: Spelling,
+    /// Whether tokens whose text would be read as different tokens are written with `\{..}`
+    /// markup, such as `\{l}1`, rather than as hexadecimal escapes such as `\x{BBBC}1`
+    pub markup: bool,
+    /// Whether the source code will be compiled with
+    /// [`TokenizeOptions::tokens_in_strings`], so that tokens such as `Disp ` can be written
+    /// inside strings as they are
+    pub tokens_in_strings: bool,
+    /// The language that token names are written in. Tokens without a translated name, or whose
+    /// translated name can't be written with the chosen [`Spelling`], use their English names.
+    pub language: Language,
+    /// Whether assembly programs are written as hexadecimal listings with [`asm::to_listing`]
+    /// rather than as tokens
+    pub asm: bool,
+}
+
+/// Decompiles a TI-8XP file into the programs that it contains.
+///
+/// Each program in the file is decompiled separately. Entries that aren't programs, such as
+/// lists in a group file, are skipped with a warning.
+///
+/// # Arguments
+///
+/// * `data` - A vector of bytes containing binary data from a TI-8XP file.
+///
+/// # Returns
+///
+/// Returns a [`Result`] containing the decompiled programs if successful, or an error if the decompilation fails.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{
+///     common::{TIFile, VarEntry},
+///     decompile::decompile,
+/// };
+///
+/// let file = TIFile::new(vec![
+///     VarEntry::program("ONE", &[0xDE, 0x31]),
+///     VarEntry::program("TWO", &[0xDE, 0x32]),
+/// ]);
+///
+/// let programs = decompile(file.to_bytes()).unwrap();
+///
+/// assert_eq!(programs[0].name, "ONE");
+/// assert_eq!(programs[0].lines, vec!["Disp 1"]);
+/// assert_eq!(programs[1].name, "TWO");
+/// assert_eq!(programs[1].lines, vec!["Disp 2"]);
+/// ```
+pub fn decompile(data: Vec<u8>) -> Result<Vec<DecompiledProgram>, anyhow::Error> {
+    decompile_with_options(data, &DecompileOptions::default())
+}
+
+/// Decompiles a TI-8XP file like [`decompile`], but reads it with [`read_binary_data_lenient`] so
+/// that slightly corrupt files can still be decompiled. Problems with the file are logged as
+/// warnings.
+///
+/// # Arguments
+///
+/// * `data` - A vector of bytes containing binary data from a TI-8XP file.
+///
+/// # Returns
+///
+/// Returns a [`Result`] containing the decompiled programs if successful, or an error if the
+/// file can't be read at all.
+pub fn decompile_lenient(data: Vec<u8>) -> Result<Vec<DecompiledProgram>, anyhow::Error> {
+    decompile_with_options(
         data,
-        footer,
-    })
+        &DecompileOptions {
+            lenient: true,
+            ..DecompileOptions::default()
+        },
+    )
 }
 
-/// Decompiles a TI-8XP file into a vector of strings representing the lines of the decompiled content.
+/// Decompiles a TI-8XP file like [`decompile`], with options for how the file is read and how
+/// tokens are spelled.
 ///
 /// # Arguments
 ///
 /// * `data` - A vector of bytes containing binary data from a TI-8XP file.
+/// * `options` - Whether to read the file leniently, and how tokens and assembly programs are
+///   written.
 ///
 /// # Returns
 ///
-/// Returns a [`Result`] containing a vector of strings if successful, or an error if the decompilation fails.
-pub fn decompile(data: Vec<u8>) -> Result<Vec<String>, anyhow::Error> {
-    let ti_data = read_binary_data(data)?;
+/// Returns a [`Result`] containing the decompiled programs if successful, or an error if the
+/// file can't be read.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{
+///     common::{TIFile, VarEntry},
+///     decompile::{decompile_with_options, DecompileOptions, Spelling},
+/// };
+///
+/// // 2→θ
+/// let file = TIFile::new(vec![VarEntry::program("THETA", &[0x32, 0x04, 0x5B])]);
+/// let options = DecompileOptions {
+///     spelling: Spelling::Ascii,
+///     ..DecompileOptions::default()
+/// };
+///
+/// let programs = decompile_with_options(file.to_bytes(), &options).unwrap();
+///
+/// assert_eq!(programs[0].lines, vec!["2->theta"]);
+///
+/// // a squished assembly program that runs ret
+/// let file = TIFile::new(vec![VarEntry::program("RET", &[0xBB, 0x6D, 0xC9])]);
+/// let options = DecompileOptions {
+///     asm: true,
+///     ..DecompileOptions::default()
+/// };
+///
+/// let programs = decompile_with_options(file.to_bytes(), &options).unwrap();
+///
+/// assert_eq!(programs[0].lines, vec!["AsmPrgm", "C9"]);
+/// ```
+pub fn decompile_with_options(
+    data: Vec<u8>,
+    options: &DecompileOptions,
+) -> Result<Vec<DecompiledProgram>, anyhow::Error> {
+    let ti_data = if options.lenient {
+        read_binary_data_lenient(data)?.0
+    } else {
+        read_binary_data(data)?
+    };
     debug!("{:x?}", ti_data);
 
-    let mut plaintext = String::new();
+    decompile_programs(&ti_data, options)
+}
+
+/// Decompiles every program in a [`TIFile`].
+fn decompile_programs(
+    ti_data: &TIFile,
+    options: &DecompileOptions,
+) -> Result<Vec<DecompiledProgram>, anyhow::Error> {
+    let mut programs = Vec::new();
+
+    for entry in &ti_data.entries {
+        // a name that isn't valid UTF-8 shouldn't stop the other programs from being decompiled
+        let name = entry.header.lossy_name();
+        match entry.program_tokens() {
+            Some(tokens) => {
+                let listing = match options.asm {
+                    true => asm::to_listing(tokens),
+                    false => None,
+                };
+                if let (None, Payload::Squished(target)) = (&listing, asm::detect(tokens)) {
+                    warn!(
+                        "{} is a squished {} assembly program, so its machine code is decompiled as tokens",
+                        name, target
+                    );
+                }
+
+                programs.push(DecompiledProgram {
+                    lines: listing.unwrap_or_else(|| decompile_tokens_with(tokens, options)),
+                    model: required_model(tokens),
+                    name,
+                })
+            }
+            None => warn!(
+                "Skipping {}, which is not a program (type {})",
+                name, entry.header.var_type
+            ),
+        }
+    }
+
+    Ok(programs)
+}
+
+/// Converts the tokens of a program into lines of source code, using the names of the tokens.
+///
+/// # Arguments
+///
+/// * `data` - The tokens of a program, without the header or the length of the program.
+///
+/// # Returns
+///
+/// The lines of source code, which
+/// [`compile_to_bytecode`](super::compile::compile_to_bytecode) turns back into `data`. Bytes
+/// that can't be decoded are written as escapes such as `\x{EF}` and logged as warnings.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::decompile::decompile_tokens;
+///
+/// // Lbl A, Goto A
+/// let lines = decompile_tokens(&[0xD6, 0x41, 0x3F, 0xD7, 0x41]);
+///
+/// assert_eq!(lines, vec!["Lbl A", "Goto A"]);
+///
+/// // Sequential, GDB1, Y1
+/// let lines = decompile_tokens(&[0x7E, 0x00, 0x3F, 0x61, 0x00, 0x3F, 0x5E, 0x10]);
+///
+/// assert_eq!(lines, vec!["Sequential", "GDB1", "y1"]);
+///
+/// // Disp inside a string, and half of a two-byte token
+/// let lines = decompile_tokens(&[0xDE, 0x2A, 0xDE, 0x2A, 0xEF]);
+///
+/// assert_eq!(lines, vec!["Disp \"\\x{DE}\"\\x{EF}"]);
+/// ```
+///
+/// Stores close strings, so the usual way of storing a string round-trips:
+///
+/// ```
+/// use tio2::translation::{compile::compile_to_bytecode, decompile::decompile_tokens};
+///
+/// // "HI→Str1
+/// let data = [0x2A, 0x48, 0x49, 0x04, 0xAA, 0x00];
+/// let lines = decompile_tokens(&data);
+///
+/// assert_eq!(lines, vec!["\"HI->Str1"]);
+/// assert_eq!(compile_to_bytecode(vec!["\"HI->Str1"]).unwrap(), data);
+/// assert_eq!(compile_to_bytecode(vec!["\"HI→Str1"]).unwrap(), data);
+/// ```
+pub fn decompile_tokens(data: &[u8]) -> Vec<String> {
+    decompile_tokens_with(data, &DecompileOptions::default())
+}
+
+/// The most source code that a single token can depend on, in bytes. This is far longer than any
+/// token name.
+const LOOKAHEAD: usize = 256;
+
+/// Converts the tokens of a program into lines of source code, spelling each token as described
+/// by `options`.
+///
+/// Each token is checked against [`match_token`] with the source code that follows it, and if
+/// its spelling would compile to something else, its name is used instead, then the same text
+/// as `\{..}` markup if [`DecompileOptions::markup`] is set, or an escape such as `\x{B0}` if
+/// that doesn't work either. With [`Spelling::Ascii`], `ᴇ` is written as `E` inside
+/// numbers like `1E-5` and as `|E` anywhere else, and tokens without an ASCII spelling are always
+/// escaped. This makes the output lossless, so that
+/// [`compile_to_bytecode`](super::compile::compile_to_bytecode) turns it back into `data`.
+///
+/// # Arguments
+///
+/// * `data` - The tokens of a program, without the header or the length of the program.
+/// * `options` - How the tokens are spelled, whether markup is used, and how the source code
+///   will be split into tokens. [`DecompileOptions::lenient`] and [`DecompileOptions::asm`] are
+///   not used.
+///
+/// # Returns
+///
+/// The lines of source code. Bytes that can't be decoded are written as escapes and logged as
+/// warnings.
+///
+/// # Examples
+///
+/// ```
+/// use tio2::translation::decompile::{decompile_tokens_with, DecompileOptions, Spelling};
+///
+/// let unicode = DecompileOptions {
+///     spelling: Spelling::Unicode,
+///     ..DecompileOptions::default()
+/// };
+/// let ascii = DecompileOptions {
+///     spelling: Spelling::Ascii,
+///     ..DecompileOptions::default()
+/// };
+///
+/// // If θ≥π, 1ᴇ⁻5-1→A
+/// let data = [
+///     0xCE, 0x5B, 0x6E, 0xAC, 0x3F, 0x31, 0x3B, 0xB0, 0x35, 0x71, 0x31, 0x04, 0x41,
+/// ];
+///
+/// assert_eq!(decompile_tokens_with(&data, &unicode), vec!["If θ≥π", "1ᴇ⁻5-1→A"]);
+/// assert_eq!(decompile_tokens_with(&data, &ascii), vec!["If theta>=pi", "1E-5-1->A"]);
+///
+/// // 2⁻1 is an implied multiplication, so its negation can't be written as -
+/// assert_eq!(decompile_tokens_with(&[0x32, 0xB0, 0x31], &ascii), vec!["2\\x{B0}1"]);
+/// ```
+///
+/// With markup, tokens that would run together with the tokens after them are marked instead:
+///
+/// ```
+/// use tio2::translation::decompile::{decompile_tokens_with, DecompileOptions};
+///
+/// let markup = DecompileOptions {
+///     markup: true,
+///     ..DecompileOptions::default()
+/// };
+///
+/// // the lowercase letter l then 1, and S then x, rather than l1 and Sx
+/// let data = [0xBB, 0xBC, 0x31, 0x3F, 0x53, 0xBB, 0xC8];
+///
+/// assert_eq!(decompile_tokens_with(&data, &markup), vec!["\\{l}1", "\\{S}x"]);
+/// assert_eq!(
+///     decompile_tokens_with(&data, &DecompileOptions::default()),
+///     vec!["\\x{BBBC}1", "\\x{53}x"]
+/// );
+/// ```
+///
+/// Tokens can be written in another language:
+///
+/// ```
+/// use tio2::translation::{
+///     decompile::{decompile_tokens_with, DecompileOptions, Spelling},
+///     languages::Language,
+/// };
+///
+/// let mut options = DecompileOptions {
+///     language: Language::French,
+///     ..DecompileOptions::default()
+/// };
+///
+/// // Répéter A≥2, End
+/// let data = [0xD2, 0x41, 0x6E, 0x32, 0x3F, 0xD4];
+///
+/// assert_eq!(decompile_tokens_with(&data, &options), vec!["Répéter A>=2", "Fin"]);
+///
+/// // without accents, Repeat keeps its English name
+/// options.spelling = Spelling::Ascii;
+/// assert_eq!(decompile_tokens_with(&data, &options), vec!["Repeat A>=2", "Fin"]);
+/// ```
+///
+/// Every program compiles back to the same bytes with the matching [`TokenizeOptions`], which is
+/// checked with random files by the property tests in `tests/round_trip.rs`.
+pub fn decompile_tokens_with(data: &[u8], options: &DecompileOptions) -> Vec<String> {
+    let tokens = decode_tokens(data);
+    let tokenize = TokenizeOptions {
+        tokens_in_strings: options.tokens_in_strings,
+        language: options.language,
+    };
+
+    // whether each token is inside a string, which changes how it's compiled
+    let mut in_string = false;
+    let strings: Vec<bool> = tokens
+        .iter()
+        .map(|&byte| {
+            let inside = in_string;
+            in_string = string_state(in_string, byte);
+            inside
+        })
+        .collect();
+
+    // spell the tokens from last to first, so that the text after each one is known
+    let mut spellings: Vec<String> = vec![String::new(); tokens.len()];
+    for index in (0..tokens.len()).rev() {
+        let mut following = String::new();
+        for spelled in &spellings[index + 1..] {
+            if following.len() >= LOOKAHEAD {
+                break;
+            }
+            following.push_str(spelled);
+        }
+
+        let previous = index.checked_sub(1).map(|i| tokens[i]);
+        let compiles = |text: &str| {
+            match_token(
+                &format!("{}{}", text, following),
+                strings[index],
+                previous,
+                &tokenize,
+            ) == Some((tokens[index], text.len()))
+        };
+
+        let candidates = spellings_of(tokens[index], options.spelling, options.language);
+        let marked = candidates
+            .iter()
+            .filter(|_| options.markup)
+            .map(|text| format!("\\{{{}}}", text));
+
+        spellings[index] = candidates
+            .iter()
+            .map(|text| text.to_string())
+            .chain(marked)
+            .find(|text| !text.is_empty() && compiles(text))
+            .unwrap_or_else(|| format!("\\x{{{}}}", tokens[index].to_hex()));
+    }
+
+    spellings.concat().split('\n').map(str::to_string).collect()
+}
+
+/// The ways a token can be spelled, most preferred first.
+fn spellings_of(byte: Byte, spelling: Spelling, language: Language) -> Vec<&'static str> {
+    let token = match metadata::get(byte) {
+        Some(token) => token,
+        None => return Vec::new(),
+    };
+
+    let mut spellings: Vec<&'static str> = language
+        .name(byte)
+        .into_iter()
+        .filter(|name| spelling != Spelling::Ascii || name.is_ascii())
+        .collect();
+
+    spellings.extend(match (spelling, byte) {
+        (Spelling::Names, _) => vec![token.name],
+        (Spelling::Unicode, _) => vec![token.display, token.name],
+        // scientific notation inside numbers, like 1E-5
+        (Spelling::Ascii, Byte::Single(0x3B)) => vec!["E", "|E"],
+        (Spelling::Ascii, _) => vec![token.ascii()]
+            .into_iter()
+            .filter(|text| text.is_ascii())
+            .collect(),
+    });
+
+    spellings
+}
+
+/// Splits the tokens of a program into [`Byte`]s. Bytes that can't be decoded are logged as
+/// warnings and returned as [`Byte::Single`]s.
+fn decode_tokens(data: &[u8]) -> Vec<Byte> {
+    let mut decoded = Vec::new();
     let tokens = &tokens::BYTE_TOKENS;
 
     let mut byte_num = 0;
-    while byte_num < ti_data.data.len() {
-        let curr_byte = ti_data.data[byte_num];
-
-        // If the current byte exists in the tokens, see if we
-        // can find a more specific one (2 bytes) that matches. If not, use
-        // the first. We only need to worry about up to 2 bytes.
-        if let Some(single_token) = tokens.get(&Byte::Single(curr_byte)) {
-            if byte_num + 1 < ti_data.data.len() {
-                if let Some(double_token) =
-                    tokens.get(&Byte::Double([curr_byte, ti_data.data[byte_num + 1]]))
-                {
-                    plaintext.push_str(double_token.as_ref());
-                    byte_num += 2;
-                } else {
-                    plaintext.push_str(single_token.as_ref());
-                    byte_num += 1;
-                }
-            } else {
-                plaintext.push_str(single_token.as_ref());
-                byte_num += 1;
+    while byte_num < data.len() {
+        let curr_byte = data[byte_num];
+        let double = data
+            .get(byte_num + 1)
+            .map(|&next| Byte::Double([curr_byte, next]));
+
+        // If the current byte and the next one make a token, use it, since it's more specific
+        // than the current byte alone. We only need to worry about up to 2 bytes.
+        match double {
+            Some(double) if tokens.contains_key(&double) => {
+                decoded.push(double);
+                byte_num += 2;
             }
-        } else if byte_num + 1 < ti_data.data.len() {
-            // If the current byte is not in the tokens, see if we can add
-            // on the next byte to make it work. If so, use that, otherwise
-            // spit out an error but do the rest.
-            match tokens.get(&Byte::Double([curr_byte, ti_data.data[byte_num + 1]])) {
-                Some(token) => {
-                    plaintext.push_str(token.as_ref());
-                    byte_num += 2;
-                }
-                None => {
-                    error!("Could not decode {:x?}", curr_byte);
-                    error!("Next byte: {:x?}", ti_data.data.get(byte_num + 1));
-                    byte_num += 1;
+            _ => {
+                if !tokens.contains_key(&Byte::Single(curr_byte)) {
+                    warn!(
+                        "Could not decode {:02X} at byte {}, writing it as an escape",
+                        curr_byte, byte_num
+                    );
                 }
+                decoded.push(Byte::Single(curr_byte));
+                byte_num += 1;
             }
         }
     }
 
-    Ok(plaintext.split('\n').map(str::to_string).collect())
+    decoded
 }
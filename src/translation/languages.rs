@@ -0,0 +1,223 @@
+//! The `languages` module provides the names that the calculator's language apps display for
+//! tokens, so that programs can be decompiled into and compiled from French, German or Spanish.
+//!
+//! Only the tokens whose names are translated are listed. Every other token, such as `Disp ` or
+//! `Lbl `, keeps its English name from [`BYTE_TOKENS`](super::tokens::BYTE_TOKENS). Compiling in
+//! a language accepts both the translated names and the English ones.
+
+use std::{fmt, str::FromStr, sync::LazyLock};
+
+use super::{
+    tokens::Byte,
+    trie::{TokenTrie, TOKEN_TRIE},
+};
+
+/// A language that token names can be written in.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{languages::Language, tokens::Byte};
+///
+/// let language: Language = "fr".parse().unwrap();
+///
+/// assert_eq!(language, Language::French);
+/// assert_eq!(language.name(Byte::Single(0xCE)), Some("Si "));
+/// assert_eq!(language.name(Byte::Single(0xDE)), None);
+/// assert_eq!(language.trie().get("Si "), Some(Byte::Single(0xCE)));
+/// assert_eq!(language.trie().get("If "), Some(Byte::Single(0xCE)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    /// The names from [`BYTE_TOKENS`](super::tokens::BYTE_TOKENS)
+    #[default]
+    English,
+    /// The names used by the French language app and the TI-83 Premium CE
+    French,
+    /// The names used by the German language app
+    German,
+    /// The names used by the Spanish language app
+    Spanish,
+}
+
+impl Language {
+    /// Returns the translated name of a token, or [`None`] if the token keeps its English name.
+    pub fn name(self, byte: Byte) -> Option<&'static str> {
+        self.names()
+            .iter()
+            .find(|(translated, _)| *translated == byte)
+            .map(|(_, name)| *name)
+    }
+
+    /// Returns every translated token name, which is empty for [`Language::English`].
+    pub fn names(self) -> &'static [(Byte, &'static str)] {
+        match self {
+            Language::English => &[],
+            Language::French => FRENCH,
+            Language::German => GERMAN,
+            Language::Spanish => SPANISH,
+        }
+    }
+
+    /// Returns a [`TokenTrie`] containing the translated names along with everything in
+    /// [`TOKEN_TRIE`]. Translated names take priority over English ones.
+    pub fn trie(self) -> &'static TokenTrie {
+        match self {
+            Language::English => &TOKEN_TRIE,
+            Language::French => &FRENCH_TRIE,
+            Language::German => &GERMAN_TRIE,
+            Language::Spanish => &SPANISH_TRIE,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Language::English => "English",
+            Language::French => "French",
+            Language::German => "German",
+            Language::Spanish => "Spanish",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    /// Parses a language name or code such as `French`, `fr` or `español`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" | "english" => Ok(Language::English),
+            "fr" | "french" | "français" | "francais" => Ok(Language::French),
+            "de" | "german" | "deutsch" => Ok(Language::German),
+            "es" | "spanish" | "español" | "espanol" => Ok(Language::Spanish),
+            _ => Err(format!(
+                "Unknown language {:?}, expected one of en, fr, de or es",
+                s
+            )),
+        }
+    }
+}
+
+/// Builds a [`TokenTrie`] for a language from [`TOKEN_TRIE`] and the translated names.
+fn translated_trie(names: &[(Byte, &str)]) -> TokenTrie {
+    let mut trie = TOKEN_TRIE.clone();
+    for (byte, name) in names {
+        trie.insert(name, *byte);
+    }
+
+    trie
+}
+
+static FRENCH_TRIE: LazyLock<TokenTrie> = LazyLock::new(|| translated_trie(FRENCH));
+static GERMAN_TRIE: LazyLock<TokenTrie> = LazyLock::new(|| translated_trie(GERMAN));
+static SPANISH_TRIE: LazyLock<TokenTrie> = LazyLock::new(|| translated_trie(SPANISH));
+
+static FRENCH: &[(Byte, &str)] = &[
+    (Byte::Single(0x12), "arrondi("),
+    (Byte::Single(0x3C), " ou "),
+    (Byte::Single(0x3D), " ouExcl "),
+    (Byte::Single(0x40), " et "),
+    (Byte::Single(0x85), "EffDessin"),
+    (Byte::Single(0x93), "Texte("),
+    (Byte::Single(0x9C), "Ligne("),
+    (Byte::Single(0xA5), "Cercle("),
+    (Byte::Single(0xAB), "NbrAléat"),
+    (Byte::Single(0xAD), "codeTouch"),
+    (Byte::Single(0xB1), "ent("),
+    (Byte::Single(0xB6), "somme("),
+    (Byte::Single(0xB7), "produit("),
+    (Byte::Single(0xB8), "non("),
+    (Byte::Single(0xB9), "partEnt("),
+    (Byte::Single(0xBA), "partDéc("),
+    (Byte::Single(0xCE), "Si "),
+    (Byte::Single(0xCF), "Alors"),
+    (Byte::Single(0xD0), "Sinon"),
+    (Byte::Single(0xD1), "TantQue "),
+    (Byte::Single(0xD2), "Répéter "),
+    (Byte::Single(0xD3), "Pour("),
+    (Byte::Single(0xD4), "Fin"),
+    (Byte::Single(0xE1), "EffÉcr"),
+    (Byte::Single(0xE2), "Remplir("),
+    (Byte::Single(0xE3), "TriCroi("),
+    (Byte::Single(0xE4), "TriDécroi("),
+    (Byte::Double([0xBB, 0x0A]), "entAléat("),
+    (Byte::Double([0xBB, 0x0C]), "sousChaîne("),
+    (Byte::Double([0xBB, 0x0F]), "carChaîne("),
+    (Byte::Double([0xBB, 0x2B]), "longueur("),
+    (Byte::Double([0xBB, 0x54]), "EffVar "),
+    (Byte::Double([0xEF, 0x32]), "reste("),
+];
+
+static GERMAN: &[(Byte, &str)] = &[
+    (Byte::Single(0x12), "Runden("),
+    (Byte::Single(0x3C), " oder "),
+    (Byte::Single(0x3D), " xoder "),
+    (Byte::Single(0x40), " und "),
+    (Byte::Single(0x85), "LöschZeich"),
+    (Byte::Single(0x9C), "Linie("),
+    (Byte::Single(0xA5), "Kreis("),
+    (Byte::Single(0xAB), "Zufall"),
+    (Byte::Single(0xAD), "Taste"),
+    (Byte::Single(0xB1), "Ganz("),
+    (Byte::Single(0xB6), "Summe("),
+    (Byte::Single(0xB7), "Produkt("),
+    (Byte::Single(0xB8), "nicht("),
+    (Byte::Single(0xB9), "GanzTeil("),
+    (Byte::Single(0xBA), "BruchTeil("),
+    (Byte::Single(0xCE), "Wenn "),
+    (Byte::Single(0xCF), "Dann"),
+    (Byte::Single(0xD0), "Sonst"),
+    (Byte::Single(0xD1), "Solange "),
+    (Byte::Single(0xD2), "Wiederhole "),
+    (Byte::Single(0xD3), "Für("),
+    (Byte::Single(0xD4), "Ende"),
+    (Byte::Single(0xD9), "Stopp"),
+    (Byte::Single(0xE1), "LöschHome"),
+    (Byte::Single(0xE2), "Füllen("),
+    (Byte::Single(0xE3), "SortAuf("),
+    (Byte::Single(0xE4), "SortAb("),
+    (Byte::Double([0xBB, 0x0A]), "ZufallGanz("),
+    (Byte::Double([0xBB, 0x0C]), "Teil("),
+    (Byte::Double([0xBB, 0x2B]), "Länge("),
+    (Byte::Double([0xBB, 0x54]), "LöschVar "),
+    (Byte::Double([0xEF, 0x32]), "Rest("),
+];
+
+static SPANISH: &[(Byte, &str)] = &[
+    (Byte::Single(0x12), "redondeo("),
+    (Byte::Single(0x3C), " o "),
+    (Byte::Single(0x3D), " oExcl "),
+    (Byte::Single(0x40), " y "),
+    (Byte::Single(0x85), "BorrarDibujo"),
+    (Byte::Single(0x93), "Texto("),
+    (Byte::Single(0x9C), "Línea("),
+    (Byte::Single(0xA5), "Círculo("),
+    (Byte::Single(0xAB), "aleat"),
+    (Byte::Single(0xAD), "obtenerTecla"),
+    (Byte::Single(0xB1), "ent("),
+    (Byte::Single(0xB6), "suma("),
+    (Byte::Single(0xB7), "producto("),
+    (Byte::Single(0xB8), "no("),
+    (Byte::Single(0xB9), "parteEnt("),
+    (Byte::Single(0xBA), "parteDec("),
+    (Byte::Single(0xCE), "Si "),
+    (Byte::Single(0xCF), "Entonces"),
+    (Byte::Single(0xD0), "SiNo"),
+    (Byte::Single(0xD1), "Mientras "),
+    (Byte::Single(0xD2), "Repetir "),
+    (Byte::Single(0xD3), "Para("),
+    (Byte::Single(0xD4), "Fin"),
+    (Byte::Single(0xD9), "Detener"),
+    (Byte::Single(0xE1), "BorrarInicio"),
+    (Byte::Single(0xE2), "Llenar("),
+    (Byte::Single(0xE3), "OrdenA("),
+    (Byte::Single(0xE4), "OrdenD("),
+    (Byte::Double([0xBB, 0x0A]), "aleatEnt("),
+    (Byte::Double([0xBB, 0x0C]), "subCad("),
+    (Byte::Double([0xBB, 0x2B]), "longitud("),
+    (Byte::Double([0xBB, 0x54]), "BorrarVar "),
+    (Byte::Double([0xEF, 0x32]), "resto("),
+];
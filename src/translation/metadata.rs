@@ -0,0 +1,622 @@
+//! The `metadata` module describes each token in [`BYTE_TOKENS`] in more detail than its name,
+//! for tools such as editors that need to know how a token is displayed, what kind of token it
+//! is and which arguments it takes.
+//!
+//! Use [`get`] to look up a token by its bytes, or [`get_by_name`] to look it up by its name, the
+//! way it's displayed on the calculator or one of its aliases.
+
+use std::{collections::HashMap, fmt, sync::LazyLock};
+
+use super::{
+    models::{minimum_model, Model},
+    parser::{is_command, is_function, is_real_variable},
+    tokens::{Byte, BYTE_TOKENS},
+};
+
+/// The kind of a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    /// A command that begins a statement, such as `Disp ` or `ClrHome`
+    Command,
+    /// A function that returns a value, such as `int(` or `getKey`
+    Function,
+    /// A variable, such as `A`, `Str1` or `Xmin`
+    Variable,
+    /// An operator, such as `+`, ` and ` or `->`
+    Operator,
+    /// A constant value, such as `π` or `BLUE`
+    Constant,
+    /// Part of a number, which is a digit, `.` or `ᴇ`
+    Literal,
+    /// Brackets, quotes, separators and the end of a line
+    Punctuation,
+    /// A character that is mostly used in strings, such as lowercase letters
+    Character,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Detailed information about a token.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{
+///     metadata::{self, Category},
+///     tokens::Byte,
+/// };
+///
+/// let token = metadata::get(Byte::Single(0xE0)).unwrap();
+///
+/// assert_eq!(token.id, "E0");
+/// assert_eq!(token.name, "Output(");
+/// assert_eq!(token.category, Category::Command);
+/// assert!(token.opens_paren);
+/// assert_eq!(token.signature, Some("Output(row,column,value)"));
+/// assert_eq!((token.min_arguments, token.max_arguments), (3, Some(3)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// A stable identifier, which is the bytes of the token in uppercase hexadecimal, such as
+    /// `DE` or `EF67`
+    pub id: String,
+    /// The bytes of the token
+    pub byte: Byte,
+    /// The name used when compiling and decompiling, which is the value in [`BYTE_TOKENS`]
+    pub name: &'static str,
+    /// How the token is displayed on the calculator, such as `→` for `->`
+    pub display: &'static str,
+    /// Other spellings of the token, including [`Token::display`] if it's different from the name
+    pub aliases: Vec<&'static str>,
+    /// The kind of token
+    pub category: Category,
+    /// Whether the token ends with an opening parenthesis, like `int(`
+    pub opens_paren: bool,
+    /// How the token is used, with optional arguments in square brackets and repeated arguments
+    /// marked with `...`, if known
+    pub signature: Option<&'static str>,
+    /// The smallest number of arguments that the token takes
+    pub min_arguments: usize,
+    /// The largest number of arguments that the token takes, or [`None`] if there is no limit
+    pub max_arguments: Option<usize>,
+    /// A short description of what the token does
+    pub description: String,
+    /// The oldest calculator model that supports the token
+    pub model: Model,
+}
+
+impl Token {
+    /// Returns a spelling of the token that only uses ASCII characters, which is its name if
+    /// possible and otherwise its first ASCII alias. Tokens without one, such as accented letters,
+    /// fall back to their name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::{metadata, tokens::Byte};
+    ///
+    /// assert_eq!(metadata::get(Byte::Single(0x6E)).unwrap().ascii(), ">=");
+    /// assert_eq!(metadata::get(Byte::Single(0xAC)).unwrap().ascii(), "pi");
+    /// assert_eq!(metadata::get(Byte::Single(0x71)).unwrap().ascii(), "-");
+    /// ```
+    pub fn ascii(&self) -> &'static str {
+        if self.name.is_ascii() {
+            return self.name;
+        }
+
+        self.aliases
+            .iter()
+            .find(|alias| alias.is_ascii())
+            .copied()
+            .unwrap_or(self.name)
+    }
+}
+
+/// Returns the metadata of the token with the given bytes.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{metadata, tokens::Byte};
+///
+/// assert_eq!(metadata::get(Byte::Single(0x04)).unwrap().display, "→");
+/// assert!(metadata::get(Byte::Double([0xEF, 0xFF])).is_none());
+/// ```
+pub fn get(byte: Byte) -> Option<&'static Token> {
+    TOKENS.get(&byte)
+}
+
+/// Returns the metadata of the token with the given name, display form or alias.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{metadata, tokens::Byte};
+///
+/// assert_eq!(metadata::get_by_name("->").unwrap().byte, Byte::Single(0x04));
+/// assert_eq!(metadata::get_by_name("→").unwrap().byte, Byte::Single(0x04));
+/// assert_eq!(metadata::get_by_name("theta").unwrap().name, "θ");
+/// ```
+pub fn get_by_name(name: &str) -> Option<&'static Token> {
+    TOKENS_BY_NAME.get(name).and_then(|byte| TOKENS.get(byte))
+}
+
+/// The metadata of every token in [`BYTE_TOKENS`].
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{
+///     metadata::{Category, TOKENS},
+///     tokens::{Byte, BYTE_TOKENS},
+/// };
+///
+/// assert_eq!(TOKENS.len(), BYTE_TOKENS.len());
+///
+/// let colors = TOKENS.values().filter(|t| t.category == Category::Constant);
+/// assert!(colors.map(|t| t.byte).any(|b| b == Byte::Double([0xEF, 0x41])));
+/// ```
+pub static TOKENS: LazyLock<HashMap<Byte, Token>> = LazyLock::new(|| {
+    let details: HashMap<Byte, Details> = DETAILS
+        .iter()
+        .map(|(byte, display, aliases, signature, description)| {
+            (
+                *byte,
+                Details {
+                    display,
+                    aliases,
+                    signature,
+                    description,
+                },
+            )
+        })
+        .collect();
+
+    BYTE_TOKENS
+        .iter()
+        .map(|(&byte, &name)| (byte, build_token(byte, name, details.get(&byte))))
+        .collect()
+});
+
+/// Maps every name, display form and alias to the bytes of its token. Names take priority over
+/// aliases, so an alias can never hide another token.
+static TOKENS_BY_NAME: LazyLock<HashMap<&'static str, Byte>> = LazyLock::new(|| {
+    let mut names: HashMap<&'static str, Byte> = HashMap::new();
+
+    for token in TOKENS.values() {
+        for alias in &token.aliases {
+            names.insert(alias, token.byte);
+        }
+    }
+    for token in TOKENS.values() {
+        names.insert(token.name, token.byte);
+    }
+
+    names
+});
+
+/// The curated parts of a token's metadata.
+struct Details {
+    display: &'static str,
+    aliases: &'static [&'static str],
+    signature: &'static str,
+    description: &'static str,
+}
+
+/// Combines the curated details of a token with the information that can be worked out from its
+/// name and bytes.
+fn build_token(byte: Byte, name: &'static str, details: Option<&Details>) -> Token {
+    let display = details
+        .map(|d| d.display)
+        .filter(|d| !d.is_empty())
+        .unwrap_or(name);
+
+    let mut aliases: Vec<&'static str> = Vec::new();
+    if display != name {
+        aliases.push(display);
+    }
+    for alias in details.map_or(&[][..], |d| d.aliases) {
+        if !aliases.contains(alias) {
+            aliases.push(alias);
+        }
+    }
+
+    let category = category(byte);
+    let signature = details.map(|d| d.signature).filter(|s| !s.is_empty());
+    let (min_arguments, max_arguments) = match signature {
+        Some(signature) => count_arguments(signature.strip_prefix(display).unwrap_or(signature)),
+        None => (0, Some(0)),
+    };
+
+    let description = match details {
+        Some(details) if !details.description.is_empty() => details.description.to_string(),
+        _ => default_description(byte, name.trim(), category),
+    };
+
+    Token {
+        id: byte.to_hex(),
+        byte,
+        name,
+        display,
+        aliases,
+        category,
+        opens_paren: name.ends_with('('),
+        signature,
+        min_arguments,
+        max_arguments,
+        description,
+        model: minimum_model(byte),
+    }
+}
+
+/// Works out the category of a token from its bytes.
+fn category(byte: Byte) -> Category {
+    match byte {
+        Byte::Single(
+            0x01..=0x04
+            | 0x0A..=0x0F
+            | 0x2D
+            | 0x3C
+            | 0x3D
+            | 0x40
+            | 0x6A..=0x71
+            | 0x82
+            | 0x83
+            | 0x94
+            | 0x95
+            | 0xB0
+            | 0xF0
+            | 0xF1,
+        )
+        | Byte::Double([0xBB, 0x2F | 0x30])
+        | Byte::Double([0xEF, 0x30 | 0x31]) => Category::Operator,
+        Byte::Single(0x05 | 0x2C | 0xAC | 0xFC..=0xFE)
+        | Byte::Double([0xBB, 0x31 | 0x5A | 0x5B])
+        | Byte::Double([0xEF, 0x41..=0x4F]) => Category::Constant,
+        Byte::Single(0x30..=0x3B) => Category::Literal,
+        Byte::Single(0x06..=0x09 | 0x10 | 0x11 | 0x29 | 0x2A | 0x2B | 0x3E | 0x3F) => {
+            Category::Punctuation
+        }
+        Byte::Single(v) if is_real_variable(v) => Category::Variable,
+        Byte::Single(0x72 | 0xEB)
+        | Byte::Double([0x5C..=0x63 | 0xAA, _])
+        | Byte::Double([0xEF, 0x50..=0x59 | 0x82..=0x8D]) => Category::Variable,
+        Byte::Single(0x7F..=0x81 | 0xAE | 0xAF)
+        | Byte::Double([0xBB, 0x6E..=0xFF])
+        | Byte::Double([0xEF, 0x2E | 0x2F]) => Category::Character,
+        Byte::Single(0xAB | 0xAD) | Byte::Double([0xEF, 0x09..=0x0E]) => Category::Function,
+        _ if is_command(byte) => Category::Command,
+        _ if is_function(byte) => Category::Function,
+        _ => Category::Command,
+    }
+}
+
+/// Counts the arguments in the part of a signature after the token, such as
+/// `(variable,start,end[,step])`.
+fn count_arguments(arguments: &str) -> (usize, Option<usize>) {
+    let arguments = arguments.trim().trim_end_matches(')');
+    let count = |s: &str| {
+        s.split(',')
+            .filter(|a| !a.trim().is_empty() && a.trim() != "...")
+            .count()
+    };
+
+    let required = count(arguments.split('[').next().unwrap_or_default());
+    if arguments.contains("...") {
+        return (required, None);
+    }
+
+    (required, Some(count(&arguments.replace(['[', ']'], ""))))
+}
+
+/// Describes a token that doesn't have a curated description.
+fn default_description(byte: Byte, name: &str, category: Category) -> String {
+    let kind = match byte {
+        Byte::Double([0x5C, _]) => "The matrix",
+        Byte::Double([0x5D, _]) => "The list",
+        Byte::Double([0x5E, _]) => "The equation variable",
+        Byte::Double([0x60, _]) => "The picture",
+        Byte::Double([0x61, _]) => "The graph database",
+        Byte::Double([0x62, _]) => "The statistics variable",
+        Byte::Double([0x63, _]) => "The window, table or finance variable",
+        Byte::Double([0xAA, _]) => "The string variable",
+        Byte::Double([0xEF, 0x41..=0x4F]) => "The color",
+        Byte::Double([0xEF, 0x50..=0x59]) => "The image variable",
+        _ => match category {
+            Category::Command => "The command",
+            Category::Function => "The function",
+            Category::Variable => "The real variable",
+            Category::Operator => "The operator",
+            Category::Constant => "The constant",
+            Category::Literal => "The number part",
+            Category::Punctuation => "The punctuation",
+            Category::Character => "The character",
+        },
+    };
+
+    format!("{} {}", kind, name)
+}
+
+/// Display forms, aliases, signatures and descriptions for tokens. Empty strings mean that the
+/// display form is the same as the name, or that the signature or description isn't known.
+#[rustfmt::skip]
+const DETAILS: &[(Byte, &str, &[&str], &str, &str)] = &[
+    // conversions and postfix operators
+    (Byte::Single(0x01), "►DMS", &[], "", "Displays a number in degrees, minutes and seconds"),
+    (Byte::Single(0x02), "►Dec", &[], "", "Displays a number as a decimal"),
+    (Byte::Single(0x03), "►Frac", &[], "", "Displays a number as a fraction"),
+    (Byte::Single(0x04), "→", &[], "", "Stores a value into a variable"),
+    (Byte::Single(0x0B), "", &["^^o"], "", "Marks an angle as degrees"),
+    (Byte::Single(0x0C), "⁻¹", &["^^-1"], "", "The reciprocal or inverse of a value"),
+    (Byte::Single(0x0D), "²", &["^^2"], "", "Squares a value"),
+    (Byte::Single(0x0E), "ᵀ", &["^^T"], "", "Transposes a matrix"),
+    (Byte::Single(0x0F), "³", &["^^3"], "", "Cubes a value"),
+    (Byte::Single(0x2D), "", &[], "", "The factorial of a value"),
+    // functions
+    (Byte::Single(0x12), "", &[], "round(value[,digits])", "Rounds a value to a number of decimal places"),
+    (Byte::Single(0x13), "", &[], "pxl-Test(row,column)", "Checks whether a pixel on the graph screen is on"),
+    (Byte::Single(0x14), "", &[], "augment(a,b)", "Joins two lists or matrices together"),
+    (Byte::Single(0x15), "", &[], "rowSwap(matrix,rowA,rowB)", "Swaps two rows of a matrix"),
+    (Byte::Single(0x16), "", &[], "row+(matrix,rowA,rowB)", "Adds one row of a matrix to another"),
+    (Byte::Single(0x17), "", &[], "*row(value,matrix,row)", "Multiplies a row of a matrix by a value"),
+    (Byte::Single(0x18), "", &[], "*row+(value,matrix,rowA,rowB)", "Adds a multiple of one row of a matrix to another"),
+    (Byte::Single(0x19), "", &[], "max(a[,b])", "The largest of two values or of the elements of a list"),
+    (Byte::Single(0x1A), "", &[], "min(a[,b])", "The smallest of two values or of the elements of a list"),
+    (Byte::Single(0x1B), "R►Pr(", &[], "R►Pr(x,y)", "Converts rectangular coordinates to a polar radius"),
+    (Byte::Single(0x1C), "R►Pθ(", &["R>Ptheta("], "R►Pθ(x,y)", "Converts rectangular coordinates to a polar angle"),
+    (Byte::Single(0x1D), "P►Rx(", &[], "P►Rx(r,θ)", "Converts polar coordinates to a rectangular x coordinate"),
+    (Byte::Single(0x1E), "P►Ry(", &[], "P►Ry(r,θ)", "Converts polar coordinates to a rectangular y coordinate"),
+    (Byte::Single(0x1F), "", &[], "median(list[,frequencies])", "The median of a list"),
+    (Byte::Single(0x20), "", &[], "randM(rows,columns)", "Creates a matrix of random integers"),
+    (Byte::Single(0x21), "", &[], "mean(list[,frequencies])", "The mean of a list"),
+    (Byte::Single(0x22), "", &[], "solve(expression,variable,guess[,{lower,upper}])", "Solves an equation for a variable"),
+    (Byte::Single(0x23), "", &[], "seq(expression,variable,start,end[,step])", "Creates a list by evaluating an expression for a range of values"),
+    (Byte::Single(0x24), "", &[], "fnInt(expression,variable,lower,upper[,tolerance])", "Numerically integrates an expression"),
+    (Byte::Single(0x25), "", &[], "nDeriv(expression,variable,value[,h])", "Numerically differentiates an expression"),
+    (Byte::Single(0x27), "", &[], "fMin(expression,variable,lower,upper[,tolerance])", "Finds the minimum of an expression"),
+    (Byte::Single(0x28), "", &[], "fMax(expression,variable,lower,upper[,tolerance])", "Finds the maximum of an expression"),
+    (Byte::Single(0x2C), "𝑖", &[], "", "The imaginary unit"),
+    // number parts and punctuation
+    (Byte::Single(0x29), "", &[], "", "A space"),
+    (Byte::Single(0x2A), "", &[], "", "Starts or ends a string"),
+    (Byte::Single(0x2B), "", &[], "", "Separates arguments"),
+    (Byte::Single(0x3A), "", &[], "", "The decimal point"),
+    (Byte::Single(0x3B), "ᴇ", &["|E"], "", "Scientific notation, which multiplies by a power of ten"),
+    (Byte::Single(0x3E), "", &[], "", "Separates statements on the same line"),
+    (Byte::Single(0x3F), "", &[], "", "Ends a line"),
+    // logic and relations
+    (Byte::Single(0x3C), "", &[], "", "True if either value is nonzero"),
+    (Byte::Single(0x3D), "", &[], "", "True if exactly one value is nonzero"),
+    (Byte::Single(0x40), "", &[], "", "True if both values are nonzero"),
+    (Byte::Single(0x5B), "", &["theta"], "", "The real variable θ"),
+    (Byte::Single(0x5F), "", &[], "prgmNAME", "Runs another program"),
+    (Byte::Single(0x6A), "", &[], "", "True if two values are equal"),
+    (Byte::Single(0x6B), "", &[], "", "True if the first value is less than the second"),
+    (Byte::Single(0x6C), "", &[], "", "True if the first value is greater than the second"),
+    (Byte::Single(0x6D), "≤", &[], "", "True if the first value is less than or equal to the second"),
+    (Byte::Single(0x6E), "≥", &[], "", "True if the first value is greater than or equal to the second"),
+    (Byte::Single(0x6F), "≠", &[], "", "True if two values are not equal"),
+    (Byte::Single(0x70), "", &[], "", "Adds two values or joins two strings"),
+    (Byte::Single(0x71), "-", &[], "", "Subtracts one value from another"),
+    (Byte::Single(0x72), "", &[], "", "The result of the last expression"),
+    (Byte::Single(0x82), "", &[], "", "Multiplies two values"),
+    (Byte::Single(0x83), "", &[], "", "Divides one value by another"),
+    (Byte::Single(0x94), "", &[], "", "The number of permutations"),
+    (Byte::Single(0x95), "", &[], "", "The number of combinations"),
+    (Byte::Single(0xB0), "⁻", &[], "", "Negates a value"),
+    (Byte::Single(0xF0), "", &[], "", "Raises a value to a power"),
+    (Byte::Single(0xF1), "ˣ√", &[], "", "Takes a root of a value"),
+    // modes and graphing
+    (Byte::Single(0x73), "", &[], "Fix digits", "Displays numbers with a fixed number of decimal places"),
+    (Byte::Single(0x85), "", &[], "", "Clears the drawings on the graph screen"),
+    (Byte::Single(0x93), "", &[], "Text(row,column,value[,value,...])", "Draws text on the graph screen"),
+    (Byte::Single(0x96), "", &[], "FnOn [function,...]", "Turns on functions in the Y= editor"),
+    (Byte::Single(0x97), "", &[], "FnOff [function,...]", "Turns off functions in the Y= editor"),
+    (Byte::Single(0x98), "", &[], "StorePic picture", "Saves the graph screen to a picture"),
+    (Byte::Single(0x99), "", &[], "RecallPic picture", "Draws a picture on the graph screen"),
+    (Byte::Single(0x9A), "", &[], "StoreGDB database", "Saves the graph settings to a graph database"),
+    (Byte::Single(0x9B), "", &[], "RecallGDB database", "Restores the graph settings from a graph database"),
+    (Byte::Single(0x9C), "", &[], "Line(x1,y1,x2,y2[,erase])", "Draws a line on the graph screen"),
+    (Byte::Single(0x9D), "", &[], "Vertical x", "Draws a vertical line on the graph screen"),
+    (Byte::Single(0x9E), "", &[], "Pt-On(x,y[,mark])", "Turns on a point on the graph screen"),
+    (Byte::Single(0x9F), "", &[], "Pt-Off(x,y[,mark])", "Turns off a point on the graph screen"),
+    (Byte::Single(0xA0), "", &[], "Pt-Change(x,y)", "Toggles a point on the graph screen"),
+    (Byte::Single(0xA1), "", &[], "Pxl-On(row,column)", "Turns on a pixel on the graph screen"),
+    (Byte::Single(0xA2), "", &[], "Pxl-Off(row,column)", "Turns off a pixel on the graph screen"),
+    (Byte::Single(0xA3), "", &[], "Pxl-Change(row,column)", "Toggles a pixel on the graph screen"),
+    (Byte::Single(0xA4), "", &[], "Shade(lower,upper[,left,right,pattern,resolution])", "Shades the area between two functions"),
+    (Byte::Single(0xA5), "", &[], "Circle(x,y,radius)", "Draws a circle on the graph screen"),
+    (Byte::Single(0xA6), "", &[], "Horizontal y", "Draws a horizontal line on the graph screen"),
+    (Byte::Single(0xA7), "", &[], "Tangent(expression,x)", "Draws a tangent line to a function"),
+    (Byte::Single(0xA8), "", &[], "DrawInv expression", "Draws the inverse of a function"),
+    (Byte::Single(0xA9), "", &[], "DrawF expression", "Draws a function on the graph screen"),
+    (Byte::Single(0xAB), "", &[], "", "A random number between 0 and 1"),
+    (Byte::Single(0xAC), "", &["pi"], "", "The ratio of a circle's circumference to its diameter"),
+    (Byte::Single(0xAD), "", &[], "", "The code of the last key pressed, or 0 if no key was pressed"),
+    // math functions
+    (Byte::Single(0xB1), "", &[], "int(value)", "The largest integer less than or equal to a value"),
+    (Byte::Single(0xB2), "", &[], "abs(value)", "The absolute value of a value"),
+    (Byte::Single(0xB3), "", &[], "det(matrix)", "The determinant of a matrix"),
+    (Byte::Single(0xB4), "", &[], "identity(size)", "Creates an identity matrix"),
+    (Byte::Single(0xB5), "", &[], "dim(value)", "The size of a list or matrix"),
+    (Byte::Single(0xB6), "", &[], "sum(list[,start,end])", "The sum of the elements of a list"),
+    (Byte::Single(0xB7), "", &[], "prod(list[,start,end])", "The product of the elements of a list"),
+    (Byte::Single(0xB8), "", &[], "not(value)", "True if a value is zero"),
+    (Byte::Single(0xB9), "", &[], "iPart(value)", "The integer part of a value"),
+    (Byte::Single(0xBA), "", &[], "fPart(value)", "The fractional part of a value"),
+    (Byte::Single(0xBC), "√(", &[], "√(value)", "The square root of a value"),
+    (Byte::Single(0xBD), "³√(", &[], "³√(value)", "The cube root of a value"),
+    (Byte::Single(0xBE), "", &[], "ln(value)", "The natural logarithm of a value"),
+    (Byte::Single(0xBF), "𝑒^(", &[], "𝑒^(value)", "Raises 𝑒 to a power"),
+    (Byte::Single(0xC0), "", &[], "log(value)", "The base 10 logarithm of a value"),
+    (Byte::Single(0xC1), "", &[], "10^(value)", "Raises 10 to a power"),
+    (Byte::Single(0xC2), "", &[], "sin(angle)", "The sine of an angle"),
+    (Byte::Single(0xC3), "sin⁻¹(", &[], "sin⁻¹(value)", "The inverse sine of a value"),
+    (Byte::Single(0xC4), "", &[], "cos(angle)", "The cosine of an angle"),
+    (Byte::Single(0xC5), "cos⁻¹(", &[], "cos⁻¹(value)", "The inverse cosine of a value"),
+    (Byte::Single(0xC6), "", &[], "tan(angle)", "The tangent of an angle"),
+    (Byte::Single(0xC7), "tan⁻¹(", &[], "tan⁻¹(value)", "The inverse tangent of a value"),
+    (Byte::Single(0xC8), "", &[], "sinh(value)", "The hyperbolic sine of a value"),
+    (Byte::Single(0xC9), "sinh⁻¹(", &[], "sinh⁻¹(value)", "The inverse hyperbolic sine of a value"),
+    (Byte::Single(0xCA), "", &[], "cosh(value)", "The hyperbolic cosine of a value"),
+    (Byte::Single(0xCB), "cosh⁻¹(", &[], "cosh⁻¹(value)", "The inverse hyperbolic cosine of a value"),
+    (Byte::Single(0xCC), "", &[], "tanh(value)", "The hyperbolic tangent of a value"),
+    (Byte::Single(0xCD), "tanh⁻¹(", &[], "tanh⁻¹(value)", "The inverse hyperbolic tangent of a value"),
+    // control flow
+    (Byte::Single(0xCE), "", &[], "If condition", "Runs the next statement, or a Then block, if a condition is true"),
+    (Byte::Single(0xCF), "", &[], "", "Starts a block that runs when an If condition is true"),
+    (Byte::Single(0xD0), "", &[], "", "Starts a block that runs when an If condition is false"),
+    (Byte::Single(0xD1), "", &[], "While condition", "Repeats a block while a condition is true"),
+    (Byte::Single(0xD2), "", &[], "Repeat condition", "Repeats a block until a condition is true"),
+    (Byte::Single(0xD3), "For(", &[], "For(variable,start,end[,step])", "Repeats a block for a range of values of a variable"),
+    (Byte::Single(0xD4), "", &[], "", "Ends an If, While, Repeat or For block"),
+    (Byte::Single(0xD5), "", &[], "", "Returns from the current program"),
+    (Byte::Single(0xD6), "", &[], "Lbl name", "Marks a place in the program that Goto and Menu( can jump to"),
+    (Byte::Single(0xD7), "", &[], "Goto name", "Jumps to a label"),
+    (Byte::Single(0xD8), "", &[], "Pause [value]", "Waits for the user to press enter, optionally displaying a value"),
+    (Byte::Single(0xD9), "", &[], "", "Stops all programs"),
+    (Byte::Single(0xDA), "", &[], "IS>(variable,value)", "Increments a variable and skips the next statement if it's greater than a value"),
+    (Byte::Single(0xDB), "DS<(", &[], "DS<(variable,value)", "Decrements a variable and skips the next statement if it's less than a value"),
+    // input and output
+    (Byte::Single(0xDC), "", &[], "Input [[prompt,]variable]", "Asks the user to enter a value"),
+    (Byte::Single(0xDD), "", &[], "Prompt variable[,variable,...]", "Asks the user to enter values for variables"),
+    (Byte::Single(0xDE), "", &[], "Disp [value,...]", "Displays values on the home screen"),
+    (Byte::Single(0xDF), "", &[], "", "Shows the graph screen"),
+    (Byte::Single(0xE0), "", &[], "Output(row,column,value)", "Displays a value at a position on the home screen"),
+    (Byte::Single(0xE1), "", &[], "", "Clears the home screen"),
+    (Byte::Single(0xE2), "", &[], "Fill(value,variable)", "Sets every element of a list or matrix to a value"),
+    (Byte::Single(0xE3), "", &[], "SortA(list[,list,...])", "Sorts lists in ascending order"),
+    (Byte::Single(0xE4), "", &[], "SortD(list[,list,...])", "Sorts lists in descending order"),
+    (Byte::Single(0xE5), "", &[], "", "Shows the table screen"),
+    (Byte::Single(0xE6), "", &[], "Menu(title,text,label[,text,label,...])", "Shows a menu and jumps to the label of the chosen option"),
+    (Byte::Single(0xE7), "", &[], "Send(variable)", "Sends a variable to a connected device"),
+    (Byte::Single(0xE8), "", &[], "Get(variable)", "Receives a variable from a connected device"),
+    (Byte::Single(0xE9), "", &[], "PlotsOn [plot,...]", "Turns on statistics plots"),
+    (Byte::Single(0xEA), "", &[], "PlotsOff [plot,...]", "Turns off statistics plots"),
+    (Byte::Single(0xEB), "", &["|L"], "", "Marks the name of a custom list"),
+    (Byte::Single(0xEC), "", &[], "Plot1(type,xlist,ylist[,mark])", "Sets up statistics plot 1"),
+    (Byte::Single(0xED), "", &[], "Plot2(type,xlist,ylist[,mark])", "Sets up statistics plot 2"),
+    (Byte::Single(0xEE), "", &[], "Plot3(type,xlist,ylist[,mark])", "Sets up statistics plot 3"),
+    (Byte::Single(0xFA), "", &[], "ClrList list[,list,...]", "Clears lists"),
+    (Byte::Single(0xFB), "", &[], "", "Clears the table"),
+    // variables
+    (Byte::Double([0x5D, 0x00]), "L₁", &[], "", "The list L₁"),
+    (Byte::Double([0x5D, 0x01]), "L₂", &[], "", "The list L₂"),
+    (Byte::Double([0x5D, 0x02]), "L₃", &[], "", "The list L₃"),
+    (Byte::Double([0x5D, 0x03]), "L₄", &[], "", "The list L₄"),
+    (Byte::Double([0x5D, 0x04]), "L₅", &[], "", "The list L₅"),
+    (Byte::Double([0x5D, 0x05]), "L₆", &[], "", "The list L₆"),
+    (Byte::Double([0x62, 0x03]), "x̄", &[], "", "The mean of the x values"),
+    (Byte::Double([0x62, 0x04]), "Σx", &[], "", "The sum of the x values"),
+    (Byte::Double([0x62, 0x05]), "Σx²", &[], "", "The sum of the squares of the x values"),
+    (Byte::Double([0x62, 0x07]), "σx", &[], "", "The population standard deviation of the x values"),
+    (Byte::Double([0x62, 0x0C]), "ȳ", &[], "", "The mean of the y values"),
+    (Byte::Double([0x62, 0x0D]), "Σy", &[], "", "The sum of the y values"),
+    (Byte::Double([0x62, 0x0E]), "Σy²", &[], "", "The sum of the squares of the y values"),
+    (Byte::Double([0x62, 0x10]), "σy", &[], "", "The population standard deviation of the y values"),
+    (Byte::Double([0x62, 0x11]), "Σxy", &[], "", "The sum of the products of the x and y values"),
+    (Byte::Double([0x62, 0x25]), "χ²", &[], "", "The chi-square statistic"),
+    (Byte::Double([0x62, 0x26]), "𝐅", &[], "", "The F statistic"),
+    (Byte::Double([0x62, 0x21]), "", &["|n"], "", "The number of data points"),
+    (Byte::Double([0x62, 0x28]), "p̂", &[], "", "The estimated proportion"),
+    (Byte::Double([0x63, 0x10]), "", &["thetamin"], "", "The smallest θ value graphed in polar mode"),
+    (Byte::Double([0x63, 0x11]), "", &["thetamax"], "", "The largest θ value graphed in polar mode"),
+    (Byte::Double([0x63, 0x16]), "", &["Zthetamin"], "", "The θmin value used by ZoomSto"),
+    (Byte::Double([0x63, 0x17]), "", &["Zthetamax"], "", "The θmax value used by ZoomSto"),
+    (Byte::Double([0x63, 0x23]), "", &["thetastep"], "", "The θ increment in polar mode"),
+    (Byte::Double([0x63, 0x25]), "", &["Zthetastep"], "", "The θstep value used by ZoomSto"),
+    // BB tokens
+    (Byte::Double([0xBB, 0x03]), "ΣPrn(", &[], "ΣPrn(start,end[,round])", "The principal paid over a range of payments"),
+    (Byte::Double([0xBB, 0x04]), "ΣInt(", &[], "ΣInt(start,end[,round])", "The interest paid over a range of payments"),
+    (Byte::Double([0xBB, 0x05]), "►Nom(", &[], "►Nom(rate,periods)", "Converts an effective interest rate to a nominal rate"),
+    (Byte::Double([0xBB, 0x06]), "►Eff(", &[], "►Eff(rate,periods)", "Converts a nominal interest rate to an effective rate"),
+    (Byte::Double([0xBB, 0x08]), "", &[], "lcm(a,b)", "The least common multiple of two values"),
+    (Byte::Double([0xBB, 0x09]), "", &[], "gcd(a,b)", "The greatest common divisor of two values"),
+    (Byte::Double([0xBB, 0x0A]), "", &[], "randInt(lower,upper[,count])", "A random integer between two values"),
+    (Byte::Double([0xBB, 0x0B]), "", &[], "randBin(trials,probability[,count])", "A random number from a binomial distribution"),
+    (Byte::Double([0xBB, 0x0C]), "", &[], "sub(string,start,length)", "Part of a string"),
+    (Byte::Double([0xBB, 0x0D]), "", &[], "stdDev(list[,frequencies])", "The sample standard deviation of a list"),
+    (Byte::Double([0xBB, 0x0E]), "", &[], "variance(list[,frequencies])", "The sample variance of a list"),
+    (Byte::Double([0xBB, 0x0F]), "", &[], "inString(string,search[,start])", "The position of one string in another, or 0 if it isn't found"),
+    (Byte::Double([0xBB, 0x1F]), "", &[], "randNorm(mean,deviation[,count])", "A random number from a normal distribution"),
+    (Byte::Double([0xBB, 0x25]), "", &[], "conj(value)", "The complex conjugate of a value"),
+    (Byte::Double([0xBB, 0x26]), "", &[], "real(value)", "The real part of a complex value"),
+    (Byte::Double([0xBB, 0x27]), "", &[], "imag(value)", "The imaginary part of a complex value"),
+    (Byte::Double([0xBB, 0x28]), "", &[], "angle(value)", "The polar angle of a complex value"),
+    (Byte::Double([0xBB, 0x29]), "", &[], "cumSum(value)", "The cumulative sums of a list or matrix"),
+    (Byte::Double([0xBB, 0x2A]), "", &[], "expr(string)", "Evaluates a string as an expression"),
+    (Byte::Double([0xBB, 0x2B]), "", &[], "length(string)", "The number of tokens in a string"),
+    (Byte::Double([0xBB, 0x2C]), "ΔList(", &[], "ΔList(list)", "The differences between consecutive elements of a list"),
+    (Byte::Double([0xBB, 0x2D]), "", &[], "ref(matrix)", "The row-echelon form of a matrix"),
+    (Byte::Double([0xBB, 0x2E]), "", &[], "rref(matrix)", "The reduced row-echelon form of a matrix"),
+    (Byte::Double([0xBB, 0x2F]), "►Rect", &[], "", "Displays a complex value in rectangular form"),
+    (Byte::Double([0xBB, 0x30]), "►Polar", &[], "", "Displays a complex value in polar form"),
+    (Byte::Double([0xBB, 0x31]), "", &["|e"], "", "The base of the natural logarithm"),
+    (Byte::Double([0xBB, 0x39]), "Matr►list(", &[], "Matr►list(matrix,list[,list,...])", "Copies the columns of a matrix into lists"),
+    (Byte::Double([0xBB, 0x3A]), "List►matr(", &[], "List►matr(list,...,matrix)", "Copies lists into the columns of a matrix"),
+    (Byte::Double([0xBB, 0x45]), "", &[], "GraphStyle(function,style)", "Sets the style that a function is drawn with"),
+    (Byte::Double([0xBB, 0x4A]), "", &[], "SetUpEditor [list,...]", "Sets the lists shown in the list editor"),
+    (Byte::Double([0xBB, 0x4E]), "", &["re^thetai"], "", "Displays complex results in polar form"),
+    (Byte::Double([0xBB, 0x52]), "", &[], "", "Clears every list"),
+    (Byte::Double([0xBB, 0x53]), "", &[], "GetCalc(variable)", "Receives a variable from another calculator"),
+    (Byte::Double([0xBB, 0x54]), "", &[], "DelVar variable", "Deletes a variable"),
+    (Byte::Double([0xBB, 0x55]), "Equ►String(", &[], "Equ►String(equation,string)", "Converts an equation variable to a string"),
+    (Byte::Double([0xBB, 0x56]), "String►Equ(", &[], "String►Equ(string,equation)", "Converts a string to an equation variable"),
+    (Byte::Double([0xBB, 0x66]), "", &[], "", "Shows r and r² after regressions"),
+    (Byte::Double([0xBB, 0x67]), "", &[], "", "Hides r and r² after regressions"),
+    (Byte::Double([0xBB, 0x68]), "", &[], "Archive variable", "Moves a variable to the archive"),
+    (Byte::Double([0xBB, 0x69]), "", &[], "UnArchive variable", "Moves a variable out of the archive"),
+    (Byte::Double([0xBB, 0x6A]), "", &[], "Asm(program)", "Runs an assembly program"),
+    (Byte::Double([0xBB, 0x6B]), "", &[], "AsmComp(source,destination)", "Compiles an assembly program written in hexadecimal"),
+    (Byte::Double([0xBB, 0x6C]), "", &[], "", "Marks the start of an assembly program written in hexadecimal"),
+    (Byte::Double([0xBB, 0xF4]), "", &[], "", "The square root symbol, for use in strings"),
+    // EF tokens
+    (Byte::Double([0xEF, 0x00]), "", &[], "setDate(year,month,day)", "Sets the date of the clock"),
+    (Byte::Double([0xEF, 0x01]), "", &[], "setTime(hour,minute,second)", "Sets the time of the clock"),
+    (Byte::Double([0xEF, 0x02]), "", &[], "checkTmr(start)", "The number of seconds since a timer was started"),
+    (Byte::Double([0xEF, 0x06]), "", &[], "dayOfWk(year,month,day)", "The day of the week of a date, where 1 is Sunday"),
+    (Byte::Double([0xEF, 0x09]), "", &[], "", "The current date as the list {year,month,day}"),
+    (Byte::Double([0xEF, 0x0A]), "", &[], "", "The current time as the list {hour,minute,second}"),
+    (Byte::Double([0xEF, 0x0B]), "", &[], "", "The current value of the clock, for use with checkTmr("),
+    (Byte::Double([0xEF, 0x1E]), "⬚", &[], "", "The empty box of a MathPrint template"),
+    (Byte::Double([0xEF, 0x32]), "", &[], "remainder(dividend,divisor)", "The remainder of a division"),
+    (Byte::Double([0xEF, 0x33]), "", &[], "Σ(expression,variable,start,end)", "The sum of an expression over a range of values"),
+    (Byte::Double([0xEF, 0x34]), "", &[], "logBASE(value,base)", "The logarithm of a value in any base"),
+    (Byte::Double([0xEF, 0x35]), "", &[], "randIntNoRep(lower,upper[,count])", "A random list of integers between two values without repeats"),
+    (Byte::Double([0xEF, 0x3D]), "", &["FRAC-APPROX"], "", "Sets answers to be shown as fractions, called FRAC-APPROX from OS 4.0"),
+    (Byte::Double([0xEF, 0x5B]), "", &[], "BackgroundOn color", "Sets the background of the graph screen"),
+    (Byte::Double([0xEF, 0x65]), "", &[], "GraphColor(function,color)", "Sets the color that a function is drawn with"),
+    (Byte::Double([0xEF, 0x67]), "", &[], "TextColor(color)", "Sets the color of text drawn with Text("),
+    (Byte::Double([0xEF, 0x68]), "", &[], "", "Marks the start of a TI-84 Plus C Silver Edition assembly program"),
+    (Byte::Double([0xEF, 0x69]), "", &[], "", "Marks the start of a squished TI-84 Plus C Silver Edition assembly program"),
+    (Byte::Double([0xEF, 0x6C]), "", &[], "BorderColor value", "Sets the color of the border around the graph screen"),
+    (Byte::Double([0xEF, 0x73]), "·", &[], "", "The smallest mark for a stat plot"),
+    (Byte::Double([0xEF, 0x7A]), "", &[], "", "Marks the start of a TI-84 Plus CE assembly program"),
+    (Byte::Double([0xEF, 0x7B]), "", &[], "", "Marks the start of a squished TI-84 Plus CE assembly program"),
+    (Byte::Double([0xEF, 0x81]), "Quartiles Setting…", &[], "", "Opens the setting for how quartiles are worked out"),
+    (Byte::Double([0xEF, 0x82]), "u(𝑛-2)", &[], "", "The sequence u two terms before"),
+    (Byte::Double([0xEF, 0x83]), "v(𝑛-2)", &[], "", "The sequence v two terms before"),
+    (Byte::Double([0xEF, 0x84]), "w(𝑛-2)", &[], "", "The sequence w two terms before"),
+    (Byte::Double([0xEF, 0x85]), "u(𝑛-1)", &[], "", "The sequence u one term before"),
+    (Byte::Double([0xEF, 0x86]), "v(𝑛-1)", &[], "", "The sequence v one term before"),
+    (Byte::Double([0xEF, 0x87]), "w(𝑛-1)", &[], "", "The sequence w one term before"),
+    (Byte::Double([0xEF, 0x88]), "u(𝑛)", &[], "", "The current term of the sequence u"),
+    (Byte::Double([0xEF, 0x89]), "v(𝑛)", &[], "", "The current term of the sequence v"),
+    (Byte::Double([0xEF, 0x8A]), "w(𝑛)", &[], "", "The current term of the sequence w"),
+    (Byte::Double([0xEF, 0x8B]), "u(𝑛+1)", &[], "", "The sequence u one term after"),
+    (Byte::Double([0xEF, 0x8C]), "v(𝑛+1)", &[], "", "The sequence v one term after"),
+    (Byte::Double([0xEF, 0x8D]), "w(𝑛+1)", &[], "", "The sequence w one term after"),
+    (Byte::Double([0xEF, 0x8E]), "", &[], "pieceWise(expression,condition,...)", "A function made of pieces, each used where its condition is true"),
+    (Byte::Double([0xEF, 0x8F]), "SEQ(𝑛)", &[], "", "Sets sequences to be written in terms of u(𝑛)"),
+    (Byte::Double([0xEF, 0x90]), "SEQ(𝑛+1)", &[], "", "Sets sequences to be written in terms of u(𝑛+1)"),
+    (Byte::Double([0xEF, 0x91]), "SEQ(𝑛+2)", &[], "", "Sets sequences to be written in terms of u(𝑛+2)"),
+    (Byte::Double([0xEF, 0x95]), "", &[], "invBinom(area,trials,probability)", "The smallest number of successes whose binomial cumulative probability reaches an area"),
+    (Byte::Double([0xEF, 0x96]), "", &[], "Wait seconds", "Pauses the program for a number of seconds"),
+    (Byte::Double([0xEF, 0x97]), "", &[], "toString(value)", "Converts a value to a string"),
+    (Byte::Double([0xEF, 0x98]), "", &[], "eval(expression)", "Evaluates an expression"),
+    (Byte::Double([0xEF, 0xA6]), "", &[], "piecewise(expression,condition,...)", "A function made of pieces, each used where its condition is true"),
+];
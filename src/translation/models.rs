@@ -0,0 +1,222 @@
+//! The `models` module describes the calculator models that TiO2 can target, and which tokens
+//! each of them supports.
+//!
+//! Each token has a minimum [`Model`] that it was introduced on. Models are ordered from oldest to
+//! newest, and a model supports every token whose minimum model is the same or older. The TI-84
+//! Plus and TI-84 Plus Silver Edition are assumed to be running OS 2.55MP, which has the same
+//! tokens on both.
+
+use std::{fmt, str::FromStr};
+
+use super::{
+    asm::{self, Payload},
+    parser::tokenize,
+    tokens::{Byte, BYTE_TOKENS},
+};
+
+/// A calculator model, ordered from oldest to newest.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::models::Model;
+///
+/// let model: Model = "84+CSE".parse().unwrap();
+///
+/// assert_eq!(model, Model::TI84PlusCSE);
+/// assert!(Model::TI83Plus < model);
+/// assert_eq!(model.to_string(), "TI-84 Plus C Silver Edition");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Model {
+    /// The TI-83 Plus and TI-83 Plus Silver Edition
+    TI83Plus,
+    /// The TI-84 Plus
+    TI84Plus,
+    /// The TI-84 Plus Silver Edition
+    TI84PlusSE,
+    /// The TI-84 Plus C Silver Edition, the first model with a color screen
+    TI84PlusCSE,
+    /// The TI-84 Plus CE, which supports every token
+    #[default]
+    TI84PlusCE,
+}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Model::TI83Plus => "TI-83 Plus",
+            Model::TI84Plus => "TI-84 Plus",
+            Model::TI84PlusSE => "TI-84 Plus Silver Edition",
+            Model::TI84PlusCSE => "TI-84 Plus C Silver Edition",
+            Model::TI84PlusCE => "TI-84 Plus CE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Model {
+    type Err = String;
+
+    /// Parses a model name such as `83+`, `TI-84+SE`, `84pcse` or `ce`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s
+            .to_lowercase()
+            .replace(['-', '_', ' '], "")
+            .replace("plus", "+");
+        let normalized = normalized.strip_prefix("ti").unwrap_or(&normalized);
+
+        match normalized {
+            "83+" | "83p" => Ok(Model::TI83Plus),
+            "84+" | "84p" => Ok(Model::TI84Plus),
+            "84+se" | "84pse" => Ok(Model::TI84PlusSE),
+            "84+cse" | "84pcse" | "84+c" => Ok(Model::TI84PlusCSE),
+            "84+ce" | "84pce" | "ce" => Ok(Model::TI84PlusCE),
+            _ => Err(format!(
+                "Unknown calculator model {:?}, expected one of 83+, 84+, 84+SE, 84+CSE or 84+CE",
+                s
+            )),
+        }
+    }
+}
+
+/// Returns the oldest model that supports a token.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{
+///     models::{minimum_model, Model},
+///     tokens::Byte,
+/// };
+///
+/// // Disp
+/// assert_eq!(minimum_model(Byte::Single(0xDE)), Model::TI83Plus);
+/// // setDate(
+/// assert_eq!(minimum_model(Byte::Double([0xEF, 0x00])), Model::TI84Plus);
+/// // BLUE
+/// assert_eq!(minimum_model(Byte::Double([0xEF, 0x41])), Model::TI84PlusCSE);
+/// // Thin
+/// assert_eq!(minimum_model(Byte::Double([0xEF, 0x74])), Model::TI84PlusCSE);
+/// // Asm84CEPrgm
+/// assert_eq!(minimum_model(Byte::Double([0xEF, 0x7A])), Model::TI84PlusCE);
+/// ```
+pub fn minimum_model(byte: Byte) -> Model {
+    match byte {
+        // clock, MathPrint and the other TI-84 Plus additions
+        Byte::Double([0xEF, 0x00..=0x40]) => Model::TI84Plus,
+        // colors, images, Thin and the other TI-84 Plus C Silver Edition additions
+        Byte::Double([0xEF, 0x41..=0x75]) => Model::TI84PlusCSE,
+        // Asm84CEPrgm, Wait, toString( and the other TI-84 Plus CE additions
+        Byte::Double([0xEF, _]) => Model::TI84PlusCE,
+        // TraceStep
+        Byte::Double([0x63, 0x38]) => Model::TI84PlusCE,
+        _ => Model::TI83Plus,
+    }
+}
+
+/// A token in a program that isn't supported by the target model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedToken {
+    /// The line of the program that the token is on, starting at 1
+    pub line: usize,
+    /// The position of the first byte of the token in the data section
+    pub offset: usize,
+    /// The token
+    pub byte: Byte,
+    /// The oldest model that supports the token
+    pub minimum_model: Model,
+}
+
+impl fmt::Display for UnsupportedToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {:?} requires a {}",
+            self.line,
+            BYTE_TOKENS.get(&self.byte).copied().unwrap_or("?"),
+            self.minimum_model
+        )
+    }
+}
+
+/// Finds the tokens in the data section of a program that aren't supported by `model`.
+///
+/// # Arguments
+///
+/// * `data` - The tokens of a program, without the header or the length of the program.
+/// * `model` - The model that the program should run on.
+///
+/// # Returns
+///
+/// Every token that requires a newer model, in the order that they appear in the program.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::models::{unsupported_tokens, Model};
+///
+/// // ClrHome, TextColor(BLUE
+/// let data = [0xE1, 0x3F, 0xEF, 0x67, 0xEF, 0x41];
+///
+/// assert!(unsupported_tokens(&data, Model::TI84PlusCE).is_empty());
+///
+/// let unsupported = unsupported_tokens(&data, Model::TI84Plus);
+/// assert_eq!(unsupported.len(), 2);
+/// assert_eq!(unsupported[0].line, 2);
+/// assert_eq!(unsupported[0].minimum_model, Model::TI84PlusCSE);
+/// ```
+pub fn unsupported_tokens(data: &[u8], model: Model) -> Vec<UnsupportedToken> {
+    let mut line = 1;
+    let mut unsupported = Vec::new();
+
+    for token in tokenize(token_data(data)) {
+        if token.byte == Byte::Single(0x3F) {
+            line += 1;
+            continue;
+        }
+
+        let minimum_model = minimum_model(token.byte);
+        if minimum_model > model {
+            unsupported.push(UnsupportedToken {
+                line,
+                offset: token.offset,
+                byte: token.byte,
+                minimum_model,
+            });
+        }
+    }
+
+    unsupported
+}
+
+/// Returns the oldest model that supports every token in the data section of a program. For
+/// squished assembly programs, this is the model that their magic bytes are for.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::models::{required_model, Model};
+///
+/// // ClockOn
+/// assert_eq!(required_model(&[0xEF, 0x10]), Model::TI84Plus);
+/// assert_eq!(required_model(&[]), Model::TI83Plus);
+/// // a squished TI-83 Plus assembly program, whose machine code isn't tokens
+/// assert_eq!(required_model(&[0xBB, 0x6D, 0xEF, 0x7B, 0xC9]), Model::TI83Plus);
+/// ```
+pub fn required_model(data: &[u8]) -> Model {
+    tokenize(token_data(data))
+        .into_iter()
+        .map(|token| minimum_model(token.byte))
+        .max()
+        .unwrap_or(Model::TI83Plus)
+}
+
+/// Returns the part of a program that is made of tokens, which for a squished assembly program is
+/// only its magic bytes.
+fn token_data(data: &[u8]) -> &[u8] {
+    match asm::detect(data) {
+        Payload::Squished(_) => &data[..2],
+        _ => data,
+    }
+}
@@ -0,0 +1,1147 @@
+//! The `parser` module turns the data section of a TI-BASIC program into a list of [`Statement`]s,
+//! each of which may contain [`Expr`] trees.
+//!
+//! Parsing follows the calculator's rules, including its order of operations, implied
+//! multiplication, and closing parentheses and quotes that are left off at the end of a line.
+//! The primary function that should be used in this module is [`parse`].
+
+use crate::{
+    errors::ParseError,
+    float::TIFloat,
+    translation::tokens::{Byte, BYTE_TOKENS},
+    utils,
+};
+
+/// A single token from the data section of a program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    /// The position of the first byte of the token in the data section
+    pub offset: usize,
+    /// The byte(s) that make up the token
+    pub byte: Byte,
+}
+
+/// A variable that can be read from and stored into.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Variable {
+    /// One of the real variables `A`-`Z` or `θ`, stored as its token byte
+    Real(u8),
+    /// One of the built-in lists `l1`-`l6`, stored as the second byte of its token
+    List(u8),
+    /// A custom list such as `ʟABC`, stored without the leading `ʟ`
+    CustomList(String),
+    /// One of the matrices `[A]`-`[J]`, stored as the second byte of its token
+    Matrix(u8),
+    /// One of the string variables `Str0`-`Str9`, stored as the second byte of its token
+    Str(u8),
+    /// One of the equation variables such as `y1` or `r_1`, stored as the second byte of its
+    /// token
+    Equation(u8),
+    /// A system variable, such as a window setting or a statistics result
+    System(Byte),
+}
+
+/// An operator that comes before its operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    /// Negation (`-`, 0xB0)
+    Negate,
+}
+
+/// An operator that comes after its operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostfixOp {
+    /// `^-1`
+    Reciprocal,
+    /// `^2`
+    Square,
+    /// `^3`
+    Cube,
+    /// `!`
+    Factorial,
+    /// `°`
+    Degree,
+    /// `^T`
+    Transpose,
+    /// `>DMS`
+    ToDMS,
+    /// `>Dec`
+    ToDec,
+    /// `>Frac`
+    ToFrac,
+}
+
+/// An operator that goes between two operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    /// `+`
+    Add,
+    /// `–` (0x71)
+    Subtract,
+    /// `*`
+    Multiply,
+    /// Two operands written next to each other, such as `2A`
+    ImpliedMultiply,
+    /// `/`
+    Divide,
+    /// `^`
+    Power,
+    /// `[xth root]`
+    XRoot,
+    /// ` nPr `
+    Permutations,
+    /// ` nCr `
+    Combinations,
+    /// `=`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<`
+    Less,
+    /// `>`
+    Greater,
+    /// `<=`
+    LessEqual,
+    /// `>=`
+    GreaterEqual,
+    /// ` and `
+    And,
+    /// ` or `
+    Or,
+    /// ` xor `
+    Xor,
+}
+
+/// An expression that produces a value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// A number literal, such as `12` or `1.5E3`
+    Number(TIFloat),
+    /// A string literal
+    Str(String),
+    /// A variable
+    Variable(Variable),
+    /// An element of a list or matrix, or an equation evaluated at a value, such as `l1(2)`
+    Index { variable: Variable, args: Vec<Expr> },
+    /// `Ans`
+    Ans,
+    /// A token that is a value on its own, such as `π`, `rand`, `getKey` or `Pic1`
+    Token(Byte),
+    /// A list literal, such as `{1,2,3}`
+    List(Vec<Expr>),
+    /// A matrix literal, such as `[[1,2][3,4]]`
+    Matrix(Vec<Vec<Expr>>),
+    /// A function call, such as `int(X)`
+    Call { function: Byte, args: Vec<Expr> },
+    /// An operator before its operand
+    Unary { op: UnaryOp, operand: Box<Expr> },
+    /// An operator after its operand
+    Postfix { op: PostfixOp, operand: Box<Expr> },
+    /// An operator between two operands
+    Binary {
+        op: BinaryOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+}
+
+/// The kind of a [`Statement`].
+///
+/// Blocks are not nested in the tree. Just like on the calculator, `Then`, `Else` and `End` are
+/// statements of their own, so that `Goto` can jump into or out of the middle of a block.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatementKind {
+    /// An expression on its own, which updates `Ans`
+    Expr(Expr),
+    /// `value->target`
+    Store { value: Expr, target: Expr },
+    /// `If condition`
+    If(Expr),
+    /// `Then`
+    Then,
+    /// `Else`
+    Else,
+    /// `End`
+    End,
+    /// `While condition`
+    While(Expr),
+    /// `Repeat condition`
+    Repeat(Expr),
+    /// `For(variable,start,end,step)`
+    For {
+        variable: Variable,
+        start: Expr,
+        end: Expr,
+        step: Option<Expr>,
+    },
+    /// `Lbl name`
+    Lbl(String),
+    /// `Goto name`
+    Goto(String),
+    /// `Stop`
+    Stop,
+    /// `Return`
+    Return,
+    /// `Disp` with any number of values
+    Disp(Vec<Expr>),
+    /// `Output(row,column,value)`
+    Output {
+        row: Expr,
+        column: Expr,
+        value: Expr,
+    },
+    /// `ClrHome`
+    ClrHome,
+    /// `Input`, optionally with a prompt and a variable to store into
+    Input {
+        prompt: Option<Expr>,
+        variable: Option<Expr>,
+    },
+    /// `Prompt` with one or more variables
+    Prompt(Vec<Expr>),
+    /// `Pause`, optionally with a value to display
+    Pause(Option<Expr>),
+    /// `Menu(title,option,label,...)`
+    Menu {
+        title: Expr,
+        options: Vec<(Expr, String)>,
+    },
+    /// `prgmNAME`
+    Program(String),
+    /// Any other command, such as `ClrDraw` or `Line(`, and its arguments
+    Command { command: Byte, args: Vec<Expr> },
+}
+
+/// A single statement, which ends at a `:` or a newline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    /// The byte offset in the data section where the statement starts
+    pub offset: usize,
+    /// What the statement does
+    pub kind: StatementKind,
+}
+
+/// Splits a data section into [`Token`]s.
+///
+/// Bytes that begin a two-byte token are grouped together with the byte that follows them.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{parser::tokenize, tokens::Byte};
+///
+/// // Disp Str1
+/// let tokens = tokenize(&[0xDE, 0xAA, 0x00]);
+///
+/// assert_eq!(tokens[0].byte, Byte::Single(0xDE));
+/// assert_eq!(tokens[1].byte, Byte::Double([0xAA, 0x00]));
+/// assert_eq!(tokens[1].offset, 1);
+/// ```
+pub fn tokenize(data: &[u8]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+
+    while offset < data.len() {
+        let curr_byte = data[offset];
+        let byte = match data.get(offset + 1) {
+            Some(&next) if utils::DOUBLE_BYTE_TOKEN_IDENT.contains(&curr_byte) => {
+                Byte::Double([curr_byte, next])
+            }
+            _ => Byte::Single(curr_byte),
+        };
+
+        tokens.push(Token { offset, byte });
+        offset += match byte {
+            Byte::Single(_) => 1,
+            Byte::Double(_) => 2,
+        };
+    }
+
+    tokens
+}
+
+/// Parses the data section of a program into a list of [`Statement`]s.
+///
+/// Empty statements (blank lines and repeated `:`) are skipped.
+///
+/// # Arguments
+///
+/// * `data` - The data section of a TI-8XP file.
+///
+/// # Returns
+///
+/// A [`Result`] containing the statements, or a [`ParseError`] pointing at the first token that
+/// could not be parsed.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::parser::{parse, BinaryOp, Expr, StatementKind, Variable};
+///
+/// // 2A->B
+/// let statements = parse(&[0x32, 0x41, 0x04, 0x42]).unwrap();
+///
+/// assert_eq!(
+///     statements[0].kind,
+///     StatementKind::Store {
+///         value: Expr::Binary {
+///             op: BinaryOp::ImpliedMultiply,
+///             left: Box::new(Expr::Number(2.into())),
+///             right: Box::new(Expr::Variable(Variable::Real(0x41))),
+///         },
+///         target: Expr::Variable(Variable::Real(0x42)),
+///     }
+/// );
+/// ```
+pub fn parse(data: &[u8]) -> Result<Vec<Statement>, ParseError> {
+    let mut parser = Parser::new(data);
+    let mut statements = Vec::new();
+
+    while parser.peek().is_some() {
+        if parser.consume_separator() {
+            continue;
+        }
+
+        statements.push(parser.parse_statement()?);
+    }
+
+    Ok(statements)
+}
+
+/// Parses a single expression, such as an answer typed in response to `Input`.
+///
+/// # Returns
+///
+/// A [`Result`] containing the expression, or a [`ParseError`] if the data isn't exactly one
+/// expression.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::parser::{parse_expression, Expr, UnaryOp};
+///
+/// // -5
+/// let expr = parse_expression(&[0xB0, 0x35]).unwrap();
+///
+/// assert_eq!(
+///     expr,
+///     Expr::Unary {
+///         op: UnaryOp::Negate,
+///         operand: Box::new(Expr::Number(5.into())),
+///     }
+/// );
+/// ```
+pub fn parse_expression(data: &[u8]) -> Result<Expr, ParseError> {
+    let mut parser = Parser::new(data);
+    let expr = parser.parse_expr()?;
+
+    if parser.peek().is_some() {
+        return Err(parser.error("Unexpected token after expression"));
+    }
+
+    Ok(expr)
+}
+
+/// Checks if a token is a command that is only valid at the start of a statement, such as
+/// `ClrDraw` or `Fix `.
+pub fn is_command(byte: Byte) -> bool {
+    let explicit = match byte {
+        Byte::Single(b) => matches!(
+            b,
+            0x2E | 0x2F
+                | 0x64..=0x69
+                | 0x73..=0x7D
+                | 0x84..=0x93
+                | 0x96..=0xA9
+                | 0xCE..=0xEA
+                | 0xEC..=0xEE
+                | 0xF2..=0xFB
+                | 0xFF
+        ),
+        Byte::Double([0x7E, _]) => true,
+        Byte::Double([0xBB, b]) => matches!(b, 0x4A..=0x58 | 0x64..=0x6D),
+        Byte::Double([0xEF, b]) => matches!(
+            b,
+            0x00 | 0x01
+                | 0x03
+                | 0x04
+                | 0x0F..=0x12
+                | 0x37..=0x40
+                | 0x5B
+                | 0x64
+                | 0x65
+                | 0x67
+                | 0x6A..=0x6C
+                | 0x75
+                | 0x96
+        ),
+        _ => false,
+    };
+
+    // commands that take arguments without parentheses end with a space, unlike operators
+    // such as " or " which start with one
+    explicit
+        || BYTE_TOKENS
+            .get(&byte)
+            .is_some_and(|s| s.ends_with(' ') && !s.starts_with(' '))
+}
+
+/// Checks if a token is a function that takes its arguments in parentheses, such as `int(`.
+pub fn is_function(byte: Byte) -> bool {
+    byte != Byte::Single(0x10)
+        && !is_command(byte)
+        && BYTE_TOKENS.get(&byte).is_some_and(|s| s.ends_with('('))
+}
+
+/// Checks if a byte is one of the real variables `A`-`Z` or `θ`.
+pub fn is_real_variable(byte: u8) -> bool {
+    (0x41..=0x5B).contains(&byte)
+}
+
+/// Checks if a token can end an operand, which means that a `-` after it is subtraction rather
+/// than negation.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{parser::ends_operand, tokens::Byte};
+///
+/// // 1, ) and A
+/// assert!(ends_operand(Byte::Single(0x31)));
+/// assert!(ends_operand(Byte::Single(0x11)));
+/// assert!(ends_operand(Byte::Single(0x41)));
+/// // +, ( and ᴇ
+/// assert!(!ends_operand(Byte::Single(0x70)));
+/// assert!(!ends_operand(Byte::Single(0x10)));
+/// assert!(!ends_operand(Byte::Single(0x3B)));
+/// ```
+pub fn ends_operand(byte: Byte) -> bool {
+    match byte {
+        // numbers, ), ], }, the closing quote of a string and Ans
+        Byte::Single(0x30..=0x3A | 0x11 | 0x07 | 0x09 | 0x2A | 0x72) => true,
+        // °, ⁻¹, ², ᵀ, ³ and !
+        Byte::Single(0x0B..=0x0F | 0x2D) => true,
+        // π, rand, getKey and i
+        Byte::Single(0xAC | 0xAB | 0xAD | 0x2C) => true,
+        Byte::Single(v) => is_real_variable(v),
+        Byte::Double([0x5C | 0x5D | 0x5E | 0x62 | 0x63 | 0xAA, _]) => true,
+        // e
+        Byte::Double([0xBB, 0x31]) => true,
+        // getDate, getTime, startTmr and the other clock values, colors and images
+        Byte::Double([0xEF, 0x09..=0x0E | 0x41..=0x59]) => true,
+        _ => false,
+    }
+}
+
+/// Reads through a list of [`Token`]s, building statements and expressions.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// The length of the data section, used as the offset of errors at the end of the program
+    data_len: usize,
+}
+
+impl Parser {
+    fn new(data: &[u8]) -> Self {
+        Self {
+            tokens: tokenize(data),
+            position: 0,
+            data_len: data.len(),
+        }
+    }
+
+    /// Creates a [`ParseError`] at the current token.
+    fn error(&self, message: impl ToString) -> ParseError {
+        let offset = self
+            .tokens
+            .get(self.position)
+            .map_or(self.data_len, |t| t.offset);
+        ParseError::new(offset, message)
+    }
+
+    /// Returns the token at the current position without consuming it.
+    fn peek(&self) -> Option<Byte> {
+        self.tokens.get(self.position).map(|t| t.byte)
+    }
+
+    /// Consumes the current token if it matches `byte`, returning whether it did.
+    fn consume(&mut self, byte: Byte) -> bool {
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes the current token, or returns an error if it doesn't match `byte`.
+    fn expect(&mut self, byte: Byte) -> Result<(), ParseError> {
+        if self.consume(byte) {
+            Ok(())
+        } else {
+            Err(self.error(format!(
+                "Expected `{}`",
+                BYTE_TOKENS.get(&byte).copied().unwrap_or("?")
+            )))
+        }
+    }
+
+    /// Checks if the current token ends a statement (`:`, a newline, or the end of the program).
+    fn at_end_of_statement(&self) -> bool {
+        matches!(self.peek(), None | Some(Byte::Single(0x3E | 0x3F)))
+    }
+
+    /// Consumes a `:` or a newline, returning whether there was one.
+    fn consume_separator(&mut self) -> bool {
+        self.consume(Byte::Single(0x3E)) || self.consume(Byte::Single(0x3F))
+    }
+
+    /// Checks if the current token closes every open parenthesis, quote and brace, which happens
+    /// at the end of a statement and at `->`.
+    fn at_implicit_close(&self) -> bool {
+        self.at_end_of_statement() || self.peek() == Some(Byte::Single(0x04))
+    }
+
+    /// Consumes a closing token such as `)`, which can be left off at the end of a statement.
+    fn close(&mut self, byte: Byte) -> Result<(), ParseError> {
+        if self.consume(byte) || self.at_implicit_close() {
+            Ok(())
+        } else {
+            self.expect(byte)
+        }
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement, ParseError> {
+        let offset = self.tokens[self.position].offset;
+        let token = self.tokens[self.position].byte;
+
+        // statements that are made up of just their token
+        let simple = match token {
+            Byte::Single(0xCF) => Some(StatementKind::Then),
+            Byte::Single(0xD0) => Some(StatementKind::Else),
+            Byte::Single(0xD4) => Some(StatementKind::End),
+            Byte::Single(0xD5) => Some(StatementKind::Return),
+            Byte::Single(0xD9) => Some(StatementKind::Stop),
+            Byte::Single(0xE1) => Some(StatementKind::ClrHome),
+            _ => None,
+        };
+
+        let kind = if let Some(kind) = simple {
+            self.position += 1;
+            kind
+        } else {
+            match token {
+                Byte::Single(0xCE) => {
+                    self.position += 1;
+                    StatementKind::If(self.parse_expr()?)
+                }
+                Byte::Single(0xD1) => {
+                    self.position += 1;
+                    StatementKind::While(self.parse_expr()?)
+                }
+                Byte::Single(0xD2) => {
+                    self.position += 1;
+                    StatementKind::Repeat(self.parse_expr()?)
+                }
+                Byte::Single(0xD3) => self.parse_for()?,
+                Byte::Single(0xD6) => {
+                    self.position += 1;
+                    StatementKind::Lbl(self.parse_label_name()?)
+                }
+                Byte::Single(0xD7) => {
+                    self.position += 1;
+                    StatementKind::Goto(self.parse_label_name()?)
+                }
+                Byte::Single(0xD8) => {
+                    self.position += 1;
+                    if self.at_end_of_statement() {
+                        StatementKind::Pause(None)
+                    } else {
+                        StatementKind::Pause(Some(self.parse_expr()?))
+                    }
+                }
+                Byte::Single(0xDC) => self.parse_input()?,
+                Byte::Single(0xDD) => {
+                    self.position += 1;
+                    let mut variables = vec![self.parse_store_target()?];
+                    while self.consume(Byte::Single(0x2B)) {
+                        variables.push(self.parse_store_target()?);
+                    }
+                    StatementKind::Prompt(variables)
+                }
+                Byte::Single(0xDE) => {
+                    self.position += 1;
+                    StatementKind::Disp(self.parse_arguments()?)
+                }
+                Byte::Single(0xE0) => {
+                    self.position += 1;
+                    let mut args = self.parse_arguments()?.into_iter();
+                    self.close(Byte::Single(0x11))?;
+                    match (args.next(), args.next(), args.next(), args.next()) {
+                        (Some(row), Some(column), Some(value), None) => {
+                            StatementKind::Output { row, column, value }
+                        }
+                        _ => return Err(ParseError::new(offset, "Output( takes 3 arguments")),
+                    }
+                }
+                Byte::Single(0xE6) => self.parse_menu()?,
+                Byte::Single(0x5F) => {
+                    self.position += 1;
+                    let mut name = String::new();
+                    while !self.at_end_of_statement() {
+                        let byte = self.tokens[self.position].byte;
+                        name.push_str(BYTE_TOKENS.get(&byte).copied().unwrap_or("?"));
+                        self.position += 1;
+                    }
+                    StatementKind::Program(name)
+                }
+                command if is_command(command) => {
+                    self.position += 1;
+                    let args = self.parse_arguments()?;
+                    if BYTE_TOKENS.get(&command).is_some_and(|s| s.ends_with('(')) {
+                        self.close(Byte::Single(0x11))?;
+                    }
+                    StatementKind::Command { command, args }
+                }
+                _ => {
+                    let value = self.parse_expr()?;
+                    if self.consume(Byte::Single(0x04)) {
+                        let target = self.parse_store_target()?;
+                        StatementKind::Store { value, target }
+                    } else {
+                        StatementKind::Expr(value)
+                    }
+                }
+            }
+        };
+
+        if !self.at_end_of_statement() {
+            return Err(self.error("Unexpected token"));
+        }
+        self.consume_separator();
+
+        Ok(Statement { offset, kind })
+    }
+
+    /// Parses comma separated expressions until the end of the statement or a `)`.
+    fn parse_arguments(&mut self) -> Result<Vec<Expr>, ParseError> {
+        let mut args = Vec::new();
+        if self.at_end_of_statement() || self.peek() == Some(Byte::Single(0x11)) {
+            return Ok(args);
+        }
+
+        args.push(self.parse_expr()?);
+        while self.consume(Byte::Single(0x2B)) {
+            args.push(self.parse_expr()?);
+        }
+
+        Ok(args)
+    }
+
+    fn parse_for(&mut self) -> Result<StatementKind, ParseError> {
+        self.position += 1;
+
+        let variable = match self.peek() {
+            Some(Byte::Single(v)) if is_real_variable(v) => Variable::Real(v),
+            _ => return Err(self.error("For( requires a real variable")),
+        };
+        self.position += 1;
+
+        self.expect(Byte::Single(0x2B))?;
+        let start = self.parse_expr()?;
+        self.expect(Byte::Single(0x2B))?;
+        let end = self.parse_expr()?;
+        let step = if self.consume(Byte::Single(0x2B)) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+        self.close(Byte::Single(0x11))?;
+
+        Ok(StatementKind::For {
+            variable,
+            start,
+            end,
+            step,
+        })
+    }
+
+    fn parse_input(&mut self) -> Result<StatementKind, ParseError> {
+        self.position += 1;
+
+        if self.at_end_of_statement() {
+            return Ok(StatementKind::Input {
+                prompt: None,
+                variable: None,
+            });
+        }
+
+        let first = self.parse_expr()?;
+        if self.consume(Byte::Single(0x2B)) {
+            Ok(StatementKind::Input {
+                prompt: Some(first),
+                variable: Some(self.parse_store_target()?),
+            })
+        } else {
+            if !is_store_target(&first) {
+                return Err(self.error("Input requires a variable"));
+            }
+            Ok(StatementKind::Input {
+                prompt: None,
+                variable: Some(first),
+            })
+        }
+    }
+
+    fn parse_menu(&mut self) -> Result<StatementKind, ParseError> {
+        self.position += 1;
+
+        let title = self.parse_expr()?;
+        let mut options = Vec::new();
+        while self.consume(Byte::Single(0x2B)) {
+            let text = self.parse_expr()?;
+            self.expect(Byte::Single(0x2B))?;
+            let label = self.parse_label_name()?;
+            options.push((text, label));
+        }
+        self.close(Byte::Single(0x11))?;
+
+        Ok(StatementKind::Menu { title, options })
+    }
+
+    /// Parses the one or two character name of a label.
+    fn parse_label_name(&mut self) -> Result<String, ParseError> {
+        let mut name = String::new();
+
+        while let Some(Byte::Single(byte)) = self.peek() {
+            if name.chars().count() == 2 || !utils::ALPHANUMERIC_RANGE.contains(&byte) {
+                break;
+            }
+            name.push_str(BYTE_TOKENS.get(&Byte::Single(byte)).copied().unwrap_or("?"));
+            self.position += 1;
+        }
+
+        if name.is_empty() {
+            return Err(self.error("Expected a label name"));
+        }
+
+        Ok(name)
+    }
+
+    /// Parses something that a value can be stored into, such as `A`, `l1(3)` or `dim(l1)`.
+    fn parse_store_target(&mut self) -> Result<Expr, ParseError> {
+        let target = self.parse_primary()?;
+
+        if !is_store_target(&target) {
+            return Err(self.error("Expected a variable"));
+        }
+
+        Ok(target)
+    }
+
+    /// Parses a full expression.
+    ///
+    /// The order of operations follows the calculator, from lowest to highest precedence:
+    /// conversions such as `>Frac`, `or`/`xor`, `and`, relational operators, `+`/`–`,
+    /// `*`/`/`/implied multiplication, `nPr`/`nCr`, negation, `^`/`[xth root]`, and finally
+    /// postfix operators such as `^2` and `!`.
+    fn parse_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_or()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Byte::Single(0x01)) => PostfixOp::ToDMS,
+                Some(Byte::Single(0x02)) => PostfixOp::ToDec,
+                Some(Byte::Single(0x03)) => PostfixOp::ToFrac,
+                _ => return Ok(expr),
+            };
+            self.position += 1;
+            expr = Expr::Postfix {
+                op,
+                operand: Box::new(expr),
+            };
+        }
+    }
+
+    /// Parses a left associative chain of binary operators.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator` - Returns the operator for a token, if it is one at this level.
+    /// * `operand` - Parses an operand at the next level of precedence.
+    fn parse_binary(
+        &mut self,
+        operator: fn(Byte) -> Option<BinaryOp>,
+        operand: fn(&mut Self) -> Result<Expr, ParseError>,
+    ) -> Result<Expr, ParseError> {
+        let mut left = operand(self)?;
+
+        while let Some(op) = self.peek().and_then(operator) {
+            self.position += 1;
+            let right = operand(self)?;
+            left = Expr::Binary {
+                op,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ParseError> {
+        self.parse_binary(
+            |byte| match byte {
+                Byte::Single(0x3C) => Some(BinaryOp::Or),
+                Byte::Single(0x3D) => Some(BinaryOp::Xor),
+                _ => None,
+            },
+            Self::parse_and,
+        )
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        self.parse_binary(
+            |byte| match byte {
+                Byte::Single(0x40) => Some(BinaryOp::And),
+                _ => None,
+            },
+            Self::parse_relational,
+        )
+    }
+
+    fn parse_relational(&mut self) -> Result<Expr, ParseError> {
+        self.parse_binary(
+            |byte| match byte {
+                Byte::Single(0x6A) => Some(BinaryOp::Equal),
+                Byte::Single(0x6B) => Some(BinaryOp::Less),
+                Byte::Single(0x6C) => Some(BinaryOp::Greater),
+                Byte::Single(0x6D) => Some(BinaryOp::LessEqual),
+                Byte::Single(0x6E) => Some(BinaryOp::GreaterEqual),
+                Byte::Single(0x6F) => Some(BinaryOp::NotEqual),
+                _ => None,
+            },
+            Self::parse_additive,
+        )
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr, ParseError> {
+        self.parse_binary(
+            |byte| match byte {
+                Byte::Single(0x70) => Some(BinaryOp::Add),
+                Byte::Single(0x71) => Some(BinaryOp::Subtract),
+                _ => None,
+            },
+            Self::parse_multiplicative,
+        )
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.parse_permutation()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Byte::Single(0x82)) => {
+                    self.position += 1;
+                    BinaryOp::Multiply
+                }
+                Some(Byte::Single(0x83)) => {
+                    self.position += 1;
+                    BinaryOp::Divide
+                }
+                // implied multiplication, such as 2A or 3(4)
+                Some(byte) if starts_operand(byte) => BinaryOp::ImpliedMultiply,
+                _ => return Ok(left),
+            };
+
+            let right = self.parse_permutation()?;
+            left = Expr::Binary {
+                op,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+    }
+
+    fn parse_permutation(&mut self) -> Result<Expr, ParseError> {
+        self.parse_binary(
+            |byte| match byte {
+                Byte::Single(0x94) => Some(BinaryOp::Permutations),
+                Byte::Single(0x95) => Some(BinaryOp::Combinations),
+                _ => None,
+            },
+            Self::parse_negation,
+        )
+    }
+
+    fn parse_negation(&mut self) -> Result<Expr, ParseError> {
+        if self.consume(Byte::Single(0xB0)) {
+            return Ok(Expr::Unary {
+                op: UnaryOp::Negate,
+                operand: Box::new(self.parse_negation()?),
+            });
+        }
+
+        self.parse_power()
+    }
+
+    fn parse_power(&mut self) -> Result<Expr, ParseError> {
+        self.parse_binary(
+            |byte| match byte {
+                Byte::Single(0xF0) => Some(BinaryOp::Power),
+                Byte::Single(0xF1) => Some(BinaryOp::XRoot),
+                _ => None,
+            },
+            Self::parse_power_operand,
+        )
+    }
+
+    /// Parses an operand of `^`, which may be negated, as in `2^-1`.
+    fn parse_power_operand(&mut self) -> Result<Expr, ParseError> {
+        if self.consume(Byte::Single(0xB0)) {
+            return Ok(Expr::Unary {
+                op: UnaryOp::Negate,
+                operand: Box::new(self.parse_power_operand()?),
+            });
+        }
+
+        self.parse_postfix()
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_primary()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Byte::Single(0x0B)) => PostfixOp::Degree,
+                Some(Byte::Single(0x0C)) => PostfixOp::Reciprocal,
+                Some(Byte::Single(0x0D)) => PostfixOp::Square,
+                Some(Byte::Single(0x0E)) => PostfixOp::Transpose,
+                Some(Byte::Single(0x0F)) => PostfixOp::Cube,
+                Some(Byte::Single(0x2D)) => PostfixOp::Factorial,
+                _ => return Ok(expr),
+            };
+            self.position += 1;
+            expr = Expr::Postfix {
+                op,
+                operand: Box::new(expr),
+            };
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ParseError> {
+        let token = match self.peek() {
+            Some(token) if !self.at_end_of_statement() => token,
+            _ => return Err(self.error("Expected an expression")),
+        };
+
+        match token {
+            Byte::Single(0x30..=0x3B) => self.parse_number(),
+            Byte::Single(0x2A) => self.parse_string(),
+            Byte::Single(0x10) => {
+                self.position += 1;
+                let expr = self.parse_expr()?;
+                self.close(Byte::Single(0x11))?;
+                Ok(expr)
+            }
+            Byte::Single(0x08) => {
+                self.position += 1;
+                let items = self.parse_arguments_until(Byte::Single(0x09))?;
+                Ok(Expr::List(items))
+            }
+            Byte::Single(0x06) => self.parse_matrix(),
+            Byte::Single(0x72) => {
+                self.position += 1;
+                Ok(Expr::Ans)
+            }
+            byte if is_function(byte) => {
+                self.position += 1;
+                let args = self.parse_arguments_until(Byte::Single(0x11))?;
+                Ok(Expr::Call {
+                    function: byte,
+                    args,
+                })
+            }
+            _ => {
+                if let Some(variable) = self.parse_variable()? {
+                    // lists, matrices and equations can be followed by an index or argument
+                    let indexable = matches!(
+                        variable,
+                        Variable::List(_)
+                            | Variable::CustomList(_)
+                            | Variable::Matrix(_)
+                            | Variable::Equation(_)
+                    );
+                    if indexable && self.consume(Byte::Single(0x10)) {
+                        let args = self.parse_arguments_until(Byte::Single(0x11))?;
+                        return Ok(Expr::Index { variable, args });
+                    }
+                    return Ok(Expr::Variable(variable));
+                }
+
+                if is_command(token)
+                    || matches!(token, Byte::Single(0x04 | 0x07 | 0x09 | 0x11 | 0x2B | 0xB0))
+                    || BYTE_TOKENS.get(&token).is_none()
+                {
+                    return Err(self.error("Unexpected token in expression"));
+                }
+
+                self.position += 1;
+                Ok(Expr::Token(token))
+            }
+        }
+    }
+
+    /// Parses comma separated expressions followed by a closing token, which can be left off
+    /// at the end of a statement. The opening token must already be consumed.
+    fn parse_arguments_until(&mut self, close: Byte) -> Result<Vec<Expr>, ParseError> {
+        let mut args = Vec::new();
+
+        if !self.consume(close) {
+            args.push(self.parse_expr()?);
+            while self.consume(Byte::Single(0x2B)) {
+                args.push(self.parse_expr()?);
+            }
+            self.close(close)?;
+        }
+
+        Ok(args)
+    }
+
+    /// Parses a matrix literal such as `[[1,2][3,4]]`.
+    fn parse_matrix(&mut self) -> Result<Expr, ParseError> {
+        self.position += 1;
+        let mut rows = Vec::new();
+
+        while self.consume(Byte::Single(0x06)) {
+            rows.push(self.parse_arguments_until(Byte::Single(0x07))?);
+        }
+
+        if rows.is_empty() {
+            return Err(self.error("Expected `[` to start a matrix row"));
+        }
+        self.close(Byte::Single(0x07))?;
+
+        Ok(Expr::Matrix(rows))
+    }
+
+    /// Parses a variable token, if the current token is one.
+    fn parse_variable(&mut self) -> Result<Option<Variable>, ParseError> {
+        let variable = match self.peek() {
+            Some(Byte::Single(v)) if is_real_variable(v) => Variable::Real(v),
+            Some(Byte::Double([0x5C, v])) => Variable::Matrix(v),
+            Some(Byte::Double([0x5D, v])) => Variable::List(v),
+            Some(Byte::Double([0x5E, v])) => Variable::Equation(v),
+            Some(Byte::Double([0xAA, v])) => Variable::Str(v),
+            Some(byte @ Byte::Double([0x62 | 0x63, _])) => Variable::System(byte),
+            Some(Byte::Single(0xEB)) => {
+                self.position += 1;
+                let mut name = String::new();
+                // custom list names are up to 5 letters, digits or θ, starting with a letter
+                while let Some(Byte::Single(byte)) = self.peek() {
+                    if name.chars().count() == 5
+                        || !utils::ALPHANUMERIC_RANGE.contains(&byte)
+                        || (name.is_empty() && byte.is_ascii_digit())
+                    {
+                        break;
+                    }
+                    name.push(if byte == 0x5B { 'θ' } else { byte as char });
+                    self.position += 1;
+                }
+                if name.is_empty() {
+                    return Err(self.error("Expected a list name"));
+                }
+                return Ok(Some(Variable::CustomList(name)));
+            }
+            _ => return Ok(None),
+        };
+
+        self.position += 1;
+        Ok(Some(variable))
+    }
+
+    /// Parses a number literal, such as `12`, `.5` or `1.2E-3`.
+    fn parse_number(&mut self) -> Result<Expr, ParseError> {
+        let mut text = String::new();
+
+        // digits 0-9 and the decimal point
+        while let Some(Byte::Single(byte @ 0x30..=0x3A)) = self.peek() {
+            text.push(if byte == 0x3A { '.' } else { byte as char });
+            self.position += 1;
+        }
+
+        // the E (0x3B) token can be followed by a negated exponent
+        if self.consume(Byte::Single(0x3B)) {
+            if text.is_empty() {
+                text.push('1');
+            }
+            text.push('E');
+            if self.consume(Byte::Single(0xB0)) {
+                text.push('-');
+            }
+            while let Some(Byte::Single(byte @ 0x30..=0x39)) = self.peek() {
+                text.push(byte as char);
+                self.position += 1;
+            }
+        }
+
+        match text.parse() {
+            Ok(number) => Ok(Expr::Number(number)),
+            Err(e) => Err(self.error(format!("Invalid number `{}`: {}", text, e))),
+        }
+    }
+
+    /// Parses a string literal. The closing quote can be left off at the end of a line or before
+    /// `->`.
+    fn parse_string(&mut self) -> Result<Expr, ParseError> {
+        self.position += 1;
+        let mut text = String::new();
+
+        while let Some(byte) = self.peek() {
+            match byte {
+                Byte::Single(0x2A) => {
+                    self.position += 1;
+                    break;
+                }
+                // a `:` doesn't end a string, but a newline or a store arrow does
+                Byte::Single(0x3F | 0x04) => break,
+                byte => {
+                    text.push_str(BYTE_TOKENS.get(&byte).copied().unwrap_or("?"));
+                    self.position += 1;
+                }
+            }
+        }
+
+        Ok(Expr::Str(text))
+    }
+}
+
+/// Checks if a token can start an operand, which means that it is implicitly multiplied with
+/// whatever comes before it.
+fn starts_operand(byte: Byte) -> bool {
+    match byte {
+        // numbers, (, {, [, Ans and negation
+        Byte::Single(0x30..=0x3B | 0x10 | 0x08 | 0x06 | 0x72 | 0xB0) => true,
+        // π, rand, getKey, i and custom lists
+        Byte::Single(0xAC | 0xAB | 0xAD | 0x2C | 0xEB) => true,
+        Byte::Single(v) if is_real_variable(v) => true,
+        Byte::Double([0x5C | 0x5D | 0x5E | 0x62 | 0x63 | 0xAA, _]) => true,
+        // e
+        Byte::Double([0xBB, 0x31]) => true,
+        _ => is_function(byte),
+    }
+}
+
+/// Checks if an expression is something that a value can be stored into.
+fn is_store_target(expr: &Expr) -> bool {
+    match expr {
+        Expr::Variable(_) | Expr::Index { .. } => true,
+        // seeds the random number generator
+        Expr::Token(Byte::Single(0xAB)) => true,
+        // dim(l1) resizes a list
+        Expr::Call { function, args } => {
+            *function == Byte::Single(0xB5) && matches!(args.as_slice(), [Expr::Variable(_)])
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,1087 @@
+//! The `sheets` module reads and writes token sheets, which describe tokens in the XML formats
+//! used by the community, or in JSON.
+//!
+//! Two XML formats can be read:
+//!
+//! * TokenIDE sheets, where each `<Token byte="$04" string="->">` may contain `<Alt string="→"/>`
+//!   spellings, and two-byte tokens are nested inside the `<Token>` of their first byte.
+//! * TI-Toolkit (tivars) sheets, where each `<token value="$04">` has `<version>`s with a
+//!   `<lang code="en">` containing its `<accessible>` name, `<display>` form and `<variant>`s, and
+//!   two-byte tokens are nested inside a `<two-byte value="$BB">`.
+//!
+//! The JSON format is a list of objects with the `bytes` of the token in hexadecimal, its `name`
+//! and an optional list of `aliases`:
+//!
+//! ```json
+//! [
+//!   { "bytes": "04", "name": "->", "aliases": ["→"] },
+//!   { "bytes": "EF9A", "name": "MyToken(" }
+//! ]
+//! ```
+//!
+//! A sheet can be [`install`]ed before any tokens are used, which adds its tokens to
+//! [`BYTE_TOKENS`] and its aliases to [`TOKEN_TRIE`](super::trie::TOKEN_TRIE), replacing the names
+//! of built-in tokens with the same bytes.
+
+use std::{fmt, fs, path::Path, str::FromStr, sync::OnceLock};
+
+use super::{
+    metadata,
+    tokens::{Byte, BYTE_TOKENS},
+};
+use crate::errors::SheetError;
+
+/// The sheet installed with [`install`], which is fixed once [`BYTE_TOKENS`] is first used.
+static INSTALLED: OnceLock<TokenSheet> = OnceLock::new();
+
+/// A single token in a [`TokenSheet`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenDefinition {
+    /// The bytes of the token
+    pub byte: Byte,
+    /// The name used when compiling and decompiling
+    pub name: String,
+    /// Other spellings that compile to the token
+    pub aliases: Vec<String>,
+}
+
+/// A list of token definitions that can be read from and written to XML or JSON.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{sheets::TokenSheet, tokens::Byte};
+///
+/// let sheet = TokenSheet::from_xml(
+///     r#"<Tokens>
+///         <Token byte="$BB">
+///             <Token byte="$6E" string="Á" />
+///         </Token>
+///     </Tokens>"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(sheet.tokens[0].byte, Byte::Double([0xBB, 0x6E]));
+/// assert_eq!(TokenSheet::from_json(&sheet.to_json()).unwrap(), sheet);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenSheet {
+    /// The tokens in the sheet, in the order that they were defined
+    pub tokens: Vec<TokenDefinition>,
+}
+
+/// The file formats that token sheets can be read from and written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SheetFormat {
+    /// TokenIDE XML when writing, and TokenIDE or TI-Toolkit XML when reading
+    Xml,
+    /// A list of JSON objects, described in the [module documentation](self)
+    Json,
+}
+
+impl SheetFormat {
+    /// Works out the format of a sheet from the extension of its path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::sheets::SheetFormat;
+    ///
+    /// assert_eq!(SheetFormat::from_path("Tokens.XML"), Some(SheetFormat::Xml));
+    /// assert_eq!(SheetFormat::from_path("tokens.txt"), None);
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?;
+        extension.parse().ok()
+    }
+}
+
+impl FromStr for SheetFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "xml" => Ok(SheetFormat::Xml),
+            "json" => Ok(SheetFormat::Json),
+            _ => Err(format!(
+                "Unknown token sheet format {:?}, expected xml or json",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SheetFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SheetFormat::Xml => write!(f, "xml"),
+            SheetFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl TokenSheet {
+    /// Creates a sheet containing every token in [`BYTE_TOKENS`], along with the aliases from
+    /// [`metadata`], ordered by their bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::{sheets::TokenSheet, tokens::BYTE_TOKENS};
+    ///
+    /// let sheet = TokenSheet::byte_tokens();
+    ///
+    /// assert_eq!(sheet.tokens.len(), BYTE_TOKENS.len());
+    /// assert_eq!(sheet.tokens[0].name, ">DMS");
+    /// ```
+    pub fn byte_tokens() -> Self {
+        let mut tokens: Vec<TokenDefinition> = BYTE_TOKENS
+            .iter()
+            .map(|(&byte, &name)| TokenDefinition {
+                byte,
+                name: name.to_string(),
+                aliases: metadata::get(byte)
+                    .map(|token| token.aliases.iter().map(|a| a.to_string()).collect())
+                    .unwrap_or_default(),
+            })
+            .collect();
+        tokens.sort_by_key(|token| token.byte.to_bytes());
+
+        Self { tokens }
+    }
+
+    /// Reads a sheet from a file, working out its format from the extension of the path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, has an unknown extension, or isn't a valid
+    /// sheet.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref();
+        let format = SheetFormat::from_path(path).ok_or_else(|| {
+            anyhow::Error::msg(format!(
+                "Can't tell the format of {}, expected a .xml or .json file",
+                path.display()
+            ))
+        })?;
+
+        Ok(Self::parse(&fs::read_to_string(path)?, format)?)
+    }
+
+    /// Reads a sheet in the given format.
+    pub fn parse(text: &str, format: SheetFormat) -> Result<Self, SheetError> {
+        match format {
+            SheetFormat::Xml => Self::from_xml(text),
+            SheetFormat::Json => Self::from_json(text),
+        }
+    }
+
+    /// Writes the sheet in the given format.
+    pub fn export(&self, format: SheetFormat) -> String {
+        match format {
+            SheetFormat::Xml => self.to_xml(),
+            SheetFormat::Json => self.to_json(),
+        }
+    }
+
+    /// Reads a TokenIDE or TI-Toolkit XML sheet.
+    ///
+    /// In TI-Toolkit sheets, the name of a token is its English `<accessible>` name from its
+    /// newest `<version>`, and its `<display>` form and `<variant>`s become aliases.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::{sheets::TokenSheet, tokens::Byte};
+    ///
+    /// let sheet = TokenSheet::from_xml(
+    ///     r#"<?xml version="1.0" encoding="utf-8"?>
+    ///     <tokens>
+    ///         <token value="$04">
+    ///             <version>
+    ///                 <lang code="en">
+    ///                     <display>&#x2192;</display>
+    ///                     <accessible>-&gt;</accessible>
+    ///                 </lang>
+    ///             </version>
+    ///         </token>
+    ///     </tokens>"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(sheet.tokens[0].byte, Byte::Single(0x04));
+    /// assert_eq!(sheet.tokens[0].name, "->");
+    /// assert_eq!(sheet.tokens[0].aliases, vec!["→"]);
+    /// ```
+    pub fn from_xml(text: &str) -> Result<Self, SheetError> {
+        let root = XmlReader::new(text).document()?;
+        let mut tokens = Vec::new();
+
+        match root.name.as_str() {
+            "Tokens" => read_tokenide(&root.children, None, &mut tokens)?,
+            "tokens" => read_ti_toolkit(&root.children, None, &mut tokens)?,
+            name => {
+                return Err(SheetError::new(
+                    root.line,
+                    format!("expected <Tokens> or <tokens>, found <{}>", name),
+                ))
+            }
+        }
+
+        Ok(Self { tokens })
+    }
+
+    /// Reads a JSON sheet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::{sheets::TokenSheet, tokens::Byte};
+    ///
+    /// let sheet =
+    ///     TokenSheet::from_json(r#"[{"bytes": "EF9A", "name": "MyToken(", "aliases": []}]"#)
+    ///         .unwrap();
+    ///
+    /// assert_eq!(sheet.tokens[0].byte, Byte::Double([0xEF, 0x9A]));
+    /// assert!(TokenSheet::from_json(r#"[{"bytes": "EF9A"}]"#).is_err());
+    /// ```
+    pub fn from_json(text: &str) -> Result<Self, SheetError> {
+        let mut reader = JsonReader::new(text);
+        let value = reader.document()?;
+
+        let items = match value.value {
+            Json::Array(items) => items,
+            _ => return Err(SheetError::new(value.line, "expected a list of tokens")),
+        };
+
+        let mut tokens = Vec::new();
+        for item in items {
+            let line = item.line;
+            let fields = match item.value {
+                Json::Object(fields) => fields,
+                _ => return Err(SheetError::new(line, "expected a token object")),
+            };
+            let field = |name: &str| fields.iter().find(|(key, _)| key == name).map(|f| &f.1);
+
+            let bytes = match field("bytes").map(|v| &v.value) {
+                Some(Json::String(bytes)) => parse_bytes(bytes).map_err(|e| e.at(line))?,
+                _ => return Err(SheetError::new(line, "expected \"bytes\" to be a string")),
+            };
+            let name = match field("name").map(|v| &v.value) {
+                Some(Json::String(name)) => name.clone(),
+                _ => return Err(SheetError::new(line, "expected \"name\" to be a string")),
+            };
+            let aliases = match field("aliases").map(|v| &v.value) {
+                None => Vec::new(),
+                Some(Json::Array(aliases)) => aliases
+                    .iter()
+                    .map(|alias| match &alias.value {
+                        Json::String(alias) => Ok(alias.clone()),
+                        _ => Err(SheetError::new(alias.line, "expected an alias string")),
+                    })
+                    .collect::<Result<_, _>>()?,
+                Some(_) => return Err(SheetError::new(line, "expected \"aliases\" to be a list")),
+            };
+
+            tokens.push(TokenDefinition {
+                byte: bytes,
+                name,
+                aliases,
+            });
+        }
+
+        Ok(Self { tokens })
+    }
+
+    /// Writes the sheet as TokenIDE XML, with two-byte tokens nested inside their first byte.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::sheets::TokenSheet;
+    ///
+    /// let sheet = TokenSheet::byte_tokens();
+    /// let xml = sheet.to_xml();
+    ///
+    /// assert!(xml.contains(r#"<Token byte="$04" string="-&gt;">"#));
+    /// assert_eq!(TokenSheet::from_xml(&xml).unwrap(), sheet);
+    /// ```
+    pub fn to_xml(&self) -> String {
+        let mut tokens: Vec<&TokenDefinition> = self.tokens.iter().collect();
+        tokens.sort_by_key(|token| token.byte.to_bytes());
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<Tokens xmlns=\"http://merthsoft.com/Tokens\">\n");
+
+        for first in 0..=0xFF_u8 {
+            let single = tokens.iter().find(|t| t.byte == Byte::Single(first));
+            let doubles: Vec<&&TokenDefinition> = tokens
+                .iter()
+                .filter(|t| matches!(t.byte, Byte::Double([b, _]) if b == first))
+                .collect();
+
+            if single.is_none() && doubles.is_empty() {
+                continue;
+            }
+
+            let mut children = String::new();
+            for double in &doubles {
+                if let Byte::Double([_, second]) = double.byte {
+                    write_xml_token(&mut children, 2, second, Some(double), "");
+                }
+            }
+            write_xml_token(&mut xml, 1, first, single.copied(), &children);
+        }
+
+        xml.push_str("</Tokens>\n");
+        xml
+    }
+
+    /// Writes the sheet as JSON.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::sheets::TokenSheet;
+    ///
+    /// let sheet = TokenSheet::byte_tokens();
+    /// let json = sheet.to_json();
+    ///
+    /// assert!(json.contains(r#"{"bytes": "04", "name": "->", "aliases": ["→"]}"#));
+    /// assert_eq!(TokenSheet::from_json(&json).unwrap(), sheet);
+    /// ```
+    pub fn to_json(&self) -> String {
+        let lines: Vec<String> = self
+            .tokens
+            .iter()
+            .map(|token| {
+                let bytes = token.byte.to_hex();
+                let aliases: Vec<String> = token.aliases.iter().map(|a| json_string(a)).collect();
+
+                format!(
+                    "  {{\"bytes\": \"{}\", \"name\": {}, \"aliases\": [{}]}}",
+                    bytes,
+                    json_string(&token.name),
+                    aliases.join(", ")
+                )
+            })
+            .collect();
+
+        format!("[\n{}\n]\n", lines.join(",\n"))
+    }
+}
+
+/// Installs a sheet, whose tokens are added to [`BYTE_TOKENS`] and whose aliases are added to
+/// [`TOKEN_TRIE`](super::trie::TOKEN_TRIE). Tokens in the sheet replace built-in tokens with the
+/// same bytes.
+///
+/// # Errors
+///
+/// The tokens are fixed the first time that they're used, so this returns an error if a sheet
+/// was already installed, or if [`BYTE_TOKENS`] has already been used.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{
+///     compile::compile_to_bytecode,
+///     decompile::decompile_tokens,
+///     sheets::{install, TokenSheet},
+/// };
+///
+/// let sheet =
+///     TokenSheet::from_json(r#"[{"bytes": "EF9A", "name": "MyToken(", "aliases": ["myTok("]}]"#)
+///         .unwrap();
+/// install(sheet).unwrap();
+///
+/// assert_eq!(compile_to_bytecode(vec!["myTok(1"]).unwrap(), [0xEF, 0x9A, 0x31]);
+/// assert_eq!(decompile_tokens(&[0xEF, 0x9A, 0x31]), vec!["MyToken(1"]);
+///
+/// // the tokens can't change once they've been used
+/// assert!(install(TokenSheet::default()).is_err());
+/// ```
+pub fn install(sheet: TokenSheet) -> Result<(), anyhow::Error> {
+    INSTALLED.set(sheet).map_err(|_| {
+        anyhow::Error::msg("Token sheets must be installed once, before any tokens are used")
+    })
+}
+
+/// Returns the tokens of the installed sheet, or nothing if no sheet was installed. After this is
+/// called, [`install`] always fails.
+pub fn installed() -> &'static [TokenDefinition] {
+    &INSTALLED.get_or_init(TokenSheet::default).tokens
+}
+
+/// Writes a TokenIDE `<Token>` element, which contains `children` if they aren't empty.
+fn write_xml_token(
+    xml: &mut String,
+    depth: usize,
+    byte: u8,
+    token: Option<&TokenDefinition>,
+    children: &str,
+) {
+    let indent = "  ".repeat(depth);
+    xml.push_str(&format!("{}<Token byte=\"${:02X}\"", indent, byte));
+    if let Some(token) = token {
+        xml.push_str(&format!(" string=\"{}\"", xml_escape(&token.name)));
+    }
+
+    let aliases = token.map_or(&[][..], |t| &t.aliases);
+    if aliases.is_empty() && children.is_empty() {
+        xml.push_str(" />\n");
+        return;
+    }
+
+    xml.push_str(">\n");
+    for alias in aliases {
+        xml.push_str(&format!(
+            "{}  <Alt string=\"{}\" />\n",
+            indent,
+            xml_escape(alias)
+        ));
+    }
+    xml.push_str(children);
+    xml.push_str(&format!("{}</Token>\n", indent));
+}
+
+/// Reads the `<Token>` elements of a TokenIDE sheet, where `prefix` is the first byte of the
+/// tokens if they're nested inside another token.
+fn read_tokenide(
+    elements: &[Element],
+    prefix: Option<u8>,
+    tokens: &mut Vec<TokenDefinition>,
+) -> Result<(), SheetError> {
+    for element in elements.iter().filter(|e| e.name == "Token") {
+        let value = element
+            .attribute("byte")
+            .ok_or_else(|| SheetError::new(element.line, "<Token> is missing a byte"))?;
+        let value = parse_byte(value).map_err(|e| e.at(element.line))?;
+
+        if let Some(name) = element.attribute("string").filter(|s| !s.is_empty()) {
+            tokens.push(TokenDefinition {
+                byte: make_byte(prefix, value),
+                name: name.to_string(),
+                aliases: element
+                    .children
+                    .iter()
+                    .filter(|child| child.name == "Alt")
+                    .filter_map(|child| child.attribute("string"))
+                    .map(str::to_string)
+                    .collect(),
+            });
+        }
+
+        if prefix.is_none() {
+            read_tokenide(&element.children, Some(value), tokens)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the `<token>` and `<two-byte>` elements of a TI-Toolkit sheet, where `prefix` is the
+/// first byte of the tokens if they're inside a `<two-byte>`.
+fn read_ti_toolkit(
+    elements: &[Element],
+    prefix: Option<u8>,
+    tokens: &mut Vec<TokenDefinition>,
+) -> Result<(), SheetError> {
+    for element in elements {
+        let value = match element.attribute("value") {
+            Some(value) => parse_byte(value).map_err(|e| e.at(element.line))?,
+            None if element.name == "token" || element.name == "two-byte" => {
+                return Err(SheetError::new(
+                    element.line,
+                    format!("<{}> is missing a value", element.name),
+                ))
+            }
+            None => continue,
+        };
+
+        match element.name.as_str() {
+            "two-byte" if prefix.is_none() => {
+                read_ti_toolkit(&element.children, Some(value), tokens)?
+            }
+            "token" => {
+                // the newest version describes how the token is spelled today
+                let lang = element
+                    .children
+                    .iter()
+                    .rev()
+                    .filter(|child| child.name == "version")
+                    .find_map(|version| {
+                        version
+                            .children
+                            .iter()
+                            .find(|c| c.name == "lang" && c.attribute("code") == Some("en"))
+                    });
+                let lang = match lang {
+                    Some(lang) => lang,
+                    None => continue,
+                };
+
+                let text = |name: &str| -> Vec<String> {
+                    lang.children
+                        .iter()
+                        .filter(|c| c.name == name && !c.text.is_empty())
+                        .map(|c| c.text.clone())
+                        .collect()
+                };
+                let display = text("display");
+                let name = match text("accessible").into_iter().next() {
+                    Some(name) => name,
+                    None => match display.first() {
+                        Some(display) => display.clone(),
+                        None => continue,
+                    },
+                };
+
+                let mut aliases: Vec<String> = Vec::new();
+                for alias in display.into_iter().chain(text("variant")) {
+                    if alias != name && !aliases.contains(&alias) {
+                        aliases.push(alias);
+                    }
+                }
+
+                tokens.push(TokenDefinition {
+                    byte: make_byte(prefix, value),
+                    name,
+                    aliases,
+                });
+            }
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates a [`Byte`] from a byte and the prefix it's nested in, if there is one.
+fn make_byte(prefix: Option<u8>, value: u8) -> Byte {
+    match prefix {
+        Some(prefix) => Byte::Double([prefix, value]),
+        None => Byte::Single(value),
+    }
+}
+
+/// A problem with a value in a sheet, which is given a line with [`ValueError::at`].
+struct ValueError(String);
+
+impl ValueError {
+    fn at(self, line: usize) -> SheetError {
+        SheetError::new(line, self.0)
+    }
+}
+
+/// Parses a single hexadecimal byte such as `$5C` or `0x5C`.
+fn parse_byte(text: &str) -> Result<u8, ValueError> {
+    match parse_bytes(text)? {
+        Byte::Single(value) => Ok(value),
+        Byte::Double(_) => Err(ValueError(format!(
+            "expected a single byte, found {:?}",
+            text
+        ))),
+    }
+}
+
+/// Parses the one or two hexadecimal bytes of a token, such as `04`, `$EF9A` or `0xEF9A`.
+fn parse_bytes(text: &str) -> Result<Byte, ValueError> {
+    let hex = text.trim();
+    let hex = hex
+        .strip_prefix('$')
+        .or_else(|| hex.strip_prefix("0x"))
+        .unwrap_or(hex);
+    // single digits are allowed, as in `$0`
+    let hex = if hex.len() == 1 {
+        format!("0{}", hex)
+    } else {
+        hex.to_string()
+    };
+
+    Byte::from_hex(&hex).ok_or_else(|| {
+        ValueError(format!(
+            "expected one or two hexadecimal bytes, found {:?}",
+            text
+        ))
+    })
+}
+
+/// Escapes text for use in an XML attribute.
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#xA;"),
+            '\r' => escaped.push_str("&#xD;"),
+            '\t' => escaped.push_str("&#x9;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Writes text as a JSON string, including its quotes.
+fn json_string(text: &str) -> String {
+    let mut escaped = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// An XML element, which is all that's needed to read token sheets.
+#[derive(Debug)]
+struct Element {
+    /// The name of the element, without a namespace prefix
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    /// The text directly inside the element, with surrounding whitespace removed
+    text: String,
+    /// The line that the element starts on
+    line: usize,
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A small XML reader that supports elements, attributes, text, comments, CDATA sections and
+/// character references, and skips declarations and doctypes.
+struct XmlReader<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> XmlReader<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn line(&self) -> usize {
+        self.text[..self.position].matches('\n').count() + 1
+    }
+
+    fn error(&self, message: impl ToString) -> SheetError {
+        SheetError::new(self.line(), message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Moves past the next occurrence of `end`.
+    fn skip_past(&mut self, end: &str) -> Result<(), SheetError> {
+        match self.rest().find(end) {
+            Some(index) => {
+                self.position += index + end.len();
+                Ok(())
+            }
+            None => Err(self.error(format!("expected {:?}", end))),
+        }
+    }
+
+    /// Skips whitespace, comments, declarations and doctypes between elements.
+    fn skip_misc(&mut self) -> Result<(), SheetError> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Reads the root element of a document.
+    fn document(&mut self) -> Result<Element, SheetError> {
+        self.skip_misc()?;
+        let root = self.element()?;
+        self.skip_misc()?;
+
+        if !self.rest().is_empty() {
+            return Err(self.error("unexpected content after the root element"));
+        }
+
+        Ok(root)
+    }
+
+    fn name(&mut self) -> Result<&'a str, SheetError> {
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/' | '<'))
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error("expected a name"));
+        }
+
+        self.position += length;
+        Ok(&rest[..length])
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), SheetError> {
+        if !self.rest().starts_with(expected) {
+            return Err(self.error(format!("expected `{}`", expected)));
+        }
+        self.position += expected.len();
+        Ok(())
+    }
+
+    fn element(&mut self) -> Result<Element, SheetError> {
+        let line = self.line();
+        self.expect("<")?;
+        let name = self.name()?;
+        let mut element = Element {
+            name: local_name(name).to_string(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+            text: String::new(),
+            line,
+        };
+
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.position += 2;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.position += 1;
+                break;
+            }
+
+            let key = local_name(self.name()?).to_string();
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+
+            let quote = match self.rest().chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return Err(self.error("expected a quoted attribute value")),
+            };
+            self.position += 1;
+            let length = self
+                .rest()
+                .find(quote)
+                .ok_or_else(|| self.error("unterminated attribute value"))?;
+            let value = decode_entities(&self.rest()[..length]).map_err(|e| self.error(e))?;
+            self.position += length + 1;
+
+            element.attributes.push((key, value));
+        }
+
+        let mut text = String::new();
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(SheetError::new(line, format!("<{}> is never closed", name)));
+            } else if rest.starts_with("</") {
+                self.position += 2;
+                let closing = self.name()?;
+                if closing != name {
+                    return Err(self.error(format!("expected </{}>, found </{}>", name, closing)));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                break;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let length = cdata
+                    .find("]]>")
+                    .ok_or_else(|| self.error("unterminated CDATA section"))?;
+                text.push_str(&cdata[..length]);
+                self.position += "<![CDATA[".len() + length + "]]>".len();
+            } else if rest.starts_with('<') {
+                element.children.push(self.element()?);
+            } else {
+                let length = rest.find('<').unwrap_or(rest.len());
+                text.push_str(&decode_entities(&rest[..length]).map_err(|e| self.error(e))?);
+                self.position += length;
+            }
+        }
+
+        element.text = text.trim().to_string();
+        Ok(element)
+    }
+}
+
+/// Removes the namespace prefix from an XML name.
+fn local_name(name: &str) -> &str {
+    match name.split_once(':') {
+        Some(("xmlns", _)) | None => name,
+        Some((_, local)) => local,
+    }
+}
+
+/// Replaces XML entity and character references with the characters they stand for.
+fn decode_entities(text: &str) -> Result<String, String> {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or_else(|| format!("unterminated reference in {:?}", text))?;
+        let reference = &rest[start + 1..start + end];
+
+        let c = match reference {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = reference.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(decimal) = reference.strip_prefix('#') {
+                    decimal.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| format!("unknown reference &{};", reference))?
+            }
+        };
+
+        decoded.push(c);
+        rest = &rest[start + end + 1..];
+    }
+
+    decoded.push_str(rest);
+    Ok(decoded)
+}
+
+/// A JSON value, along with the line it starts on.
+#[derive(Debug)]
+struct JsonValue {
+    value: Json,
+    line: usize,
+}
+
+#[derive(Debug)]
+enum Json {
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+    /// A number, `true`, `false` or `null`, none of which are used by sheets
+    Other,
+}
+
+/// A small JSON reader.
+struct JsonReader<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> JsonReader<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn line(&self) -> usize {
+        self.text[..self.position].matches('\n').count() + 1
+    }
+
+    fn error(&self, message: impl ToString) -> SheetError {
+        SheetError::new(self.line(), message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn document(&mut self) -> Result<JsonValue, SheetError> {
+        let value = self.value()?;
+        self.skip_whitespace();
+
+        if !self.rest().is_empty() {
+            return Err(self.error("unexpected content after the end of the sheet"));
+        }
+
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<JsonValue, SheetError> {
+        self.skip_whitespace();
+        let line = self.line();
+        let rest = self.rest();
+
+        let value = match rest.chars().next() {
+            Some('{') => {
+                self.position += 1;
+                let mut fields = Vec::new();
+                self.list('}', |reader| {
+                    reader.skip_whitespace();
+                    let key = reader.string()?;
+                    reader.skip_whitespace();
+                    if !reader.rest().starts_with(':') {
+                        return Err(reader.error("expected `:`"));
+                    }
+                    reader.position += 1;
+                    fields.push((key, reader.value()?));
+                    Ok(())
+                })?;
+                Json::Object(fields)
+            }
+            Some('[') => {
+                self.position += 1;
+                let mut items = Vec::new();
+                self.list(']', |reader| {
+                    items.push(reader.value()?);
+                    Ok(())
+                })?;
+                Json::Array(items)
+            }
+            Some('"') => Json::String(self.string()?),
+            _ if rest.starts_with("true") || rest.starts_with("null") => {
+                self.position += 4;
+                Json::Other
+            }
+            _ if rest.starts_with("false") => {
+                self.position += 5;
+                Json::Other
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let length = rest
+                    .find(|c: char| {
+                        !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+                    })
+                    .unwrap_or(rest.len());
+                if rest[..length].parse::<f64>().is_err() {
+                    return Err(self.error(format!("invalid number {:?}", &rest[..length])));
+                }
+                self.position += length;
+                Json::Other
+            }
+            _ => return Err(self.error("expected a value")),
+        };
+
+        Ok(JsonValue { value, line })
+    }
+
+    /// Reads comma separated items with `item` until `end`, after the opening bracket.
+    fn list(
+        &mut self,
+        end: char,
+        mut item: impl FnMut(&mut Self) -> Result<(), SheetError>,
+    ) -> Result<(), SheetError> {
+        self.skip_whitespace();
+        if self.rest().starts_with(end) {
+            self.position += 1;
+            return Ok(());
+        }
+
+        loop {
+            item(self)?;
+            self.skip_whitespace();
+            match self.rest().chars().next() {
+                Some(',') => self.position += 1,
+                Some(c) if c == end => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.error(format!("expected `,` or `{}`", end))),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SheetError> {
+        if !self.rest().starts_with('"') {
+            return Err(self.error("expected a string"));
+        }
+        self.position += 1;
+
+        let mut string = String::new();
+        loop {
+            let mut chars = self.rest().chars();
+            let c = chars
+                .next()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.position += c.len_utf8();
+
+            match c {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escape = chars
+                        .next()
+                        .ok_or_else(|| self.error("unterminated string"))?;
+                    self.position += 1;
+                    match escape {
+                        '"' | '\\' | '/' => string.push(escape),
+                        'b' => string.push('\u{8}'),
+                        'f' => string.push('\u{c}'),
+                        'n' => string.push('\n'),
+                        'r' => string.push('\r'),
+                        't' => string.push('\t'),
+                        'u' => string.push(self.unicode_escape()?),
+                        _ => return Err(self.error(format!("invalid escape `\\{}`", escape))),
+                    }
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    /// Reads the hexadecimal digits of a `\u` escape, including a second escape for characters
+    /// outside the basic multilingual plane.
+    fn unicode_escape(&mut self) -> Result<char, SheetError> {
+        fn read_unit(reader: &mut JsonReader) -> Result<u32, SheetError> {
+            let hex = reader
+                .rest()
+                .get(..4)
+                .ok_or_else(|| reader.error("invalid \\u escape"))?;
+            let unit =
+                u32::from_str_radix(hex, 16).map_err(|_| reader.error("invalid \\u escape"))?;
+            reader.position += 4;
+            Ok(unit)
+        }
+
+        let high = read_unit(self)?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.rest().starts_with("\\u") {
+                return Err(self.error("expected the second half of a surrogate pair"));
+            }
+            self.position += 2;
+            let low = read_unit(self)?;
+            0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF)
+        } else {
+            high
+        };
+
+        char::from_u32(code).ok_or_else(|| self.error("invalid \\u escape"))
+    }
+}
@@ -2,6 +2,8 @@
 //! interacting with TI-8XP files.
 use std::{collections::HashMap, hash::Hash, sync::LazyLock};
 
+use super::sheets;
+
 /// A utility function that returns the inverse mapping of byte tokens.
 /// Used when compiling to bytes instead of decompiling from bytes.
 ///
@@ -47,21 +49,97 @@ pub enum Byte {
     Double([u8; 2]),
 }
 
+impl Byte {
+    /// Returns the bytes that represent the token in a program.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::tokens::Byte;
+    ///
+    /// assert_eq!(Byte::Double([0x60, 0x00]).to_bytes(), vec![0x60, 0x00]);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Byte::Single(val) => vec![*val],
+            Byte::Double(arr) => arr.to_vec(),
+        }
+    }
+
+    /// Returns the bytes of the token in uppercase hexadecimal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::tokens::Byte;
+    ///
+    /// assert_eq!(Byte::Double([0xEF, 0x9A]).to_hex(), "EF9A");
+    /// ```
+    pub fn to_hex(&self) -> String {
+        self.to_bytes()
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect()
+    }
+
+    /// Reads a token from two or four hexadecimal digits, such as `3F` or `EF9A`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::tokens::Byte;
+    ///
+    /// assert_eq!(Byte::from_hex("ef9a"), Some(Byte::Double([0xEF, 0x9A])));
+    /// assert_eq!(Byte::from_hex("3F"), Some(Byte::Single(0x3F)));
+    /// assert_eq!(Byte::from_hex("3"), None);
+    /// ```
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        match hex.len() {
+            2 => Some(Byte::Single(u8::from_str_radix(hex, 16).ok()?)),
+            4 => Some(Byte::Double([
+                u8::from_str_radix(&hex[..2], 16).ok()?,
+                u8::from_str_radix(&hex[2..], 16).ok()?,
+            ])),
+            _ => None,
+        }
+    }
+}
+
 /// Provides a [`HashMap`] of byte tokens where the key is a [`Byte`] and the value is a `&'static str`.
 ///
-/// This hashmap contains mappings for byte tokens used in TI-8XP files.
+/// This hashmap contains mappings for byte tokens used in TI-8XP files, covering the TI-83 Plus,
+/// TI-84 Plus, TI-84 Plus C Silver Edition and TI-84 Plus CE. Tokens that are displayed the same
+/// way on the calculator, such as the lowercase letter `n` and the statistics variable `n`, are
+/// given different names so that every token can be compiled back to the same bytes.
+///
+/// Tokens from a sheet installed with [`sheets::install`] are added, replacing the names of
+/// built-in tokens with the same bytes.
 ///
 /// # Example
 ///
 /// ```
-/// use tio2::translation::tokens::{Byte, BYTE_TOKENS};
+/// use tio2::translation::{
+///     tokens::{Byte, BYTE_TOKENS},
+///     trie::TOKEN_TRIE,
+/// };
 ///
 /// if let Some(token) = BYTE_TOKENS.get(&Byte::Single(0x01)) {
 ///     assert_eq!(token, &">DMS");
 /// }
+///
+/// assert_eq!(BYTE_TOKENS.get(&Byte::Double([0xEF, 0x00])), Some(&"setDate("));
+///
+/// // no two tokens share a name
+/// for (byte, name) in BYTE_TOKENS.iter() {
+///     assert_eq!(TOKEN_TRIE.get(name), Some(*byte), "{:?}", name);
+/// }
 /// ```
 pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(|| {
-    let m: HashMap<Byte, &'static str> = [
+    let mut m: HashMap<Byte, &'static str> = [
         // http://tibasicdev.wikidot.com/tokens
         (Byte::Single(0x01), ">DMS"),
         (Byte::Single(0x02), ">Dec"),
@@ -286,7 +364,7 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Single(0xE8), "Get("),
         (Byte::Single(0xE9), "PlotsOn "),
         (Byte::Single(0xEA), "PlotsOff "),
-        (Byte::Single(0xEB), "l"),
+        (Byte::Single(0xEB), "ʟ"),
         (Byte::Single(0xEC), "Plot1("),
         (Byte::Single(0xED), "Plot2("),
         (Byte::Single(0xEE), "Plot3("),
@@ -306,7 +384,7 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Single(0xFD), "xyLine"),
         (Byte::Single(0xFE), "Scatter"),
         (Byte::Single(0xFF), "LinReg(ax+b) "),
-        // System variables (incomplete: TODO)
+        // Matrices
         (Byte::Double([0x5C, 0x00]), "[A]"),
         (Byte::Double([0x5C, 0x01]), "[B]"),
         (Byte::Double([0x5C, 0x02]), "[C]"),
@@ -317,6 +395,7 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0x5C, 0x07]), "[H]"),
         (Byte::Double([0x5C, 0x08]), "[I]"),
         (Byte::Double([0x5C, 0x09]), "[J]"),
+        // Lists
         (Byte::Double([0x5D, 0x00]), "l1"),
         (Byte::Double([0x5D, 0x01]), "l2"),
         (Byte::Double([0x5D, 0x02]), "l3"),
@@ -326,6 +405,7 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0x5D, 0x06]), "l7"),
         (Byte::Double([0x5D, 0x07]), "l8"),
         (Byte::Double([0x5D, 0x08]), "l0"),
+        // Equation variables (Y1-Y0, parametric, polar and sequence)
         (Byte::Double([0x5E, 0x10]), "y1"),
         (Byte::Double([0x5E, 0x11]), "y2"),
         (Byte::Double([0x5E, 0x12]), "y3"),
@@ -336,12 +416,6 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0x5E, 0x17]), "y8"),
         (Byte::Double([0x5E, 0x18]), "y9"),
         (Byte::Double([0x5E, 0x19]), "y0"),
-        (Byte::Double([0x5E, 0x40]), "r_1"),
-        (Byte::Double([0x5E, 0x41]), "r_2"),
-        (Byte::Double([0x5E, 0x42]), "r_3"),
-        (Byte::Double([0x5E, 0x43]), "r_4"),
-        (Byte::Double([0x5E, 0x44]), "r_5"),
-        (Byte::Double([0x5E, 0x45]), "r_6"),
         (Byte::Double([0x5E, 0x20]), "x1t"),
         (Byte::Double([0x5E, 0x21]), "y1t"),
         (Byte::Double([0x5E, 0x22]), "x2t"),
@@ -354,6 +428,16 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0x5E, 0x29]), "y5t"),
         (Byte::Double([0x5E, 0x2A]), "x6t"),
         (Byte::Double([0x5E, 0x2B]), "y6t"),
+        (Byte::Double([0x5E, 0x40]), "r_1"),
+        (Byte::Double([0x5E, 0x41]), "r_2"),
+        (Byte::Double([0x5E, 0x42]), "r_3"),
+        (Byte::Double([0x5E, 0x43]), "r_4"),
+        (Byte::Double([0x5E, 0x44]), "r_5"),
+        (Byte::Double([0x5E, 0x45]), "r_6"),
+        (Byte::Double([0x5E, 0x80]), "[u]"),
+        (Byte::Double([0x5E, 0x81]), "[v]"),
+        (Byte::Double([0x5E, 0x82]), "[w]"),
+        // Pictures
         (Byte::Double([0x60, 0x00]), "Pic1"),
         (Byte::Double([0x60, 0x01]), "Pic2"),
         (Byte::Double([0x60, 0x02]), "Pic3"),
@@ -364,6 +448,7 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0x60, 0x07]), "Pic8"),
         (Byte::Double([0x60, 0x08]), "Pic9"),
         (Byte::Double([0x60, 0x09]), "Pic0"),
+        // Graph databases
         (Byte::Double([0x61, 0x00]), "GDB1"),
         (Byte::Double([0x61, 0x01]), "GDB2"),
         (Byte::Double([0x61, 0x02]), "GDB3"),
@@ -374,7 +459,9 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0x61, 0x07]), "GDB8"),
         (Byte::Double([0x61, 0x08]), "GDB9"),
         (Byte::Double([0x61, 0x09]), "GDB0"),
+        // Statistics variables
         (Byte::Double([0x62, 0x01]), "RegEq"),
+        (Byte::Double([0x62, 0x02]), "[n]"),
         (Byte::Double([0x62, 0x03]), "[x-bar]"),
         (Byte::Double([0x62, 0x04]), "[Summ x]"),
         (Byte::Double([0x62, 0x05]), "[Summ x^2]"),
@@ -390,15 +477,25 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0x62, 0x0F]), "Sy"),
         (Byte::Double([0x62, 0x10]), "[sigma]y"),
         (Byte::Double([0x62, 0x11]), "[Summ xy]"),
+        (Byte::Double([0x62, 0x12]), "[r]"),
         (Byte::Double([0x62, 0x13]), "Med"),
         (Byte::Double([0x62, 0x14]), "Q1"),
         (Byte::Double([0x62, 0x15]), "Q3"),
-        (Byte::Double([0x62, 0x1B]), "x1"),
-        (Byte::Double([0x62, 0x1C]), "x2"),
-        (Byte::Double([0x62, 0x1D]), "x3"),
-        (Byte::Double([0x62, 0x1E]), "y1"),
-        (Byte::Double([0x62, 0x1F]), "y2"),
-        (Byte::Double([0x62, 0x20]), "y3"),
+        (Byte::Double([0x62, 0x16]), "[a]"),
+        (Byte::Double([0x62, 0x17]), "[b]"),
+        (Byte::Double([0x62, 0x18]), "[c]"),
+        (Byte::Double([0x62, 0x19]), "[d]"),
+        (Byte::Double([0x62, 0x1A]), "[e]"),
+        (Byte::Double([0x62, 0x1B]), "[x1]"),
+        (Byte::Double([0x62, 0x1C]), "[x2]"),
+        (Byte::Double([0x62, 0x1D]), "[x3]"),
+        (Byte::Double([0x62, 0x1E]), "[y1]"),
+        (Byte::Double([0x62, 0x1F]), "[y2]"),
+        (Byte::Double([0x62, 0x20]), "[y3]"),
+        (Byte::Double([0x62, 0x21]), "𝑛"),
+        (Byte::Double([0x62, 0x22]), "[p]"),
+        (Byte::Double([0x62, 0x23]), "[z]"),
+        (Byte::Double([0x62, 0x24]), "[t]"),
         (Byte::Double([0x62, 0x25]), "[chi]2"),
         (Byte::Double([0x62, 0x26]), "[fin]"),
         (Byte::Double([0x62, 0x27]), "df"),
@@ -414,10 +511,16 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0x62, 0x31]), "Sxp"),
         (Byte::Double([0x62, 0x32]), "lower"),
         (Byte::Double([0x62, 0x33]), "upper"),
+        (Byte::Double([0x62, 0x34]), "[s]"),
         (Byte::Double([0x62, 0x35]), "r2"),
         (Byte::Double([0x62, 0x36]), "R2"),
+        (Byte::Double([0x62, 0x37]), "[Factor df]"),
         (Byte::Double([0x62, 0x38]), "SS"),
         (Byte::Double([0x62, 0x39]), "MS"),
+        (Byte::Double([0x62, 0x3A]), "[Error df]"),
+        (Byte::Double([0x62, 0x3B]), "[Error SS]"),
+        (Byte::Double([0x62, 0x3C]), "[Error MS]"),
+        // Window, table and finance variables
         (Byte::Double([0x63, 0x00]), "ZXscl"),
         (Byte::Double([0x63, 0x01]), "ZYscl"),
         (Byte::Double([0x63, 0x02]), "Xscl"),
@@ -426,7 +529,56 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0x63, 0x05]), "V_nStart"),
         (Byte::Double([0x63, 0x06]), "U_(n-1)"),
         (Byte::Double([0x63, 0x07]), "V_(n-1)"),
-        // System variables (TODO)
+        (Byte::Double([0x63, 0x08]), "ZU_nStart"),
+        (Byte::Double([0x63, 0x09]), "ZV_nStart"),
+        (Byte::Double([0x63, 0x0A]), "Xmin"),
+        (Byte::Double([0x63, 0x0B]), "Xmax"),
+        (Byte::Double([0x63, 0x0C]), "Ymin"),
+        (Byte::Double([0x63, 0x0D]), "Ymax"),
+        (Byte::Double([0x63, 0x0E]), "Tmin"),
+        (Byte::Double([0x63, 0x0F]), "Tmax"),
+        (Byte::Double([0x63, 0x10]), "θmin"),
+        (Byte::Double([0x63, 0x11]), "θmax"),
+        (Byte::Double([0x63, 0x12]), "ZXmin"),
+        (Byte::Double([0x63, 0x13]), "ZXmax"),
+        (Byte::Double([0x63, 0x14]), "ZYmin"),
+        (Byte::Double([0x63, 0x15]), "ZYmax"),
+        (Byte::Double([0x63, 0x16]), "Zθmin"),
+        (Byte::Double([0x63, 0x17]), "Zθmax"),
+        (Byte::Double([0x63, 0x18]), "ZTmin"),
+        (Byte::Double([0x63, 0x19]), "ZTmax"),
+        (Byte::Double([0x63, 0x1A]), "TblStart"),
+        (Byte::Double([0x63, 0x1B]), "PlotStart"),
+        (Byte::Double([0x63, 0x1C]), "ZPlotStart"),
+        (Byte::Double([0x63, 0x1D]), "nMax"),
+        (Byte::Double([0x63, 0x1E]), "ZnMax"),
+        (Byte::Double([0x63, 0x1F]), "nMin"),
+        (Byte::Double([0x63, 0x20]), "ZnMin"),
+        (Byte::Double([0x63, 0x21]), "DeltaTbl"),
+        (Byte::Double([0x63, 0x22]), "Tstep"),
+        (Byte::Double([0x63, 0x23]), "θstep"),
+        (Byte::Double([0x63, 0x24]), "ZTstep"),
+        (Byte::Double([0x63, 0x25]), "Zθstep"),
+        (Byte::Double([0x63, 0x26]), "DeltaX"),
+        (Byte::Double([0x63, 0x27]), "DeltaY"),
+        (Byte::Double([0x63, 0x28]), "XFact"),
+        (Byte::Double([0x63, 0x29]), "YFact"),
+        (Byte::Double([0x63, 0x2A]), "TblInput"),
+        (Byte::Double([0x63, 0x2B]), "[N]"),
+        (Byte::Double([0x63, 0x2C]), "[I%]"),
+        (Byte::Double([0x63, 0x2D]), "[PV]"),
+        (Byte::Double([0x63, 0x2E]), "[PMT]"),
+        (Byte::Double([0x63, 0x2F]), "[FV]"),
+        (Byte::Double([0x63, 0x30]), "[P/Y]"),
+        (Byte::Double([0x63, 0x31]), "[C/Y]"),
+        (Byte::Double([0x63, 0x32]), "w(nMin)"),
+        (Byte::Double([0x63, 0x33]), "Zw(nMin)"),
+        (Byte::Double([0x63, 0x34]), "PlotStep"),
+        (Byte::Double([0x63, 0x35]), "ZPlotStep"),
+        (Byte::Double([0x63, 0x36]), "Xres"),
+        (Byte::Double([0x63, 0x37]), "ZXres"),
+        (Byte::Double([0x63, 0x38]), "TraceStep"),
+        // Strings
         (Byte::Double([0xAA, 0x00]), "Str1"),
         (Byte::Double([0xAA, 0x01]), "Str2"),
         (Byte::Double([0xAA, 0x02]), "Str3"),
@@ -437,7 +589,7 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0xAA, 0x07]), "Str8"),
         (Byte::Double([0xAA, 0x08]), "Str9"),
         (Byte::Double([0xAA, 0x09]), "Str0"),
-        // BB tokens (two-byte), Incomplete TODO
+        // BB tokens (two-byte)
         (Byte::Double([0xBB, 0x00]), "npv("),
         (Byte::Double([0xBB, 0x01]), "irr("),
         (Byte::Double([0xBB, 0x02]), "bal("),
@@ -446,7 +598,7 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0xBB, 0x05]), ">Nom("),
         (Byte::Double([0xBB, 0x06]), ">Eff("),
         (Byte::Double([0xBB, 0x07]), "dbd("),
-        (Byte::Double([0xBB, 0x08]), "Icm("),
+        (Byte::Double([0xBB, 0x08]), "lcm("),
         (Byte::Double([0xBB, 0x09]), "gcd("),
         (Byte::Double([0xBB, 0x0A]), "randInt("),
         (Byte::Double([0xBB, 0x0B]), "randBin("),
@@ -454,6 +606,156 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0xBB, 0x0D]), "stdDev("),
         (Byte::Double([0xBB, 0x0E]), "variance("),
         (Byte::Double([0xBB, 0x0F]), "inString("),
+        (Byte::Double([0xBB, 0x10]), "normalcdf("),
+        (Byte::Double([0xBB, 0x11]), "invNorm("),
+        (Byte::Double([0xBB, 0x12]), "tcdf("),
+        (Byte::Double([0xBB, 0x13]), "[chi]2cdf("),
+        (Byte::Double([0xBB, 0x14]), "Fcdf("),
+        (Byte::Double([0xBB, 0x15]), "binompdf("),
+        (Byte::Double([0xBB, 0x16]), "binomcdf("),
+        (Byte::Double([0xBB, 0x17]), "poissonpdf("),
+        (Byte::Double([0xBB, 0x18]), "poissoncdf("),
+        (Byte::Double([0xBB, 0x19]), "geometpdf("),
+        (Byte::Double([0xBB, 0x1A]), "geometcdf("),
+        (Byte::Double([0xBB, 0x1B]), "normalpdf("),
+        (Byte::Double([0xBB, 0x1C]), "tpdf("),
+        (Byte::Double([0xBB, 0x1D]), "[chi]2pdf("),
+        (Byte::Double([0xBB, 0x1E]), "Fpdf("),
+        (Byte::Double([0xBB, 0x1F]), "randNorm("),
+        (Byte::Double([0xBB, 0x20]), "tvm_Pmt"),
+        (Byte::Double([0xBB, 0x21]), "tvm_I%"),
+        (Byte::Double([0xBB, 0x22]), "tvm_PV"),
+        (Byte::Double([0xBB, 0x23]), "tvm_N"),
+        (Byte::Double([0xBB, 0x24]), "tvm_FV"),
+        (Byte::Double([0xBB, 0x25]), "conj("),
+        (Byte::Double([0xBB, 0x26]), "real("),
+        (Byte::Double([0xBB, 0x27]), "imag("),
+        (Byte::Double([0xBB, 0x28]), "angle("),
+        (Byte::Double([0xBB, 0x29]), "cumSum("),
+        (Byte::Double([0xBB, 0x2A]), "expr("),
+        (Byte::Double([0xBB, 0x2B]), "length("),
+        (Byte::Double([0xBB, 0x2C]), "DeltaList("),
+        (Byte::Double([0xBB, 0x2D]), "ref("),
+        (Byte::Double([0xBB, 0x2E]), "rref("),
+        (Byte::Double([0xBB, 0x2F]), ">Rect"),
+        (Byte::Double([0xBB, 0x30]), ">Polar"),
+        (Byte::Double([0xBB, 0x31]), "𝑒"),
+        (Byte::Double([0xBB, 0x32]), "SinReg "),
+        (Byte::Double([0xBB, 0x33]), "Logistic "),
+        (Byte::Double([0xBB, 0x34]), "LinRegTTest "),
+        (Byte::Double([0xBB, 0x35]), "ShadeNorm("),
+        (Byte::Double([0xBB, 0x36]), "Shade_t("),
+        (Byte::Double([0xBB, 0x37]), "Shade[chi]2("),
+        (Byte::Double([0xBB, 0x38]), "ShadeF("),
+        (Byte::Double([0xBB, 0x39]), "Matr>list("),
+        (Byte::Double([0xBB, 0x3A]), "List>matr("),
+        (Byte::Double([0xBB, 0x3B]), "Z-Test("),
+        (Byte::Double([0xBB, 0x3C]), "T-Test "),
+        (Byte::Double([0xBB, 0x3D]), "2-SampZTest("),
+        (Byte::Double([0xBB, 0x3E]), "1-PropZTest("),
+        (Byte::Double([0xBB, 0x3F]), "2-PropZTest("),
+        (Byte::Double([0xBB, 0x40]), "[chi]2-Test("),
+        (Byte::Double([0xBB, 0x41]), "ZInterval "),
+        (Byte::Double([0xBB, 0x42]), "2-SampZInt("),
+        (Byte::Double([0xBB, 0x43]), "1-PropZInt("),
+        (Byte::Double([0xBB, 0x44]), "2-PropZInt("),
+        (Byte::Double([0xBB, 0x45]), "GraphStyle("),
+        (Byte::Double([0xBB, 0x46]), "2-SampTTest "),
+        (Byte::Double([0xBB, 0x47]), "2-SampFTest "),
+        (Byte::Double([0xBB, 0x48]), "TInterval "),
+        (Byte::Double([0xBB, 0x49]), "2-SampTInt "),
+        (Byte::Double([0xBB, 0x4A]), "SetUpEditor "),
+        (Byte::Double([0xBB, 0x4B]), "Pmt_End"),
+        (Byte::Double([0xBB, 0x4C]), "Pmt_Bgn"),
+        (Byte::Double([0xBB, 0x4D]), "Real"),
+        (Byte::Double([0xBB, 0x4E]), "re^θi"),
+        (Byte::Double([0xBB, 0x4F]), "a+bi"),
+        (Byte::Double([0xBB, 0x50]), "ExprOn"),
+        (Byte::Double([0xBB, 0x51]), "ExprOff"),
+        (Byte::Double([0xBB, 0x52]), "ClrAllLists"),
+        (Byte::Double([0xBB, 0x53]), "GetCalc("),
+        (Byte::Double([0xBB, 0x54]), "DelVar "),
+        (Byte::Double([0xBB, 0x55]), "Equ>String("),
+        (Byte::Double([0xBB, 0x56]), "String>Equ("),
+        (Byte::Double([0xBB, 0x57]), "Clear Entries"),
+        (Byte::Double([0xBB, 0x58]), "Select("),
+        (Byte::Double([0xBB, 0x59]), "ANOVA("),
+        (Byte::Double([0xBB, 0x5A]), "ModBoxplot"),
+        (Byte::Double([0xBB, 0x5B]), "NormProbPlot"),
+        (Byte::Double([0xBB, 0x64]), "G-T"),
+        (Byte::Double([0xBB, 0x65]), "ZoomFit"),
+        (Byte::Double([0xBB, 0x66]), "DiagnosticOn"),
+        (Byte::Double([0xBB, 0x67]), "DiagnosticOff"),
+        (Byte::Double([0xBB, 0x68]), "Archive "),
+        (Byte::Double([0xBB, 0x69]), "UnArchive "),
+        (Byte::Double([0xBB, 0x6A]), "Asm("),
+        (Byte::Double([0xBB, 0x6B]), "AsmComp("),
+        (Byte::Double([0xBB, 0x6C]), "AsmPrgm"),
+        (Byte::Double([0xBB, 0x6E]), "Á"),
+        (Byte::Double([0xBB, 0x6F]), "À"),
+        (Byte::Double([0xBB, 0x70]), "Â"),
+        (Byte::Double([0xBB, 0x71]), "Ä"),
+        (Byte::Double([0xBB, 0x72]), "á"),
+        (Byte::Double([0xBB, 0x73]), "à"),
+        (Byte::Double([0xBB, 0x74]), "â"),
+        (Byte::Double([0xBB, 0x75]), "ä"),
+        (Byte::Double([0xBB, 0x76]), "É"),
+        (Byte::Double([0xBB, 0x77]), "È"),
+        (Byte::Double([0xBB, 0x78]), "Ê"),
+        (Byte::Double([0xBB, 0x79]), "Ë"),
+        (Byte::Double([0xBB, 0x7A]), "é"),
+        (Byte::Double([0xBB, 0x7B]), "è"),
+        (Byte::Double([0xBB, 0x7C]), "ê"),
+        (Byte::Double([0xBB, 0x7D]), "ë"),
+        (Byte::Double([0xBB, 0x7E]), "Í"),
+        (Byte::Double([0xBB, 0x7F]), "Ì"),
+        (Byte::Double([0xBB, 0x80]), "Î"),
+        (Byte::Double([0xBB, 0x81]), "Ï"),
+        (Byte::Double([0xBB, 0x82]), "í"),
+        (Byte::Double([0xBB, 0x83]), "ì"),
+        (Byte::Double([0xBB, 0x84]), "î"),
+        (Byte::Double([0xBB, 0x85]), "ï"),
+        (Byte::Double([0xBB, 0x86]), "Ó"),
+        (Byte::Double([0xBB, 0x87]), "Ò"),
+        (Byte::Double([0xBB, 0x88]), "Ô"),
+        (Byte::Double([0xBB, 0x89]), "Ö"),
+        (Byte::Double([0xBB, 0x8A]), "ó"),
+        (Byte::Double([0xBB, 0x8B]), "ò"),
+        (Byte::Double([0xBB, 0x8C]), "ô"),
+        (Byte::Double([0xBB, 0x8D]), "ö"),
+        (Byte::Double([0xBB, 0x8E]), "Ú"),
+        (Byte::Double([0xBB, 0x8F]), "Ù"),
+        (Byte::Double([0xBB, 0x90]), "Û"),
+        (Byte::Double([0xBB, 0x91]), "Ü"),
+        (Byte::Double([0xBB, 0x92]), "ú"),
+        (Byte::Double([0xBB, 0x93]), "ù"),
+        (Byte::Double([0xBB, 0x94]), "û"),
+        (Byte::Double([0xBB, 0x95]), "ü"),
+        (Byte::Double([0xBB, 0x96]), "Ç"),
+        (Byte::Double([0xBB, 0x97]), "ç"),
+        (Byte::Double([0xBB, 0x98]), "Ñ"),
+        (Byte::Double([0xBB, 0x99]), "ñ"),
+        (Byte::Double([0xBB, 0x9A]), "´"),
+        (Byte::Double([0xBB, 0x9B]), "`"),
+        (Byte::Double([0xBB, 0x9C]), "¨"),
+        (Byte::Double([0xBB, 0x9D]), "¿"),
+        (Byte::Double([0xBB, 0x9E]), "¡"),
+        (Byte::Double([0xBB, 0x9F]), "α"),
+        (Byte::Double([0xBB, 0xA0]), "β"),
+        (Byte::Double([0xBB, 0xA1]), "γ"),
+        (Byte::Double([0xBB, 0xA2]), "Δ"),
+        (Byte::Double([0xBB, 0xA3]), "δ"),
+        (Byte::Double([0xBB, 0xA4]), "ε"),
+        (Byte::Double([0xBB, 0xA5]), "λ"),
+        (Byte::Double([0xBB, 0xA6]), "μ"),
+        (Byte::Double([0xBB, 0xA7]), "[pi]"),
+        (Byte::Double([0xBB, 0xA8]), "ρ"),
+        (Byte::Double([0xBB, 0xA9]), "Σ"),
+        (Byte::Double([0xBB, 0xAB]), "φ"),
+        (Byte::Double([0xBB, 0xAC]), "Ω"),
+        (Byte::Double([0xBB, 0xAD]), "p̂"),
+        (Byte::Double([0xBB, 0xAE]), "χ"),
+        (Byte::Double([0xBB, 0xAF]), "𝐅"),
         (Byte::Double([0xBB, 0xB0]), "a"),
         (Byte::Double([0xBB, 0xB1]), "b"),
         (Byte::Double([0xBB, 0xB2]), "c"),
@@ -480,55 +782,46 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0xBB, 0xC8]), "x"),
         (Byte::Double([0xBB, 0xC9]), "y"),
         (Byte::Double([0xBB, 0xCA]), "z"),
-        (Byte::Double([0xBB, 0x52]), "ClrAllLists"),
-        (Byte::Double([0xBB, 0x4F]), "a+bi"),
-        (Byte::Double([0xBB, 0x4D]), "Real"),
-        (Byte::Double([0xBB, 0x67]), "DiagnosticOff"),
-        (Byte::Double([0xBB, 0x51]), "ExprOff"),
-        (Byte::Double([0xBB, 0x66]), "DiagnosticOn"),
-        (Byte::Double([0xBB, 0x50]), "ExprOn"),
-        (Byte::Double([0xBB, 0x4A]), "SetUpEditor "),
-        (Byte::Double([0xBB, 0x68]), "Archive "),
-        (Byte::Double([0xBB, 0x54]), "DelVar "),
-        // end of xBB
-        (Byte::Double([0xEF, 0x67]), "TextColor("),
-        (Byte::Double([0xEF, 0x5B]), "BackgroundOn "),
-        (Byte::Double([0xEF, 0x41]), "BLUE"),
-        (Byte::Double([0xEF, 0x42]), "RED"),
-        (Byte::Double([0xEF, 0x43]), "BLACK"),
-        (Byte::Double([0xEF, 0x44]), "MAGENTA"),
-        (Byte::Double([0xEF, 0x45]), "GREEN"),
-        (Byte::Double([0xEF, 0x46]), "ORANGE"),
-        (Byte::Double([0xEF, 0x47]), "BROWN"),
-        (Byte::Double([0xEF, 0x48]), "NAVY"),
-        (Byte::Double([0xEF, 0x49]), "LTBLUE"),
-        (Byte::Double([0xEF, 0x4A]), "YELLOW"),
-        (Byte::Double([0xEF, 0x4B]), "WHITE"),
-        (Byte::Double([0xEF, 0x4C]), "LTGRAY"),
-        (Byte::Double([0xEF, 0x4D]), "MEDGRAY"),
-        (Byte::Double([0xEF, 0x4E]), "GRAY"),
-        (Byte::Double([0xEF, 0x4F]), "DRAKGRAY"),
-        (Byte::Double([0xEF, 0x09]), "getDate"),
-        (Byte::Double([0xEF, 0x0A]), "getTime"),
-        (Byte::Double([0xEF, 0x06]), "dayOfWk("),
-        (Byte::Double([0xEF, 0x64]), "BackgroundOff"),
-        (Byte::Double([0xEF, 0x32]), "remainder("),
-        (Byte::Double([0xEF, 0x6C]), "BorderColor "),
-        (Byte::Double([0xEF, 0x37]), "MATHPRINT"),
-        (Byte::Double([0xEF, 0x3B]), "AUTO"),
-        (Byte::Double([0xEF, 0x39]), "[n/d]"),
-        (Byte::Double([0xEF, 0x10]), "ClockOn"),
-        (Byte::Double([0xEF, 0x0F]), "ClockOff"),
-        (Byte::Double([0xEF, 0x96]), "Wait "),
-        (Byte::Double([0xEF, 0x38]), "CLASSIC"),
-        (Byte::Double([0xEF, 0x75]), "Dot-Thin"),
-        (Byte::Double([0xEF, 0x3A]), "[Un/d]"),
-        (Byte::Double([0xEF, 0x40]), "STARTWIZARD OFF"),
-        (Byte::Double([0xEF, 0x6B]), "DetectAsymOff"),
-        (Byte::Double([0xEF, 0x6A]), "DetectAsymOn"),
-        (Byte::Double([0xEF, 0x3F]), "STARTWIZARD ON"),
-        // 73 ** (Graph Options)
-        (Byte::Double([0x7E, 0x06]), "Thick"),
+        (Byte::Double([0xBB, 0xCB]), "σ"),
+        (Byte::Double([0xBB, 0xCC]), "τ"),
+        (Byte::Double([0xBB, 0xCE]), "GarbageCollect"),
+        (Byte::Double([0xBB, 0xCF]), "~"),
+        (Byte::Double([0xBB, 0xD1]), "@"),
+        (Byte::Double([0xBB, 0xD2]), "#"),
+        (Byte::Double([0xBB, 0xD3]), "$"),
+        (Byte::Double([0xBB, 0xD4]), "&"),
+        (Byte::Double([0xBB, 0xD5]), "‘"),
+        (Byte::Double([0xBB, 0xD6]), ";"),
+        (Byte::Double([0xBB, 0xD7]), "\\"),
+        (Byte::Double([0xBB, 0xD8]), "|"),
+        (Byte::Double([0xBB, 0xD9]), "_"),
+        (Byte::Double([0xBB, 0xDA]), "%"),
+        (Byte::Double([0xBB, 0xDB]), "…"),
+        (Byte::Double([0xBB, 0xDC]), "∠"),
+        (Byte::Double([0xBB, 0xDD]), "ß"),
+        (Byte::Double([0xBB, 0xDE]), "ˣ"),
+        (Byte::Double([0xBB, 0xDF]), "ᴛ"),
+        (Byte::Double([0xBB, 0xE0]), "₀"),
+        (Byte::Double([0xBB, 0xE1]), "₁"),
+        (Byte::Double([0xBB, 0xE2]), "₂"),
+        (Byte::Double([0xBB, 0xE3]), "₃"),
+        (Byte::Double([0xBB, 0xE4]), "₄"),
+        (Byte::Double([0xBB, 0xE5]), "₅"),
+        (Byte::Double([0xBB, 0xE6]), "₆"),
+        (Byte::Double([0xBB, 0xE7]), "₇"),
+        (Byte::Double([0xBB, 0xE8]), "₈"),
+        (Byte::Double([0xBB, 0xE9]), "₉"),
+        (Byte::Double([0xBB, 0xEA]), "₁₀"),
+        (Byte::Double([0xBB, 0xEB]), "◄"),
+        (Byte::Double([0xBB, 0xEC]), "►"),
+        (Byte::Double([0xBB, 0xED]), "↑"),
+        (Byte::Double([0xBB, 0xEE]), "↓"),
+        (Byte::Double([0xBB, 0xF0]), "×"),
+        (Byte::Double([0xBB, 0xF1]), "∫"),
+        (Byte::Double([0xBB, 0xF2]), "🡅"),
+        (Byte::Double([0xBB, 0xF3]), "🡇"),
+        (Byte::Double([0xBB, 0xF4]), "√"),
+        // 7E tokens (graph format)
         (Byte::Double([0x7E, 0x00]), "Sequential"),
         (Byte::Double([0x7E, 0x01]), "Simul"),
         (Byte::Double([0x7E, 0x02]), "PolarGC"),
@@ -539,6 +832,7 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         // on my TI-84+CE this is Thick, but on older models i think
         // its Connected
         // (Byte::Double([0x7E, 0x06]), "Connected"),
+        (Byte::Double([0x7E, 0x06]), "Thick"),
         (Byte::Double([0x7E, 0x07]), "Dot"),
         (Byte::Double([0x7E, 0x08]), "AxesOn"),
         (Byte::Double([0x7E, 0x09]), "AxesOff"),
@@ -551,9 +845,142 @@ pub static BYTE_TOKENS: LazyLock<HashMap<Byte, &'static str>> = LazyLock::new(||
         (Byte::Double([0x7E, 0x10]), "uvAxes"),
         (Byte::Double([0x7E, 0x11]), "vwAxes"),
         (Byte::Double([0x7E, 0x12]), "uwAxes"),
+        // EF tokens (TI-84 Plus, TI-84 Plus C SE and TI-84 Plus CE), from the TI-Toolkit token
+        // sheet. The sheet has no tokens at EF1F-EF2D, EF36, EF3E, EF5C-EF63, EF6D-EF72,
+        // EF76-EF78, EF7C-EF80, EF99-EF9D or from EFA7, so they're left out. EF69 and EF7B aren't
+        // in the sheet either, but they're kept as the first bytes of squished assembly programs.
+        (Byte::Double([0xEF, 0x00]), "setDate("),
+        (Byte::Double([0xEF, 0x01]), "setTime("),
+        (Byte::Double([0xEF, 0x02]), "checkTmr("),
+        (Byte::Double([0xEF, 0x03]), "setDtFmt("),
+        (Byte::Double([0xEF, 0x04]), "setTmFmt("),
+        (Byte::Double([0xEF, 0x05]), "timeCnv("),
+        (Byte::Double([0xEF, 0x06]), "dayOfWk("),
+        (Byte::Double([0xEF, 0x07]), "getDtStr("),
+        (Byte::Double([0xEF, 0x08]), "getTmStr("),
+        (Byte::Double([0xEF, 0x09]), "getDate"),
+        (Byte::Double([0xEF, 0x0A]), "getTime"),
+        (Byte::Double([0xEF, 0x0B]), "startTmr"),
+        (Byte::Double([0xEF, 0x0C]), "getDtFmt"),
+        (Byte::Double([0xEF, 0x0D]), "getTmFmt"),
+        (Byte::Double([0xEF, 0x0E]), "isClockOn"),
+        (Byte::Double([0xEF, 0x0F]), "ClockOff"),
+        (Byte::Double([0xEF, 0x10]), "ClockOn"),
+        (Byte::Double([0xEF, 0x11]), "OpenLib("),
+        (Byte::Double([0xEF, 0x12]), "ExecLib"),
+        (Byte::Double([0xEF, 0x13]), "invT("),
+        (Byte::Double([0xEF, 0x14]), "[chi]2GOF-Test("),
+        (Byte::Double([0xEF, 0x15]), "LinRegTInt "),
+        (Byte::Double([0xEF, 0x16]), "Manual-Fit "),
+        (Byte::Double([0xEF, 0x17]), "ZQuadrant1"),
+        (Byte::Double([0xEF, 0x18]), "ZFrac1/2"),
+        (Byte::Double([0xEF, 0x19]), "ZFrac1/3"),
+        (Byte::Double([0xEF, 0x1A]), "ZFrac1/4"),
+        (Byte::Double([0xEF, 0x1B]), "ZFrac1/5"),
+        (Byte::Double([0xEF, 0x1C]), "ZFrac1/8"),
+        (Byte::Double([0xEF, 0x1D]), "ZFrac1/10"),
+        (Byte::Double([0xEF, 0x1E]), "mathprintbox"),
+        (Byte::Double([0xEF, 0x2E]), "⁄"),
+        (Byte::Double([0xEF, 0x2F]), "ᵁ"),
+        (Byte::Double([0xEF, 0x30]), ">n/d<>Un/d"),
+        (Byte::Double([0xEF, 0x31]), ">F<>D"),
+        (Byte::Double([0xEF, 0x32]), "remainder("),
+        (Byte::Double([0xEF, 0x33]), "Σ("),
+        (Byte::Double([0xEF, 0x34]), "logBASE("),
+        (Byte::Double([0xEF, 0x35]), "randIntNoRep("),
+        (Byte::Double([0xEF, 0x37]), "MATHPRINT"),
+        (Byte::Double([0xEF, 0x38]), "CLASSIC"),
+        (Byte::Double([0xEF, 0x39]), "[n/d]"),
+        (Byte::Double([0xEF, 0x3A]), "[Un/d]"),
+        (Byte::Double([0xEF, 0x3B]), "AUTO"),
+        (Byte::Double([0xEF, 0x3C]), "DEC"),
+        (Byte::Double([0xEF, 0x3D]), "FRAC"),
+        (Byte::Double([0xEF, 0x3F]), "STATWIZARD ON"),
+        (Byte::Double([0xEF, 0x40]), "STATWIZARD OFF"),
+        (Byte::Double([0xEF, 0x41]), "BLUE"),
+        (Byte::Double([0xEF, 0x42]), "RED"),
+        (Byte::Double([0xEF, 0x43]), "BLACK"),
+        (Byte::Double([0xEF, 0x44]), "MAGENTA"),
+        (Byte::Double([0xEF, 0x45]), "GREEN"),
+        (Byte::Double([0xEF, 0x46]), "ORANGE"),
+        (Byte::Double([0xEF, 0x47]), "BROWN"),
+        (Byte::Double([0xEF, 0x48]), "NAVY"),
+        (Byte::Double([0xEF, 0x49]), "LTBLUE"),
+        (Byte::Double([0xEF, 0x4A]), "YELLOW"),
+        (Byte::Double([0xEF, 0x4B]), "WHITE"),
+        (Byte::Double([0xEF, 0x4C]), "LTGRAY"),
+        (Byte::Double([0xEF, 0x4D]), "MEDGRAY"),
+        (Byte::Double([0xEF, 0x4E]), "GRAY"),
+        (Byte::Double([0xEF, 0x4F]), "DARKGRAY"),
+        (Byte::Double([0xEF, 0x50]), "Image1"),
+        (Byte::Double([0xEF, 0x51]), "Image2"),
+        (Byte::Double([0xEF, 0x52]), "Image3"),
+        (Byte::Double([0xEF, 0x53]), "Image4"),
+        (Byte::Double([0xEF, 0x54]), "Image5"),
+        (Byte::Double([0xEF, 0x55]), "Image6"),
+        (Byte::Double([0xEF, 0x56]), "Image7"),
+        (Byte::Double([0xEF, 0x57]), "Image8"),
+        (Byte::Double([0xEF, 0x58]), "Image9"),
+        (Byte::Double([0xEF, 0x59]), "Image0"),
+        (Byte::Double([0xEF, 0x5A]), "GridLine "),
+        (Byte::Double([0xEF, 0x5B]), "BackgroundOn "),
+        (Byte::Double([0xEF, 0x64]), "BackgroundOff"),
+        (Byte::Double([0xEF, 0x65]), "GraphColor("),
+        (Byte::Double([0xEF, 0x66]), "QuickPlot&Fit-EQ"),
+        (Byte::Double([0xEF, 0x67]), "TextColor("),
+        (Byte::Double([0xEF, 0x68]), "Asm84CPrgm"),
+        (Byte::Double([0xEF, 0x69]), "Asm84CCmp"),
+        (Byte::Double([0xEF, 0x6A]), "DetectAsymOn"),
+        (Byte::Double([0xEF, 0x6B]), "DetectAsymOff"),
+        (Byte::Double([0xEF, 0x6C]), "BorderColor "),
+        (Byte::Double([0xEF, 0x73]), "plottinydot"),
+        (Byte::Double([0xEF, 0x74]), "Thin"),
+        (Byte::Double([0xEF, 0x75]), "Dot-Thin"),
+        (Byte::Double([0xEF, 0x79]), "PlySmlt2"),
+        (Byte::Double([0xEF, 0x7A]), "Asm84CEPrgm"),
+        (Byte::Double([0xEF, 0x7B]), "Asm84CECmp"),
+        (Byte::Double([0xEF, 0x81]), "Quartiles Setting..."),
+        (Byte::Double([0xEF, 0x82]), "u(n-2)"),
+        (Byte::Double([0xEF, 0x83]), "v(n-2)"),
+        (Byte::Double([0xEF, 0x84]), "w(n-2)"),
+        (Byte::Double([0xEF, 0x85]), "u(n-1)"),
+        (Byte::Double([0xEF, 0x86]), "v(n-1)"),
+        (Byte::Double([0xEF, 0x87]), "w(n-1)"),
+        (Byte::Double([0xEF, 0x88]), "u(n)"),
+        (Byte::Double([0xEF, 0x89]), "v(n)"),
+        (Byte::Double([0xEF, 0x8A]), "w(n)"),
+        (Byte::Double([0xEF, 0x8B]), "u(n+1)"),
+        (Byte::Double([0xEF, 0x8C]), "v(n+1)"),
+        (Byte::Double([0xEF, 0x8D]), "w(n+1)"),
+        (Byte::Double([0xEF, 0x8E]), "pieceWise("),
+        (Byte::Double([0xEF, 0x8F]), "SEQ(n)"),
+        (Byte::Double([0xEF, 0x90]), "SEQ(n+1)"),
+        (Byte::Double([0xEF, 0x91]), "SEQ(n+2)"),
+        (Byte::Double([0xEF, 0x92]), "LEFT"),
+        (Byte::Double([0xEF, 0x93]), "CENTER"),
+        (Byte::Double([0xEF, 0x94]), "RIGHT"),
+        (Byte::Double([0xEF, 0x95]), "invBinom("),
+        (Byte::Double([0xEF, 0x96]), "Wait "),
+        (Byte::Double([0xEF, 0x97]), "toString("),
+        (Byte::Double([0xEF, 0x98]), "eval("),
+        (Byte::Double([0xEF, 0x9E]), "Execute Program"),
+        (Byte::Double([0xEF, 0x9F]), "Undo Clear"),
+        (Byte::Double([0xEF, 0xA0]), "Insert Line Above"),
+        (Byte::Double([0xEF, 0xA1]), "Cut Line"),
+        (Byte::Double([0xEF, 0xA2]), "Copy Line"),
+        (Byte::Double([0xEF, 0xA3]), "Paste Line Below"),
+        (Byte::Double([0xEF, 0xA4]), "Insert Comment Above"),
+        (Byte::Double([0xEF, 0xA5]), "Quit Editor"),
+        (Byte::Double([0xEF, 0xA6]), "piecewise("),
     ]
     .iter()
     .cloned()
     .collect();
+
+    // tokens from an installed sheet add to and override the built-in tokens
+    for token in sheets::installed() {
+        m.insert(token.byte, token.name.as_str());
+    }
+
     m
 });
@@ -0,0 +1,150 @@
+//! The `trie` module provides [`TokenTrie`], a prefix tree over the names and aliases of the
+//! tokens in [`BYTE_TOKENS`] that is used to find the longest token at the start of some text in a
+//! single pass.
+//!
+//! The trie is keyed by [`char`], so matches always end on a character boundary, even when the
+//! token names contain multibyte characters such as `θ` or `°`.
+
+use std::{collections::HashMap, sync::LazyLock};
+
+use super::{
+    metadata::TOKENS,
+    sheets,
+    tokens::{Byte, BYTE_TOKENS},
+};
+
+/// A prefix tree mapping token names to their [`Byte`]s.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{tokens::Byte, trie::TokenTrie};
+///
+/// let mut trie = TokenTrie::default();
+/// trie.insert("Disp ", Byte::Single(0xDE));
+/// trie.insert("D", Byte::Single(0x44));
+///
+/// assert_eq!(trie.longest_match("Disp X"), Some((Byte::Single(0xDE), 5)));
+/// assert_eq!(trie.longest_match("Dis"), Some((Byte::Single(0x44), 1)));
+/// assert_eq!(trie.longest_match("X"), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TokenTrie {
+    children: HashMap<char, TokenTrie>,
+    token: Option<Byte>,
+}
+
+/// A [`TokenTrie`] containing every token in [`BYTE_TOKENS`], along with the display forms and
+/// aliases from [`TOKENS`], such as `→` and `theta`, and the aliases from an installed
+/// [sheet](sheets::install).
+///
+/// When several tokens share a name, the one with the lowest bytes is used, so that the result
+/// doesn't depend on the iteration order of [`BYTE_TOKENS`]. Names always take priority over
+/// aliases, so an alias can never hide another token.
+///
+/// # Example
+///
+/// ```
+/// use tio2::translation::{tokens::Byte, trie::TOKEN_TRIE};
+///
+/// assert_eq!(TOKEN_TRIE.longest_match("→A"), Some((Byte::Single(0x04), "→".len())));
+/// assert_eq!(TOKEN_TRIE.longest_match("R>Pθ(1,2)"), Some((Byte::Single(0x1C), "R>Pθ(".len())));
+/// assert_eq!(TOKEN_TRIE.get("theta"), Some(Byte::Single(0x5B)));
+/// // the display form of subtraction is the name of negation
+/// assert_eq!(TOKEN_TRIE.get("-"), Some(Byte::Single(0xB0)));
+/// ```
+pub static TOKEN_TRIE: LazyLock<TokenTrie> = LazyLock::new(|| {
+    let mut tokens: Vec<(&Byte, &&'static str)> = BYTE_TOKENS.iter().collect();
+    tokens.sort_by_key(|(byte, _)| byte.to_bytes());
+
+    let mut trie = TokenTrie::default();
+    for (byte, name) in &tokens {
+        if trie.get(name).is_none() {
+            trie.insert(name, **byte);
+        }
+    }
+    for (byte, _) in &tokens {
+        for alias in TOKENS.get(byte).map_or(&[][..], |token| &token.aliases) {
+            if trie.get(alias).is_none() {
+                trie.insert(alias, **byte);
+            }
+        }
+    }
+    for token in sheets::installed() {
+        for alias in &token.aliases {
+            if trie.get(alias).is_none() {
+                trie.insert(alias, token.byte);
+            }
+        }
+    }
+
+    trie
+});
+
+impl TokenTrie {
+    /// Adds a token to the trie, replacing any token that already has the same name.
+    pub fn insert(&mut self, name: &str, byte: Byte) {
+        let mut node = self;
+        for c in name.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        node.token = Some(byte);
+    }
+
+    /// Returns the token with exactly the given name, if there is one.
+    pub fn get(&self, name: &str) -> Option<Byte> {
+        let mut node = self;
+        for c in name.chars() {
+            node = node.children.get(&c)?;
+        }
+        node.token
+    }
+
+    /// Finds every token at the start of `text`, shortest first.
+    ///
+    /// # Returns
+    ///
+    /// The [`Byte`] of each token and the length of its name in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tio2::translation::{tokens::Byte, trie::TokenTrie};
+    ///
+    /// let mut trie = TokenTrie::default();
+    /// trie.insert("Disp ", Byte::Single(0xDE));
+    /// trie.insert("D", Byte::Single(0x44));
+    ///
+    /// assert_eq!(
+    ///     trie.prefixes("Disp X"),
+    ///     vec![(Byte::Single(0x44), 1), (Byte::Single(0xDE), 5)]
+    /// );
+    /// ```
+    pub fn prefixes(&self, text: &str) -> Vec<(Byte, usize)> {
+        let mut node = self;
+        let mut prefixes = Vec::new();
+
+        for (index, c) in text.char_indices() {
+            node = match node.children.get(&c) {
+                Some(child) => child,
+                None => break,
+            };
+
+            if let Some(byte) = node.token {
+                prefixes.push((byte, index + c.len_utf8()));
+            }
+        }
+
+        prefixes
+    }
+
+    /// Finds the longest token at the start of `text`.
+    ///
+    /// # Returns
+    ///
+    /// The [`Byte`] of the token and the length of its name in bytes, which is always on a
+    /// character boundary of `text`, or [`None`] if no token matches.
+    pub fn longest_match(&self, text: &str) -> Option<(Byte, usize)> {
+        self.prefixes(text).pop()
+    }
+}
@@ -1,11 +1,13 @@
 //! This module contains various utility functions.
 
 use std::{
-    fs::File,
-    io::{BufRead, BufReader, Read},
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Read},
     path::Path,
 };
 
+use anyhow::Context;
+
 /// Alphanumeric tokens in TI-BASIC. Includes A-Z, 0-9, and theta.
 pub const ALPHANUMERIC_RANGE: [u8; 37] = [
     0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46,
@@ -67,6 +69,93 @@ pub fn read_file_lines(filename: impl AsRef<Path>) -> Result<Vec<String>, anyhow
         .collect())
 }
 
+/// Opens a file for writing, refusing to replace an existing file unless `overwrite` is `true`.
+///
+/// # Arguments
+///
+/// * `filename` - The path of the file to create.
+/// * `overwrite` - Whether an existing file should be truncated and replaced.
+///
+/// # Returns
+///
+/// A `Result` containing the opened [`File`], or an `anyhow::Error` if the file already exists
+/// and `overwrite` is `false`, or if the file can't be created.
+///
+/// # Example
+///
+/// ```
+/// use tio2::utils::create_output_file;
+///
+/// let path = std::env::temp_dir().join("tio2_create_output_file_doctest.txt");
+/// let _ = std::fs::remove_file(&path);
+///
+/// assert!(create_output_file(&path, false).is_ok());
+/// assert!(create_output_file(&path, false).is_err());
+/// assert!(create_output_file(&path, true).is_ok());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn create_output_file(
+    filename: impl AsRef<Path>,
+    overwrite: bool,
+) -> Result<File, anyhow::Error> {
+    let filename = filename.as_ref();
+
+    let result = if overwrite {
+        File::create(filename)
+    } else {
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(filename)
+    };
+
+    match result {
+        Ok(f) => Ok(f),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Err(anyhow::anyhow!(
+            "{} already exists, refusing to overwrite it",
+            filename.display()
+        )),
+        Err(e) => Err(e).with_context(|| format!("Could not create {}", filename.display())),
+    }
+}
+
+/// Turns the name of a variable into a name that can safely be used for a file.
+///
+/// Names come from the files being read, so they can't be trusted. Only `A`-`Z`, `0`-`9` and `θ`
+/// are kept, and every other character is escaped as `_` followed by the hexadecimal values of
+/// its UTF-8 bytes, so that no name can contain a path separator or be `..`. An empty name
+/// becomes `_`.
+///
+/// # Example
+///
+/// ```
+/// use tio2::utils::file_stem;
+///
+/// assert_eq!(file_stem("GAMEθ2"), "GAMEθ2");
+/// assert_eq!(file_stem("../PWN"), "_2E_2E_2FPWN");
+/// assert_eq!(file_stem("/etc"), "_2F_65_74_63");
+/// assert_eq!(file_stem(""), "_");
+/// ```
+pub fn file_stem(name: &str) -> String {
+    if name.is_empty() {
+        return "_".to_string();
+    }
+
+    let mut stem = String::new();
+    for c in name.chars() {
+        if c.is_ascii_uppercase() || c.is_ascii_digit() || c == 'θ' {
+            stem.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                stem.push_str(&format!("_{:02X}", byte));
+            }
+        }
+    }
+
+    stem
+}
+
 /// Checks if the provided binary data is valid UTF-8 encoded text.
 ///
 /// This function checks if the input bytes are valid UTF-8 encoded text by attempting
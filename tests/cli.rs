@@ -0,0 +1,107 @@
+//! Tests that the command line refuses options that don't apply to the chosen action, instead of
+//! silently ignoring them.
+
+use std::process::Command;
+
+/// Runs `tio2` with the given arguments and returns its exit code.
+fn exit_code(args: &[&str]) -> Option<i32> {
+    Command::new(env!("CARGO_BIN_EXE_tio2"))
+        .args(args)
+        .output()
+        .expect("tio2 should run")
+        .status
+        .code()
+}
+
+#[test]
+fn options_for_other_actions_are_refused() {
+    let refused: &[&[&str]] = &[
+        &["-d", "p.8xp", "--locked"],
+        &["-d", "p.8xp", "--comment", "x"],
+        &["-d", "p.8xp", "--archived"],
+        &["-d", "p.8xp", "--version-byte", "1"],
+        &["-d", "p.8xp", "--name", "HI"],
+        &["-d", "p.8xp", "--squish"],
+        &["-d", "p.8xp", "--keys", "keys.txt"],
+        &["-d", "p.8xp", "--screenshot", "a.png"],
+        &["-d", "p.8xp", "--seed", "1"],
+        &["-r", "p.8xp", "--archived"],
+        &["-r", "p.8xp", "--unicode"],
+        &["-r", "p.8xp", "--ascii"],
+        &["-r", "p.8xp", "--markup"],
+        &["-r", "p.8xp", "--asm"],
+        &["-r", "p.8xp", "--ambiguities"],
+        &["-r", "p.8xp", "--string-tokens"],
+        &["-r", "p.8xp", "--language", "fr"],
+        &["-c", "p.txt", "-n", "HI", "--asm"],
+        &["-c", "p.txt", "-n", "HI", "--markup"],
+        &["-c", "p.txt", "-n", "HI", "--unicode"],
+        &["-c", "p.txt", "-n", "HI", "--lenient"],
+        &["-c", "p.txt", "-n", "HI", "--seed", "1"],
+        &["--export-tokens", "json", "--lenient"],
+        &["-d", "p.8xp", "--unicode", "--ascii"],
+    ];
+
+    for args in refused {
+        // clap exits with 2 for usage errors
+        assert_eq!(
+            exit_code(args),
+            Some(2),
+            "tio2 {} was accepted",
+            args.join(" ")
+        );
+    }
+}
+
+#[test]
+fn options_for_the_chosen_action_are_accepted() {
+    let accepted: &[&[&str]] = &[
+        &[
+            "-d",
+            "missing.8xp",
+            "--unicode",
+            "--markup",
+            "--asm",
+            "--lenient",
+        ],
+        &["-d", "missing.8xp", "--string-tokens", "--language", "fr"],
+        &[
+            "-r",
+            "missing.8xp",
+            "--lenient",
+            "--seed",
+            "1",
+            "--screenshot",
+            "a.png",
+        ],
+        &[
+            "-c",
+            "missing.txt",
+            "-n",
+            "HI",
+            "--locked",
+            "--archived",
+            "--ambiguities",
+        ],
+        &[
+            "-c",
+            "missing.txt",
+            "-n",
+            "HI",
+            "--comment",
+            "x",
+            "--version-byte",
+            "1",
+        ],
+    ];
+
+    for args in accepted {
+        // the input files don't exist, which is only noticed once the options are accepted
+        assert_eq!(
+            exit_code(args),
+            Some(1),
+            "tio2 {} was refused",
+            args.join(" ")
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Property tests checking that decompiling a TI-8XP file and compiling the source code again
+//! gives back exactly the same file.
+
+use proptest::prelude::*;
+use tio2::translation::{
+    common::TIFile,
+    compile::{compile_to_bytecode_with, create_file, CompileOptions, TokenizeOptions},
+    decompile::{decompile_with_options, read_binary_data, DecompileOptions, Spelling},
+    languages::Language,
+};
+
+/// Tokens that are the most likely to collide with each other when spelled: strings, new lines,
+/// stores, the two minus signs, `ᴇ` and the first bytes of two-byte tokens.
+const COLLIDING: [u8; 11] = [
+    0x2A, 0x3F, 0x04, 0x3B, 0x71, 0xB0, 0x45, 0x30, 0x3A, 0xBB, 0xEF,
+];
+
+/// The tokens of a program, which don't have to be valid.
+fn tokens() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(
+        prop_oneof![1 => prop::sample::select(&COLLIDING[..]), 3 => any::<u8>()],
+        0..48,
+    )
+}
+
+/// The options that a program is compiled with, apart from the comment, which is per file.
+fn compile_options() -> impl Strategy<Value = CompileOptions> {
+    (
+        "[A-Z][A-Z0-9]{0,7}",
+        any::<bool>(),
+        any::<bool>(),
+        any::<u8>(),
+    )
+        .prop_map(|(name, locked, archived, version)| CompileOptions {
+            locked,
+            archived,
+            version,
+            ..CompileOptions::new(name)
+        })
+}
+
+/// A file containing one or more programs.
+fn file() -> impl Strategy<Value = (TIFile, Vec<CompileOptions>)> {
+    (
+        prop::collection::vec((tokens(), compile_options()), 1..4),
+        "[ -~]{0,42}",
+    )
+        .prop_map(|(programs, comment)| {
+            let entries = programs
+                .iter()
+                .flat_map(|(tokens, options)| create_file(tokens, options).unwrap().entries)
+                .collect();
+            let mut file = TIFile::new(entries);
+            file.header.set_comment(&comment).unwrap();
+
+            (
+                file,
+                programs.into_iter().map(|(_, options)| options).collect(),
+            )
+        })
+}
+
+/// Every way of spelling the tokens that can be compiled again.
+fn decompile_options() -> impl Strategy<Value = DecompileOptions> {
+    let spelling = prop_oneof![
+        Just(Spelling::Names),
+        Just(Spelling::Unicode),
+        Just(Spelling::Ascii)
+    ];
+    let language = prop_oneof![
+        Just(Language::English),
+        Just(Language::French),
+        Just(Language::German),
+        Just(Language::Spanish)
+    ];
+
+    (spelling, any::<bool>(), any::<bool>(), language).prop_map(
+        |(spelling, markup, tokens_in_strings, language)| DecompileOptions {
+            spelling,
+            markup,
+            tokens_in_strings,
+            language,
+            ..DecompileOptions::default()
+        },
+    )
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(500))]
+
+    #[test]
+    fn files_are_read_back_unchanged((file, _) in file()) {
+        let bytes = file.to_bytes();
+        let read = read_binary_data(bytes.clone()).unwrap();
+
+        prop_assert_eq!(&read, &file);
+        prop_assert_eq!(read.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn decompiled_files_compile_to_the_same_bytes(
+        (file, compile_options) in file(),
+        options in decompile_options(),
+    ) {
+        let bytes = file.to_bytes();
+        let programs = decompile_with_options(bytes.clone(), &options).unwrap();
+        prop_assert_eq!(programs.len(), compile_options.len());
+
+        let tokenize = TokenizeOptions {
+            tokens_in_strings: options.tokens_in_strings,
+            language: options.language,
+        };
+        let mut entries = Vec::new();
+        for (program, compile_options) in programs.iter().zip(&compile_options) {
+            prop_assert_eq!(&program.name, &compile_options.name);
+
+            let lines = program.lines.iter().map(String::as_str).collect();
+            let tokens = compile_to_bytecode_with(lines, &tokenize).unwrap();
+            entries.extend(create_file(&tokens, compile_options).unwrap().entries);
+        }
+        let mut compiled = TIFile::new(entries);
+        compiled.header.set_comment(&file.header.comment()).unwrap();
+
+        prop_assert_eq!(compiled.to_bytes(), bytes);
+    }
+}
@@ -0,0 +1,76 @@
+//! Tests that every token in the token table can be decompiled and compiled again.
+
+use tio2::translation::{
+    compile::{compile_to_bytecode_with, TokenizeOptions},
+    decompile::{decompile_tokens_with, DecompileOptions, Spelling},
+    tokens::{Byte, BYTE_TOKENS},
+};
+
+#[test]
+fn every_token_round_trips() {
+    let mut failures = Vec::new();
+
+    for (byte, name) in BYTE_TOKENS.iter() {
+        let bytes = byte.to_bytes();
+
+        for spelling in [Spelling::Names, Spelling::Unicode, Spelling::Ascii] {
+            let options = DecompileOptions {
+                spelling,
+                ..DecompileOptions::default()
+            };
+            let lines = decompile_tokens_with(&bytes, &options);
+            let compiled = compile_to_bytecode_with(
+                lines.iter().map(String::as_str).collect(),
+                &TokenizeOptions::default(),
+            );
+
+            // a token on its own should be written with its name rather than an escape, unless
+            // the name can't be written in ASCII
+            let escaped = spelling != Spelling::Ascii && lines.concat().contains("\\x{");
+            if compiled.ok().as_ref() != Some(&bytes) || escaped {
+                failures.push(format!(
+                    "{} ({}) with {:?}: {:?}",
+                    byte.to_hex(),
+                    name,
+                    spelling,
+                    lines
+                ));
+            }
+        }
+    }
+
+    failures.sort();
+    assert!(
+        failures.is_empty(),
+        "{} tokens don't round-trip:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn every_ef_token_in_the_sheet_is_known() {
+    // the second bytes of the EF tokens in the TI-Toolkit token sheet, up to OS 5.3
+    let sheet = (0x00..=0x1E)
+        .chain(0x2E..=0x35)
+        .chain(0x37..=0x3D)
+        .chain(0x3F..=0x5B)
+        .chain(0x64..=0x68)
+        .chain(0x6A..=0x6C)
+        .chain(0x73..=0x75)
+        .chain(0x79..=0x7A)
+        .chain(0x81..=0x98)
+        .chain(0x9E..=0xA6);
+
+    let missing: Vec<String> = sheet
+        .map(|second| Byte::Double([0xEF, second]))
+        .filter(|byte| !BYTE_TOKENS.contains_key(byte))
+        .map(|byte| byte.to_hex())
+        .collect();
+
+    assert!(
+        missing.is_empty(),
+        "missing EF tokens: {}",
+        missing.join(", ")
+    );
+}